@@ -0,0 +1,60 @@
+use super::prelude::*;
+
+/// Average hash: each bit of an 8x8 grayscale thumbnail is set when the pixel
+/// is brighter than the mean.
+pub fn ahash(image: &DynamicImage) -> u64 {
+  let pixels = thumbnail(image, 8, 8);
+  let mean = pixels.iter().map(|&p| u32::from(p)).sum::<u32>() / 64;
+  pixels
+    .iter()
+    .enumerate()
+    .filter(|&(_, &p)| u32::from(p) > mean)
+    .fold(0, |hash, (i, _)| hash | 1 << i)
+}
+
+/// Difference hash: each bit of a 9x8 grayscale thumbnail is set when a
+/// pixel is brighter than its right-hand neighbour.
+pub fn dhash(image: &DynamicImage) -> u64 {
+  let pixels = thumbnail(image, 9, 8);
+  let mut hash = 0;
+  for row in 0..8 {
+    for col in 0..8 {
+      let (left, right) = (pixels[row * 9 + col], pixels[row * 9 + col + 1]);
+      if left > right {
+        hash |= 1 << (row * 8 + col);
+      }
+    }
+  }
+  hash
+}
+
+fn thumbnail(image: &DynamicImage, width: u32, height: u32) -> Vec<u8> {
+  let gray: GrayImage = image.to_luma8();
+  image::imageops::resize(&gray, width, height, FilterType::Triangle).into_raw()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use image::{Rgb, RgbImage};
+
+  #[test]
+  fn test_hashes_tell_images_apart() {
+    let left_lit =
+      DynamicImage::ImageRgb8(RgbImage::from_fn(64, 64, |x, _| {
+        if x < 32 { Rgb([255; 3]) } else { Rgb([0; 3]) }
+      }));
+    let top_lit = DynamicImage::ImageRgb8(RgbImage::from_fn(64, 64, |_, y| {
+      if y < 32 { Rgb([255; 3]) } else { Rgb([0; 3]) }
+    }));
+
+    assert_eq!(
+      ahash(&left_lit),
+      ahash(&left_lit.resize_exact(128, 96, FilterType::Nearest))
+    );
+    assert_eq!(ahash(&left_lit).count_ones(), 32);
+    assert_ne!(ahash(&left_lit), ahash(&top_lit));
+    assert_ne!(dhash(&left_lit), 0);
+    assert_eq!(dhash(&top_lit), 0);
+  }
+}
@@ -0,0 +1,173 @@
+//|-> Modules
+mod hash;
+mod palette;
+
+//|-> Internal Exports
+mod prelude {
+  pub use crate::prelude::*;
+  pub use image::{DynamicImage, GrayImage, imageops::FilterType};
+}
+
+//|-> External Exports
+use crate::{
+  data::ImageMeta,
+  provider::{offline, read_limited},
+  utils::proxy
+};
+use prelude::*;
+use std::{
+  collections::VecDeque,
+  sync::{Mutex, PoisonError}
+};
+
+pub use hash::{ahash, dhash};
+pub use palette::palette;
+
+//~@ Upstream images larger than this are refused
+const MAX_BYTES: usize = 10 * 1024 * 1024;
+
+//~@ Colours kept in the palette
+const PALETTE_SIZE: usize = 5;
+
+//~@ Analyses remembered by URL so a photo is downloaded and decoded once
+const RECENT: usize = 1_024;
+
+static ANALYZED: Mutex<VecDeque<(String, ImageMeta)>> =
+  Mutex::new(VecDeque::new());
+
+/// Downloads an image, refusing anything larger than [`MAX_BYTES`] or from
+/// a host the image proxy wouldn't fetch from.
+pub async fn download(url: &str) -> Result<Vec<u8>> {
+  //~@ URLs come from clients, so never reach internal hosts with them
+  if !proxy::is_allowed(url) {
+    return Err(Error::Url(format!("{url} is not on an allowed host")));
+  }
+  fetch(url).await
+}
+
+/// Downloads an image like [`download`] but from any host, for URLs the
+/// operator chose rather than a client.
+pub async fn fetch(url: &str) -> Result<Vec<u8>> {
+  //~@ A mounted offline bundle answers before the network is touched
+  if let Some(bytes) = offline::mounted().and_then(|bundle| bundle.image(url)) {
+    return Ok(bytes.to_vec());
+  }
+
+  debug!("Downloading image from: {url}");
+  let response = reqwest::get(url).await?.error_for_status()?;
+  read_limited(response, MAX_BYTES).await?.ok_or_else(|| {
+    Error::Image(format!("{url} is larger than {MAX_BYTES} bytes"))
+  })
+}
+
+/// Decodes `bytes` and extracts its [`ImageMeta`].
+pub fn analyze(bytes: &[u8]) -> Result<ImageMeta> {
+  let format = image::guess_format(bytes)?;
+  let image = image::load_from_memory_with_format(bytes, format)?;
+
+  Ok(ImageMeta {
+    width: image.width(),
+    height: image.height(),
+    format: format
+      .extensions_str()
+      .first()
+      .copied()
+      .unwrap_or("unknown")
+      .to_string(),
+    bytes: bytes.len() as u64,
+    palette: palette(&image, PALETTE_SIZE),
+    ahash: ahash(&image),
+    dhash: dhash(&image)
+  })
+}
+
+/// Downloads and analyzes `url`, decoding off the async runtime.
+///
+/// The last [`RECENT`] results are remembered, so a photo hashed while
+/// picking a dog isn't fetched again when it is saved.
+pub async fn analyze_url(url: &str) -> Result<ImageMeta> {
+  if let Some(meta) = recent(url) {
+    return Ok(meta);
+  }
+  let bytes = download(url).await?;
+  let meta = tokio::task::spawn_blocking(move || analyze(&bytes))
+    .await
+    .map_err(|e| Error::Image(e.to_string()))??;
+  remember(url, &meta);
+  Ok(meta)
+}
+
+// -- Private Helper --
+
+fn recent(url: &str) -> Option<ImageMeta> {
+  let analyzed = ANALYZED.lock().unwrap_or_else(PoisonError::into_inner);
+  analyzed
+    .iter()
+    .find(|(known, _)| known == url)
+    .map(|(_, meta)| meta.clone())
+}
+
+fn remember(url: &str, meta: &ImageMeta) {
+  let mut analyzed = ANALYZED.lock().unwrap_or_else(PoisonError::into_inner);
+  if analyzed.len() == RECENT {
+    analyzed.pop_front();
+  }
+  analyzed.push_back((url.to_string(), meta.clone()));
+}
+
+#[cfg(test)]
+pub mod testing {
+  use image::{ImageFormat, RgbImage};
+  use std::io::Cursor;
+
+  /// Encodes a synthetic gradient with a red block, `width` x `height`.
+  pub fn photo(width: u32, height: u32, format: ImageFormat) -> Vec<u8> {
+    let image = RgbImage::from_fn(width, height, |x, y| {
+      if x < width / 3 && y < height / 2 {
+        image::Rgb([220, 30, 30])
+      } else {
+        let shade = (255 * x / width) as u8;
+        image::Rgb([shade, shade, (255 * y / height) as u8])
+      }
+    });
+    let mut bytes = Cursor::new(Vec::new());
+    image.write_to(&mut bytes, format).unwrap();
+    bytes.into_inner()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use image::ImageFormat;
+
+  #[test]
+  fn test_analyze() {
+    let bytes = testing::photo(300, 200, ImageFormat::Png);
+    let meta = analyze(&bytes).unwrap();
+
+    assert_eq!((meta.width, meta.height), (300, 200));
+    assert_eq!(meta.format, "png");
+    assert_eq!(meta.bytes, bytes.len() as u64);
+    assert!(!meta.palette.is_empty() && meta.palette.len() <= PALETTE_SIZE);
+
+    //~@ Re-encoding and resizing keeps the hashes close
+    let resized =
+      analyze(&testing::photo(150, 100, ImageFormat::Jpeg)).unwrap();
+    assert_eq!(resized.format, "jpg");
+    assert!(meta.distance(&resized) <= 4);
+
+    assert!(analyze(b"not an image").is_err());
+  }
+
+  #[tokio::test]
+  async fn test_download_refuses_other_hosts() {
+    for url in [
+      "http://169.254.169.254/latest/meta-data/",
+      "http://localhost:8080/admin",
+      "file:///etc/passwd"
+    ] {
+      assert!(matches!(download(url).await, Err(Error::Url(_))), "{url}");
+    }
+  }
+}
@@ -0,0 +1,59 @@
+use super::prelude::*;
+use std::collections::HashMap;
+
+//~@ Side of the thumbnail colours are sampled from
+const SAMPLE: u32 = 64;
+
+//~@ Bits kept per channel when bucketing colours
+const BITS: u32 = 4;
+
+/// The `count` most common colours as `#rrggbb`, most common first.
+///
+/// Colours are bucketed at [`BITS`] bits per channel and each bucket is
+/// reported as the average of its pixels.
+pub fn palette(image: &DynamicImage, count: usize) -> Vec<String> {
+  let sample = image.resize(SAMPLE, SAMPLE, FilterType::Triangle).to_rgb8();
+
+  let mut buckets: HashMap<[u8; 3], ([u64; 3], u64)> = HashMap::new();
+  for pixel in sample.pixels() {
+    let key = pixel.0.map(|c| c >> (8 - BITS));
+    let (sum, n) = buckets.entry(key).or_default();
+    for (total, channel) in sum.iter_mut().zip(pixel.0) {
+      *total += u64::from(channel);
+    }
+    *n += 1;
+  }
+
+  let mut buckets: Vec<_> = buckets.into_iter().collect();
+  buckets.sort_by(|(ka, (_, a)), (kb, (_, b))| b.cmp(a).then(ka.cmp(kb)));
+  buckets
+    .into_iter()
+    .take(count)
+    .map(|(_, (sum, n))| {
+      let [r, g, b] = sum.map(|total| (total / n) as u8);
+      format!("#{r:02x}{g:02x}{b:02x}")
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use image::{Rgb, RgbImage};
+
+  #[test]
+  fn test_palette_orders_by_coverage() {
+    let image = DynamicImage::ImageRgb8(RgbImage::from_fn(100, 100, |x, _| {
+      if x < 75 {
+        Rgb([255, 0, 0])
+      } else {
+        Rgb([0, 0, 255])
+      }
+    }));
+
+    let colours = palette(&image, 5);
+    assert_eq!(colours[0], "#ff0000");
+    assert!(colours.contains(&"#0000ff".to_string()));
+    assert_eq!(palette(&image, 1).len(), 1);
+  }
+}
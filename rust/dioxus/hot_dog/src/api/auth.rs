@@ -0,0 +1,86 @@
+use super::prelude::*;
+use crate::data::User;
+
+#[cfg(feature = "server")]
+use crate::{
+  auth,
+  data::{
+    sync::Change,
+    user::{validate_password, validate_username}
+  },
+  server::sync::publish,
+  storage::{users, with_db},
+  utils::time
+};
+
+/// The visitor behind this session, starting an anonymous one if there is
+/// none yet.
+#[server]
+pub async fn whoami() -> Result<User, ServerFnError> {
+  if let Some(user) = auth::user()? {
+    return Ok(user);
+  }
+  let user = with_db(|db| users::create_anonymous(db, time::now()))?;
+  auth::start_session(user.id)?;
+  Ok(user)
+}
+
+/// Turns the current guest into an account, keeping what they saved.
+#[server]
+pub async fn register(
+  username: String,
+  password: String
+) -> Result<User, ServerFnError> {
+  let username = validate_username(&username)?;
+  validate_password(&password)?;
+  let hash = auth::hash_password(password).await?;
+
+  let guest = match auth::user()? {
+    Some(user) if user.is_anonymous() => user,
+    Some(_) =>
+      return Err(
+        Error::Auth("sign out before creating an account".into()).into()
+      ),
+    None => with_db(|db| users::create_anonymous(db, time::now()))?
+  };
+  let user = with_db(|db| users::register(db, guest.id, &username, &hash))?;
+  auth::start_session(user.id)?;
+  Ok(user)
+}
+
+/// Signs in to an existing account, bringing along anything saved as a guest.
+#[server]
+pub async fn login(
+  username: String,
+  password: String
+) -> Result<User, ServerFnError> {
+  let found = with_db(|db| users::credentials(db, username.trim()))?;
+  let verified = match &found {
+    Some((_, hash)) => auth::verify_password(password, hash.clone()).await?,
+    None => false
+  };
+  let user = match found {
+    Some((user, _)) if verified => user,
+    _ => return Err(Error::Auth("wrong username or password".into()).into())
+  };
+
+  if let Some(guest) = auth::user()?.filter(User::is_anonymous) {
+    with_db(|db| {
+      let tx = db.unchecked_transaction()?;
+      users::merge(&tx, guest.id, user.id)?;
+      tx.commit()?;
+      Ok(())
+    })?;
+    //~@ The account's other devices gain what the guest saved
+    publish(user.id, Change::Favorites);
+    publish(user.id, Change::Ratings);
+  }
+  auth::start_session(user.id)?;
+  Ok(user)
+}
+
+/// Ends the current session; the next page load starts a fresh guest.
+#[server]
+pub async fn logout() -> Result<(), ServerFnError> {
+  Ok(auth::end_session()?)
+}
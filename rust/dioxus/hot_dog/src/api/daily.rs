@@ -0,0 +1,30 @@
+use super::prelude::*;
+use crate::data::Dog;
+
+#[cfg(feature = "server")]
+use crate::{
+  data::daily,
+  provider::Provider,
+  storage::{daily as days, with_db},
+  utils::time
+};
+
+/// The dog of the day for a `YYYY-MM-DD` date.
+///
+/// The first request for a day picks the dog from the date's seed and caches
+/// it, so everyone sees the same one even if the catalog changes later.
+#[server]
+pub async fn dog_of_the_day(date: String) -> Result<Dog, ServerFnError> {
+  let now = time::now();
+  if !daily::is_available(&date, now) {
+    return Err(Error::NotFound(format!("dog of the day for {date}")).into());
+  }
+  if let Some(dog) = with_db(|db| days::get(db, &date))? {
+    return Ok(dog);
+  }
+
+  let dog = Provider::configured()
+    .seeded_dog(daily::seed(&date))
+    .await?;
+  Ok(with_db(|db| days::insert(db, &date, &dog, now))?)
+}
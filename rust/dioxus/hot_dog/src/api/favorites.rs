@@ -0,0 +1,220 @@
+use super::prelude::*;
+use crate::data::{
+  Dog, Favorite,
+  favorite::{MergeReport, Saved},
+  filter::BreedFilter
+};
+
+#[cfg(feature = "server")]
+use crate::{
+  analysis, auth,
+  data::{
+    history::{CAPACITY, History, Scope},
+    sync::Change
+  },
+  provider::{Provider, SeenByOwner, SeenPhotos},
+  server::sync::publish,
+  storage::{favorites, history, with_db},
+  utils::{proxy, time}
+};
+#[cfg(feature = "server")]
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Photos served to or saved by `scope` since the server started, seeded
+/// with a user's favorites' hashes on first use.
+#[cfg(feature = "server")]
+fn seen_photos(scope: &Scope) -> Result<SeenPhotos, ServerFnError> {
+  static SEEN: OnceLock<SeenByOwner> = OnceLock::new();
+  let seen = SEEN.get_or_init(SeenByOwner::default);
+  Ok(seen.get(&scope.key(), || match scope {
+    Scope::User(id) => match id.parse() {
+      Ok(owner) => with_db(|db| favorites::analyzed(db, owner)),
+      Err(_) => Ok(Vec::new())
+    },
+    Scope::Session(_) => Ok(Vec::new())
+  })?)
+}
+
+/// The history scopes of the request's session cookie, followed by its
+/// user's; empty without a session.
+#[cfg(feature = "server")]
+fn scopes() -> Result<Vec<Scope>, ServerFnError> {
+  Ok(match (auth::session_id(), auth::user()?) {
+    (Some(session), Some(user)) =>
+      vec![Scope::Session(session), Scope::User(user.id.to_string())],
+    _ => Vec::new()
+  })
+}
+
+/// The provider the client picked by `name`, unless the server runs from an
+/// offline bundle or the pick is a custom provider off the proxy's hosts.
+#[cfg(feature = "server")]
+fn chosen_provider(name: &str) -> Provider {
+  let configured = Provider::configured();
+  match Provider::from_name(name) {
+    _ if matches!(configured, Provider::Offline) => configured,
+    Provider::Custom(base_url) if !proxy::is_allowed(&base_url) => configured,
+    chosen => chosen
+  }
+}
+
+/// Saves `dog` to the user's favorites, returning the stored row along with
+/// any of their saved photos that look like the same picture.
+///
+/// Saving the same photo twice is a no-op and returns the original entry.
+/// New photos are analyzed on the way in; a failed analysis still saves.
+/// Photos from hosts the image proxy won't fetch from are refused.
+#[server]
+pub async fn save_dog(dog: Dog) -> Result<Saved, ServerFnError> {
+  let owner = auth::require_user()?.id;
+  if !proxy::is_allowed(&dog.photo_url) {
+    return Err(
+      Error::Url(format!("{} is not on an allowed host", dog.photo_url)).into()
+    );
+  }
+  let mut favorite =
+    with_db(|db| favorites::insert(db, owner, &dog, time::now()))?;
+  if favorite.meta.is_none() {
+    match analysis::analyze_url(&dog.photo_url).await {
+      Ok(meta) => {
+        with_db(|db| favorites::set_meta(db, favorite.id, &meta))?;
+        seen_photos(&Scope::User(owner.to_string()))?.insert(meta.clone());
+        favorite.meta = Some(meta);
+      }
+      Err(e) => warn!("Failed to analyze {}: {e}", dog.photo_url)
+    }
+  }
+
+  publish(owner, Change::Saved(Box::new(favorite.clone())));
+
+  let duplicates = match &favorite.meta {
+    Some(meta) =>
+      with_db(|db| favorites::find_duplicates(db, owner, favorite.id, meta))?,
+    None => Vec::new()
+  };
+  if !duplicates.is_empty() {
+    debug!(
+      "{} looks like {} saved photos",
+      dog.photo_url,
+      duplicates.len()
+    );
+  }
+  Ok(Saved {
+    favorite,
+    duplicates
+  })
+}
+
+/// Fetches a dog passing `filter` from `provider`, skipping photos already
+/// served to this session or user or `queued` behind the one on screen, and
+/// ones that look like a saved or recently served photo.
+///
+/// The dog only counts as served once the client calls [`mark_served`].
+#[server]
+pub async fn next_dog(
+  provider: String,
+  filter: BreedFilter,
+  queued: Vec<String>
+) -> Result<Dog, ServerFnError> {
+  let scopes = scopes()?;
+  //~@ Look-alikes are tracked per user; a client without a session starts
+  //~@ from nothing
+  let seen = match scopes.last() {
+    Some(scope) => seen_photos(scope)?,
+    None => SeenPhotos::default()
+  };
+
+  //{ Merge the histories so a repeat in either scope is skipped }
+  let mut served = queued;
+  for scope in &scopes {
+    served.extend(with_db(|db| history::load(db, scope, CAPACITY))?.urls());
+  }
+  let capacity = served.len().max(2 * CAPACITY);
+  let history = Arc::new(Mutex::new(History::from_urls(capacity, served)));
+
+  let dog = chosen_provider(&provider)
+    .unseen_dog(&seen, history, &filter)
+    .await?;
+  Ok(dog)
+}
+
+/// Records `photo_url` as served to this session and user, once the client
+/// has put it on screen.
+#[server]
+pub async fn mark_served(photo_url: String) -> Result<(), ServerFnError> {
+  auth::require_user()?;
+  if !proxy::is_allowed(&photo_url) {
+    return Err(
+      Error::Url(format!("{photo_url} is not on an allowed host")).into()
+    );
+  }
+  for scope in &scopes()? {
+    with_db(|db| {
+      history::record(db, scope, &photo_url, time::now(), CAPACITY)
+    })?;
+  }
+  Ok(())
+}
+
+/// Collapses the user's near-duplicate favorites into their oldest copy.
+#[server]
+pub async fn merge_duplicates() -> Result<MergeReport, ServerFnError> {
+  let owner = auth::require_user()?.id;
+  let report = with_db(|db| favorites::merge_duplicates(db, owner))?;
+  publish(owner, Change::Favorites);
+  Ok(report)
+}
+
+/// Lists the user's favorites, newest first; empty without a session.
+#[server]
+pub async fn list_favorites() -> Result<Vec<Favorite>, ServerFnError> {
+  let Some(user) = auth::user()? else {
+    return Ok(Vec::new());
+  };
+  Ok(with_db(|db| favorites::list(db, user.id))?)
+}
+
+/// Analyzes every favorite saved before analysis existed, returning how many
+/// were updated.
+#[server]
+pub async fn analyze_favorites() -> Result<usize, ServerFnError> {
+  let owner = auth::require_user()?.id;
+  let pending = with_db(|db| favorites::missing_meta(db, owner))?;
+  let mut analyzed = 0;
+  for favorite in pending {
+    match analysis::analyze_url(&favorite.dog.photo_url).await {
+      Ok(meta) => {
+        with_db(|db| favorites::set_meta(db, favorite.id, &meta))?;
+        analyzed += 1;
+      }
+      Err(e) => warn!("Failed to analyze {}: {e}", favorite.dog.photo_url)
+    }
+  }
+  if analyzed > 0 {
+    publish(owner, Change::Favorites);
+  }
+  Ok(analyzed)
+}
+
+/// Removes the given favorites, returning how many were deleted.
+#[server]
+pub async fn delete_favorites(ids: Vec<i64>) -> Result<usize, ServerFnError> {
+  let owner = auth::require_user()?.id;
+  let deleted = with_db(|db| favorites::delete(db, owner, &ids))?;
+  if deleted > 0 {
+    publish(owner, Change::Deleted(ids));
+  }
+  Ok(deleted)
+}
+
+/// Replaces the tags on one favorite.
+#[server]
+pub async fn set_favorite_tags(
+  id: i64,
+  tags: Vec<String>
+) -> Result<(), ServerFnError> {
+  let owner = auth::require_user()?.id;
+  with_db(|db| favorites::set_tags(db, owner, id, &tags))?;
+  publish(owner, Change::Tagged { id, tags });
+  Ok(())
+}
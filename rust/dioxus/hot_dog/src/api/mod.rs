@@ -0,0 +1,29 @@
+//|-> Modules
+mod auth;
+mod daily;
+mod favorites;
+mod offline;
+mod quiz;
+mod ratings;
+mod transfer;
+
+//|-> Internal Exports
+mod prelude {
+  pub use crate::prelude::*;
+  //~@ Server functions must return `Result<T, ServerFnError>`
+  pub use std::result::Result;
+}
+
+//|-> External Exports
+pub use auth::{login, logout, register, whoami};
+pub use daily::dog_of_the_day;
+pub use favorites::{
+  analyze_favorites, delete_favorites, list_favorites, mark_served,
+  merge_duplicates, next_dog, save_dog, set_favorite_tags
+};
+pub use offline::{
+  bundled_breed_photos, bundled_breeds, bundled_dogs, offline_mode
+};
+pub use quiz::{high_scores, submit_score};
+pub use ratings::{leaderboard, rate_dog, record_matchup};
+pub use transfer::import_favorites;
@@ -0,0 +1,53 @@
+use super::prelude::*;
+use crate::{data::Dog, provider::BreedsApiResponse};
+
+#[cfg(feature = "server")]
+use crate::provider::{DogCeo, Provider, offline};
+#[cfg(feature = "server")]
+use std::path::PathBuf;
+
+/// Whether the server runs from a mounted offline bundle, in which case the
+/// client must not call providers itself.
+#[server]
+pub async fn offline_mode() -> Result<bool, ServerFnError> {
+  Ok(offline::mounted().is_some())
+}
+
+/// `count` random dogs from the mounted bundle.
+#[server]
+pub async fn bundled_dogs(count: usize) -> Result<Vec<Dog>, ServerFnError> {
+  Ok(Provider::Offline.dogs(count).await?)
+}
+
+/// The breed list the server runs from, the bundle's snapshot when one is
+/// mounted.
+#[server]
+pub async fn bundled_breeds() -> Result<BreedsApiResponse, ServerFnError> {
+  Ok(DogCeo::get_breeds(None::<PathBuf>, None).await?)
+}
+
+/// Photo URLs of the bundled dogs of one breed, every sub-breed included
+/// when `sub` is `None`.
+#[server]
+pub async fn bundled_breed_photos(
+  main: String,
+  sub: Option<String>
+) -> Result<Vec<String>, ServerFnError> {
+  let Some(bundle) = offline::mounted() else {
+    return Ok(Vec::new());
+  };
+  Ok(
+    bundle
+      .manifest
+      .dogs
+      .iter()
+      .filter(|entry| {
+        entry.dog.breed == main
+          && sub
+            .as_ref()
+            .is_none_or(|sub| entry.dog.sub_breed.as_ref() == Some(sub))
+      })
+      .map(|entry| entry.dog.photo_url.clone())
+      .collect()
+  )
+}
@@ -0,0 +1,43 @@
+use super::prelude::*;
+use crate::data::quiz::{Difficulty, HighScore};
+
+#[cfg(feature = "server")]
+use crate::{
+  auth,
+  storage::{quiz, with_db},
+  utils::time
+};
+
+//~@ Rows shown per difficulty in the high-score table
+const HIGH_SCORES: u32 = 10;
+
+/// Stores a finished round and returns the updated table for its difficulty.
+///
+/// Scores no run of answers could have produced are refused.
+#[server]
+pub async fn submit_score(
+  score: HighScore
+) -> Result<Vec<HighScore>, ServerFnError> {
+  let user = auth::require_user()?;
+  if !score.is_possible() {
+    return Err(Error::Invalid("that score can't be reached".into()).into());
+  }
+  let mut score = score;
+  score.name = score.name.trim().chars().take(32).collect();
+  if score.name.is_empty() {
+    score.name = user.display_name().to_string();
+  }
+  score.played_at = time::now();
+  Ok(with_db(|db| {
+    quiz::insert(db, &score)?;
+    quiz::top(db, score.difficulty, HIGH_SCORES)
+  })?)
+}
+
+/// The best scores for one difficulty.
+#[server]
+pub async fn high_scores(
+  difficulty: Difficulty
+) -> Result<Vec<HighScore>, ServerFnError> {
+  Ok(with_db(|db| quiz::top(db, difficulty, HIGH_SCORES))?)
+}
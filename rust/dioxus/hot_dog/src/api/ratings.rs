@@ -0,0 +1,61 @@
+use super::prelude::*;
+use crate::data::{BreedScore, Dog, Vote};
+
+#[cfg(feature = "server")]
+use crate::{
+  auth,
+  data::sync::Change,
+  server::sync::publish,
+  storage::{favorites, ratings, with_db},
+  utils::{proxy, time}
+};
+
+/// Rebuilds a client's `dog` from its photo URL, so ratings only ever count
+/// real breeds on allowed hosts.
+#[cfg(feature = "server")]
+fn from_photo(dog: &Dog) -> Result<Dog, ServerFnError> {
+  if !proxy::is_allowed(&dog.photo_url) {
+    return Err(
+      Error::Url(format!("{} is not on an allowed host", dog.photo_url)).into()
+    );
+  }
+  Ok(favorites::dog_from_url(&dog.photo_url)?)
+}
+
+/// Records the user's like or skip on `dog`, returning its breed's updated
+/// score.
+#[server]
+pub async fn rate_dog(
+  dog: Dog,
+  vote: Vote
+) -> Result<BreedScore, ServerFnError> {
+  let owner = auth::require_user()?.id;
+  let dog = from_photo(&dog)?;
+  let score =
+    with_db(|db| ratings::record_vote(db, owner, &dog, vote, time::now()))?;
+  publish(owner, Change::Ratings);
+  Ok(score)
+}
+
+/// Records `winner` beating `loser` head-to-head, returning both new scores.
+#[server]
+pub async fn record_matchup(
+  winner: Dog,
+  loser: Dog
+) -> Result<(BreedScore, BreedScore), ServerFnError> {
+  let owner = auth::require_user()?.id;
+  let (winner, loser) = (from_photo(&winner)?, from_photo(&loser)?);
+  let scores =
+    with_db(|db| ratings::record_matchup(db, owner, &winner, &loser))?;
+  publish(owner, Change::Ratings);
+  Ok(scores)
+}
+
+/// Every breed the user has rated, best first; empty without a session.
+#[server]
+pub async fn leaderboard() -> Result<Vec<BreedScore>, ServerFnError> {
+  let Some(user) = auth::user()? else {
+    return Ok(Vec::new());
+  };
+  Ok(with_db(|db| ratings::leaderboard(db, user.id))?)
+}
@@ -0,0 +1,82 @@
+use super::prelude::*;
+use crate::data::transfer::{Format, ImportReport};
+
+#[cfg(feature = "server")]
+use crate::{
+  auth,
+  data::{
+    sync::Change,
+    transfer::{self, Rejected}
+  },
+  provider::DogCeo,
+  server::sync::publish,
+  storage::{favorites, with_db},
+  utils::{proxy, time}
+};
+#[cfg(feature = "server")]
+use std::path::PathBuf;
+
+/// Imports favorites exported in any [`Format`], detecting it from the
+/// contents when `format` is `None`.
+///
+/// Every photo must be a dog.ceo URL of a known breed, on a host the image
+/// proxy fetches from. With `dry_run` set
+/// nothing is saved and the report shows what would change.
+#[server]
+pub async fn import_favorites(
+  content: String,
+  format: Option<Format>,
+  dry_run: bool
+) -> Result<ImportReport, ServerFnError> {
+  let owner = auth::require_user()?.id;
+  let format = format.unwrap_or_else(|| Format::detect(&content));
+  let (parsed, mut rejected) = transfer::parse(&content, format)?;
+
+  //{ Keep only known breeds, described from the URL rather than the input }
+  let catalog = DogCeo::get_breeds(None::<PathBuf>, None).await?;
+  let mut valid = Vec::with_capacity(parsed.len());
+  for (position, record) in parsed {
+    if !proxy::is_allowed(&record.photo_url) {
+      rejected.push(Rejected {
+        position,
+        reason: format!("{} is not on an allowed host", record.photo_url),
+        input: record.photo_url
+      });
+      continue;
+    }
+    let checked = DogCeo::extract_breed_from_url(&record.photo_url)
+      .and_then(|(main, sub)| DogCeo::verify_breed(&main, &sub, &catalog))
+      .and_then(|()| favorites::dog_from_url(&record.photo_url));
+    match checked {
+      Ok(dog) => valid.push(record.with_dog(dog)),
+      Err(e) => rejected.push(Rejected {
+        position,
+        input: record.photo_url,
+        reason: e.to_string()
+      })
+    }
+  }
+
+  let existing = with_db(|db| favorites::list(db, owner))?;
+  let mut report = transfer::plan(&existing, valid);
+  report.rejected = rejected;
+  report.dry_run = dry_run;
+  if dry_run {
+    return Ok(report);
+  }
+
+  let now = time::now();
+  with_db(|db| {
+    let tx = db.unchecked_transaction()?;
+    for record in report.added.iter().chain(&report.retagged) {
+      let dog = favorites::dog_from_url(&record.photo_url)?;
+      let saved =
+        favorites::insert(&tx, owner, &dog, record.saved_at.unwrap_or(now))?;
+      favorites::set_tags(&tx, owner, saved.id, &record.tags)?;
+    }
+    tx.commit()?;
+    Ok(())
+  })?;
+  publish(owner, Change::Favorites);
+  Ok(report)
+}
@@ -0,0 +1,116 @@
+//|-> Modules
+pub mod password;
+pub mod session;
+
+//|-> Internal Exports
+mod prelude {
+  pub use crate::prelude::*;
+}
+
+//|-> External Exports
+use crate::{
+  data::User,
+  storage::{users, with_db},
+  utils::time
+};
+use axum::http::{
+  HeaderMap, HeaderValue,
+  header::{COOKIE, SET_COOKIE}
+};
+use prelude::*;
+use sha2::{Digest, Sha256};
+
+/// The session token carried in the cookies of `headers`.
+fn token_from(headers: &HeaderMap) -> Option<String> {
+  headers
+    .get_all(COOKIE)
+    .iter()
+    .filter_map(|value| value.to_str().ok())
+    .find_map(|header| session::token(header).map(str::to_string))
+}
+
+/// The session token sent with the current request.
+fn request_token() -> Option<String> {
+  token_from(&server_context().request_parts().headers)
+}
+
+fn set_cookie(value: &str) {
+  match HeaderValue::from_str(value) {
+    Ok(value) => {
+      server_context()
+        .response_parts_mut()
+        .headers
+        .append(SET_COOKIE, value);
+    }
+    Err(e) => error!("Invalid session cookie: {e}")
+  }
+}
+
+/// The user behind the current request's session, if it has a valid one.
+pub fn user() -> Result<Option<User>> {
+  let Some(token) = request_token() else {
+    return Ok(None);
+  };
+  with_db(|db| users::session_user(db, &token, time::now()))
+}
+
+/// The user behind the session cookie in `headers`, for plain routes that
+/// run outside a server function.
+pub fn user_from(headers: &HeaderMap) -> Result<Option<User>> {
+  let Some(token) = token_from(headers) else {
+    return Ok(None);
+  };
+  with_db(|db| users::session_user(db, &token, time::now()))
+}
+
+/// An id for the current request's session that is safe to store: a digest
+/// of its token, which itself stays in the sessions table.
+pub fn session_id() -> Option<String> {
+  let digest = Sha256::digest(request_token()?.as_bytes());
+  Some(digest.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+/// Like [`user`], but rejects requests without a session.
+///
+/// Every write goes through here; the app starts an anonymous session on
+/// load, so only clients that never asked for one are turned away.
+pub fn require_user() -> Result<User> {
+  user()?.ok_or_else(|| Error::Auth("no session, reload the page".into()))
+}
+
+/// Signs the current request in as `user_id` under a fresh token, dropping
+/// whatever session it had.
+pub fn start_session(user_id: i64) -> Result<()> {
+  let token = session::new_token();
+  with_db(|db| {
+    if let Some(old) = request_token() {
+      users::delete_session(db, &old)?;
+    }
+    users::create_session(db, &token, user_id, time::now() + session::TTL)
+  })?;
+  set_cookie(&session::set_cookie(&token));
+  Ok(())
+}
+
+/// Ends the current request's session, if it has one.
+pub fn end_session() -> Result<()> {
+  if let Some(token) = request_token() {
+    with_db(|db| users::delete_session(db, &token))?;
+  }
+  set_cookie(&session::clear_cookie());
+  Ok(())
+}
+
+/// Hashes `password` off the async runtime; Argon2 is slow on purpose.
+pub async fn hash_password(password: String) -> Result<String> {
+  tokio::task::spawn_blocking(move || password::hash(&password))
+    .await
+    .map_err(|e| Error::Auth(e.to_string()))?
+}
+
+/// Checks `password` against `hash` off the async runtime.
+pub async fn verify_password(password: String, hash: String) -> Result<bool> {
+  tokio::task::spawn_blocking(move || password::verify(&password, &hash))
+    .await
+    .map_err(|e| Error::Auth(e.to_string()))
+}
@@ -0,0 +1,40 @@
+use super::prelude::*;
+use argon2::{
+  Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+  password_hash::{SaltString, rand_core::OsRng}
+};
+
+/// Hashes `password` with Argon2id and a fresh salt, in PHC string format.
+pub fn hash(password: &str) -> Result<String> {
+  let salt = SaltString::generate(&mut OsRng);
+  Argon2::default()
+    .hash_password(password.as_bytes(), &salt)
+    .map(|hash| hash.to_string())
+    .map_err(|e| Error::Auth(format!("failed to hash password: {e}")))
+}
+
+/// Whether `password` matches a hash produced by [`hash`].
+pub fn verify(password: &str, hash: &str) -> bool {
+  PasswordHash::new(hash).is_ok_and(|parsed| {
+    Argon2::default()
+      .verify_password(password.as_bytes(), &parsed)
+      .is_ok()
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_hash_and_verify() {
+    let hashed = hash("correct horse").unwrap();
+    assert!(hashed.starts_with("$argon2id$"));
+    assert!(verify("correct horse", &hashed));
+    assert!(!verify("battery staple", &hashed));
+    assert!(!verify("correct horse", "not a hash"));
+
+    //~@ Salts differ, so equal passwords never share a hash
+    assert_ne!(hash("correct horse").unwrap(), hashed);
+  }
+}
@@ -0,0 +1,59 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+
+pub const COOKIE: &str = "hot_dog_session";
+
+//~@ Sessions last thirty days from sign-in
+pub const TTL: i64 = 30 * 86_400;
+
+/// A random, unguessable session token.
+pub fn new_token() -> String {
+  let mut bytes = [0u8; 32];
+  OsRng.fill_bytes(&mut bytes);
+  bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Picks the session token out of a `Cookie` header.
+pub fn token(cookie_header: &str) -> Option<&str> {
+  cookie_header
+    .split(';')
+    .filter_map(|pair| pair.trim().split_once('='))
+    .find(|(name, _)| *name == COOKIE)
+    .map(|(_, value)| value)
+    .filter(|value| !value.is_empty())
+}
+
+/// The `Set-Cookie` value that stores `token` for [`TTL`] seconds.
+pub fn set_cookie(token: &str) -> String {
+  format!("{COOKIE}={token}; Path=/; Max-Age={TTL}; HttpOnly; SameSite=Lax")
+}
+
+/// The `Set-Cookie` value that removes the session cookie.
+pub fn clear_cookie() -> String {
+  format!("{COOKIE}=; Path=/; Max-Age=0; HttpOnly; SameSite=Lax")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_token_from_cookie_header() {
+    let header = format!("theme=dark; {COOKIE}=abc123; other=1");
+    assert_eq!(token(&header), Some("abc123"));
+    assert_eq!(token("theme=dark"), None);
+    assert_eq!(token(&format!("{COOKIE}=")), None);
+  }
+
+  #[test]
+  fn test_new_token_is_random() {
+    let (a, b) = (new_token(), new_token());
+    assert_eq!(a.len(), 64);
+    assert_ne!(a, b);
+  }
+
+  #[test]
+  fn test_cookies() {
+    assert!(set_cookie("abc").starts_with(&format!("{COOKIE}=abc;")));
+    assert!(clear_cookie().contains("Max-Age=0"));
+  }
+}
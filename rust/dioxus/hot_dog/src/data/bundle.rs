@@ -0,0 +1,163 @@
+use super::prelude::*;
+use crate::data::Dog;
+
+/// Layout version written into every manifest.
+pub const VERSION: u32 = 1;
+
+//~@ Entry names inside the archive
+pub const MANIFEST: &str = "manifest.json";
+pub const BREEDS: &str = "dog_ceo_breeds.json";
+pub const IMAGES: &str = "images/";
+
+/// Route that packs the signed-in user's favorites into a bundle.
+pub const DOWNLOAD_PATH: &str = "/bundle.zip";
+
+/// Route serving the mounted bundle's photos by their original URL.
+pub const PHOTO_PATH: &str = "/bundle/photo";
+
+/// Where the browser loads the bundled copy of `photo_url` from.
+pub fn photo_path(photo_url: &str) -> String {
+  format!(
+    "{}{PHOTO_PATH}?url={}",
+    server_fn::client::get_server_url(),
+    percent_encode(photo_url)
+  )
+}
+
+/// The download link for a bundle of `ids`, or of every favorite when empty.
+pub fn download_url(ids: &[i64]) -> String {
+  if ids.is_empty() {
+    return DOWNLOAD_PATH.to_string();
+  }
+  let ids: Vec<String> = ids.iter().map(i64::to_string).collect();
+  format!("{DOWNLOAD_PATH}?ids={}", ids.join(","))
+}
+
+/// Reads the comma-separated `ids` of a download link, skipping junk.
+pub fn parse_ids(ids: &str) -> Vec<i64> {
+  ids
+    .split(',')
+    .filter_map(|id| id.trim().parse().ok())
+    .collect()
+}
+
+/// The index of an offline bundle: every dog it holds and where its photo
+/// lives in the archive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+  pub version: u32,
+  pub created_at: i64,
+  pub dogs: Vec<Entry>
+}
+
+/// One bundled dog, keeping its original photo URL so favorites, history
+/// and permalinks still line up when the bundle is mounted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+  pub dog: Dog,
+  /// Path of the photo inside the archive.
+  pub file: String
+}
+
+impl Manifest {
+  pub fn new(created_at: i64) -> Self {
+    Self {
+      version: VERSION,
+      created_at,
+      dogs: Vec::new()
+    }
+  }
+
+  /// Adds `dog` unless its photo is already bundled, returning the archive
+  /// path its photo should be stored under.
+  pub fn push(&mut self, dog: Dog) -> Option<String> {
+    if self.find(&dog.photo_url).is_some() {
+      return None;
+    }
+    let file = file_name(&dog);
+    self.dogs.push(Entry {
+      dog,
+      file: file.clone()
+    });
+    Some(file)
+  }
+
+  pub fn find(&self, photo_url: &str) -> Option<&Entry> {
+    self
+      .dogs
+      .iter()
+      .find(|entry| entry.dog.photo_url == photo_url)
+  }
+
+  pub fn len(&self) -> usize {
+    self.dogs.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.dogs.is_empty()
+  }
+}
+
+/// Where the photo of `dog` is stored, derived from its permalink id so
+/// names are stable and can't escape the images folder.
+pub fn file_name(dog: &Dog) -> String {
+  let id = dog.id();
+  format!("{IMAGES}{}/{}", id.provider, id.id)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn dog(photo_url: &str) -> Dog {
+    Dog {
+      photo_url: photo_url.into(),
+      breed: "hound".into(),
+      sub_breed: Some("afghan".into()),
+      display_name: "Afghan Hound".into(),
+      reference_url: "https://dog.ceo/api/breed/hound/afghan".into()
+    }
+  }
+
+  #[test]
+  fn test_files_follow_permalink_ids() {
+    let photo = "https://images.dog.ceo/breeds/hound-afghan/n02088094_1003.jpg";
+    assert_eq!(
+      file_name(&dog(photo)),
+      "images/dog_ceo/hound-afghan~n02088094_1003.jpg"
+    );
+
+    let custom = file_name(&dog("https://example.com/../../etc/passwd"));
+    assert!(custom.starts_with("images/custom/"));
+    assert!(!custom.contains(".."));
+  }
+
+  #[test]
+  fn test_download_links_round_trip() {
+    assert_eq!(download_url(&[]), "/bundle.zip");
+    assert_eq!(download_url(&[3, 14]), "/bundle.zip?ids=3,14");
+    assert_eq!(parse_ids("3,14"), vec![3, 14]);
+    assert_eq!(parse_ids("3,,x, 7"), vec![3, 7]);
+  }
+
+  #[test]
+  fn test_photo_paths_carry_the_original_url() {
+    assert_eq!(
+      photo_path("https://images.dog.ceo/breeds/pug/1.jpg"),
+      "/bundle/photo?url=https%3A%2F%2Fimages.dog.ceo%2Fbreeds%2Fpug%2F1.jpg"
+    );
+  }
+
+  #[test]
+  fn test_photos_are_bundled_once() {
+    let photo = "https://images.dog.ceo/breeds/hound-afghan/n02088094_1003.jpg";
+    let mut manifest = Manifest::new(0);
+    assert!(manifest.push(dog(photo)).is_some());
+    assert_eq!(manifest.push(dog(photo)), None);
+    assert_eq!(manifest.len(), 1);
+    assert_eq!(
+      manifest.find(photo).map(|e| e.dog.breed.as_str()),
+      Some("hound")
+    );
+  }
+}
@@ -0,0 +1,56 @@
+use super::prelude::*;
+use crate::utils::time;
+
+/// Days past the server's UTC date that may be requested, since the
+/// furthest timezones run ahead of UTC.
+pub const LEAD_DAYS: i64 = 1;
+
+/// The first day with a dog, so old dates can't each cache a new row.
+pub const FIRST_DAY: &str = "2025-01-01";
+
+/// The fixed seed behind one calendar day's dog.
+///
+/// FNV-1a over the date string, so it never changes between builds or
+/// platforms the way `DefaultHasher` may.
+pub fn seed(date: &str) -> u64 {
+  date.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+    (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+  })
+}
+
+/// Whether `date` is a real `YYYY-MM-DD` day from [`FIRST_DAY`] on that has
+/// already started somewhere, relative to the Unix time `now`.
+pub fn is_available(date: &str, now: i64) -> bool {
+  let first = time::parse_date(FIRST_DAY).unwrap_or_default();
+  time::parse_date(date)
+    .is_some_and(|day| (first..=now + LEAD_DAYS * 86_400).contains(&day))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_seed_is_stable() {
+    assert_eq!(seed(""), 0xcbf2_9ce4_8422_2325);
+    assert_eq!(seed("2025-10-18"), seed("2025-10-18"));
+    assert_ne!(seed("2025-10-18"), seed("2025-10-19"));
+  }
+
+  #[test]
+  fn test_is_available() {
+    let now = time::parse_date("2025-10-18").unwrap() + 3_600;
+    assert!(is_available("2025-10-17", now));
+    assert!(is_available("2025-10-19", now));
+    assert!(!is_available("2025-10-20", now));
+    assert!(!is_available("2025-13-01", now));
+  }
+
+  #[test]
+  fn test_is_available_from_first_day() {
+    let now = time::parse_date("2025-10-18").unwrap();
+    assert!(is_available(FIRST_DAY, now));
+    assert!(!is_available("2024-12-31", now));
+    assert!(!is_available("1970-01-01", now));
+  }
+}
@@ -1,3 +1,7 @@
+use super::prelude::*;
+use crate::provider::Breed;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Dog {
   pub photo_url: String,
   pub breed: String,
@@ -5,3 +9,128 @@ pub struct Dog {
   pub display_name: String,
   pub reference_url: String
 }
+
+impl Dog {
+  pub fn new(photo_url: impl Into<String>, breed: Breed) -> Self {
+    Self {
+      photo_url: photo_url.into(),
+      breed: breed.main_breed,
+      sub_breed: breed.sub_breed,
+      display_name: breed.display_name,
+      reference_url: breed.url_reference
+    }
+  }
+
+  /// The stable identifier behind this dog's permalink.
+  pub fn id(&self) -> DogId {
+    DogId::from_photo_url(&self.photo_url)
+  }
+
+  /// Alt text for the photo, from [`Breed::display_name`].
+  pub fn alt_text(&self) -> String {
+    photo_alt(&self.display_name)
+  }
+}
+
+//~@ dog.ceo photos live at `<prefix><breed>/<file>`
+const DOG_CEO_PHOTOS: &str = "https://images.dog.ceo/breeds/";
+
+/// A stable, URL-safe identifier for a dog, derived from the provider and the
+/// photo's path.
+///
+/// dog.ceo photos keep a readable `breed~file` id; photos from anywhere else
+/// fall under `custom` with the hex-encoded URL as the id.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DogId {
+  pub provider: String,
+  pub id: String
+}
+
+impl DogId {
+  pub fn from_photo_url(photo_url: &str) -> Self {
+    let dog_ceo = photo_url
+      .strip_prefix(DOG_CEO_PHOTOS)
+      .and_then(|path| path.split_once('/'))
+      .filter(|(breed, file)| {
+        let reserved = |part: &str| part.contains(['/', '~', '?', '#']);
+        !(breed.is_empty()
+          || file.is_empty()
+          || reserved(breed)
+          || reserved(file))
+      });
+    match dog_ceo {
+      Some((breed, file)) => Self {
+        provider: "dog_ceo".into(),
+        id: format!("{breed}~{file}")
+      },
+      None => Self {
+        provider: "custom".into(),
+        id: hex_encode(photo_url)
+      }
+    }
+  }
+
+  /// The photo URL this id was derived from.
+  pub fn photo_url(&self) -> Result<String> {
+    let invalid =
+      || Error::Url(format!("Unknown dog id: {}/{}", self.provider, self.id));
+    match self.provider.as_str() {
+      "dog_ceo" => {
+        let (breed, file) = self.id.split_once('~').ok_or_else(invalid)?;
+        Ok(format!("{DOG_CEO_PHOTOS}{breed}/{file}"))
+      }
+      "custom" => hex_decode(&self.id).ok_or_else(invalid),
+      _ => Err(invalid())
+    }
+  }
+}
+
+fn hex_encode(s: &str) -> String {
+  s.bytes().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn hex_decode(hex: &str) -> Option<String> {
+  if !hex.len().is_multiple_of(2) {
+    return None;
+  }
+  let bytes = (0..hex.len())
+    .step_by(2)
+    .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+    .collect::<Option<Vec<u8>>>()?;
+  String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_dog_ceo_id_round_trips() {
+    let url = "https://images.dog.ceo/breeds/hound-afghan/n02088094_1003.jpg";
+    let id = DogId::from_photo_url(url);
+    assert_eq!(id.provider, "dog_ceo");
+    assert_eq!(id.id, "hound-afghan~n02088094_1003.jpg");
+    assert_eq!(id.photo_url().unwrap(), url);
+  }
+
+  #[test]
+  fn test_custom_id_round_trips() {
+    let url = "https://example.com/dogs/beagle.jpg?size=large";
+    let id = DogId::from_photo_url(url);
+    assert_eq!(id.provider, "custom");
+    assert!(id.id.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_eq!(id.photo_url().unwrap(), url);
+  }
+
+  #[test]
+  fn test_invalid_ids() {
+    let id = |provider: &str, id: &str| DogId {
+      provider: provider.into(),
+      id: id.into()
+    };
+    assert!(id("dog_ceo", "no-separator.jpg").photo_url().is_err());
+    assert!(id("custom", "abc").photo_url().is_err());
+    assert!(id("custom", "zz").photo_url().is_err());
+    assert!(id("other", "00").photo_url().is_err());
+  }
+}
@@ -0,0 +1,184 @@
+use super::prelude::*;
+use crate::data::{Dog, ImageMeta};
+use std::cmp::Reverse;
+
+/// A saved dog, as stored by the favorites server functions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Favorite {
+  pub id: i64,
+  pub dog: Dog,
+  pub tags: Vec<String>,
+  pub saved_at: i64,
+  /// Filled in by the server once the photo has been analyzed.
+  #[serde(default)]
+  pub meta: Option<ImageMeta>
+}
+
+/// What `save_dog` stored, plus any near-duplicates that were already saved.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Saved {
+  pub favorite: Favorite,
+  pub duplicates: Vec<Favorite>
+}
+
+/// Outcome of merging near-duplicate favorites.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MergeReport {
+  pub groups: usize,
+  pub removed: usize
+}
+
+/// How the favorites gallery is ordered.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+  #[default]
+  Newest,
+  Oldest,
+  Breed
+}
+
+/// Which favorites the gallery shows.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Filter {
+  pub breed: Option<String>,
+  pub tag: Option<String>,
+  pub sort: Sort
+}
+
+impl Favorite {
+  pub fn has_tag(&self, tag: &str) -> bool {
+    self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+  }
+}
+
+impl Sort {
+  pub const ALL: [Sort; 3] = [Sort::Newest, Sort::Oldest, Sort::Breed];
+
+  pub fn label(&self) -> &'static str {
+    match self {
+      Sort::Newest => "Newest first",
+      Sort::Oldest => "Oldest first",
+      Sort::Breed => "Breed"
+    }
+  }
+}
+
+impl Filter {
+  /// Returns the matching favorites in display order.
+  pub fn apply(&self, favorites: &[Favorite]) -> Vec<Favorite> {
+    let mut matching: Vec<Favorite> = favorites
+      .iter()
+      .filter(|f| {
+        self
+          .breed
+          .as_ref()
+          .is_none_or(|breed| &f.dog.display_name == breed)
+      })
+      .filter(|f| self.tag.as_ref().is_none_or(|tag| f.has_tag(tag)))
+      .cloned()
+      .collect();
+
+    match self.sort {
+      Sort::Newest => matching.sort_by_key(|f| Reverse(f.saved_at)),
+      Sort::Oldest => matching.sort_by_key(|f| f.saved_at),
+      Sort::Breed => matching.sort_by(|a, b| {
+        a.dog
+          .display_name
+          .cmp(&b.dog.display_name)
+          .then(b.saved_at.cmp(&a.saved_at))
+      })
+    }
+    matching
+  }
+}
+
+/// Ids of analyzed favorites that are near-duplicates of the oldest one in
+/// their group, which comes first.
+pub fn duplicate_groups(favorites: &[Favorite]) -> Vec<Vec<i64>> {
+  let mut analyzed: Vec<&Favorite> =
+    favorites.iter().filter(|f| f.meta.is_some()).collect();
+  analyzed.sort_by_key(|f| (f.saved_at, f.id));
+  super::image::duplicate_groups(
+    analyzed
+      .into_iter()
+      .filter_map(|f| f.meta.as_ref().map(|meta| (f.id, meta)))
+  )
+}
+
+/// Splits user input like `"fluffy, Sleepy ,"` into clean, unique tags.
+pub fn parse_tags(input: &str) -> Vec<String> {
+  let mut tags: Vec<String> = Vec::new();
+  for tag in input.split(',').map(|t| t.trim().to_lowercase()) {
+    if !tag.is_empty() && !tags.contains(&tag) {
+      tags.push(tag);
+    }
+  }
+  tags
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn favorite(id: i64, name: &str, tags: &[&str], saved_at: i64) -> Favorite {
+    Favorite {
+      id,
+      dog: Dog {
+        photo_url: format!("https://images.dog.ceo/breeds/{name}/{id}.jpg"),
+        breed: name.to_lowercase(),
+        sub_breed: None,
+        display_name: name.to_string(),
+        reference_url: String::new()
+      },
+      tags: tags.iter().map(|t| t.to_string()).collect(),
+      saved_at,
+      meta: None
+    }
+  }
+
+  #[test]
+  fn test_filter_and_sort() {
+    let favorites = vec![
+      favorite(1, "Pug", &["sleepy"], 10),
+      favorite(2, "Akita", &[], 30),
+      favorite(3, "Pug", &["fluffy"], 20),
+    ];
+
+    let ids = |filter: Filter| -> Vec<i64> {
+      filter.apply(&favorites).iter().map(|f| f.id).collect()
+    };
+
+    assert_eq!(ids(Filter::default()), vec![2, 3, 1]);
+    assert_eq!(
+      ids(Filter {
+        sort: Sort::Oldest,
+        ..Default::default()
+      }),
+      vec![1, 3, 2]
+    );
+    assert_eq!(
+      ids(Filter {
+        sort: Sort::Breed,
+        ..Default::default()
+      }),
+      vec![2, 3, 1]
+    );
+    assert_eq!(
+      ids(Filter {
+        breed: Some("Pug".into()),
+        tag: Some("FLUFFY".into()),
+        ..Default::default()
+      }),
+      vec![3]
+    );
+  }
+
+  #[test]
+  fn test_parse_tags() {
+    assert_eq!(
+      parse_tags("fluffy, Sleepy ,,fluffy"),
+      vec!["fluffy", "sleepy"]
+    );
+    assert!(parse_tags(" , ").is_empty());
+  }
+}
@@ -0,0 +1,190 @@
+use super::prelude::*;
+use std::fmt;
+
+/// A breed, or every sub-breed of a main breed when `sub` is `None`.
+///
+/// Written like dog.ceo's breed folders: `hound-afghan`, or just `hound`.
+#[derive(
+  Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub struct BreedKey {
+  pub main: String,
+  pub sub: Option<String>
+}
+
+impl BreedKey {
+  pub fn new(main: impl Into<String>, sub: Option<impl Into<String>>) -> Self {
+    Self {
+      main: main.into(),
+      sub: sub.map(Into::into)
+    }
+  }
+
+  /// Reads `main` or `main-sub`; names are lowercase letters only.
+  pub fn parse(label: &str) -> Option<Self> {
+    let label = label.trim().to_lowercase();
+    let (main, sub) = match label.split_once('-') {
+      Some((main, sub)) => (main, Some(sub)),
+      None => (label.as_str(), None)
+    };
+    let valid = |name: &str| {
+      !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase())
+    };
+    (valid(main) && sub.is_none_or(valid)).then(|| Self::new(main, sub))
+  }
+
+  pub fn label(&self) -> String {
+    match &self.sub {
+      Some(sub) => format!("{}-{sub}", self.main),
+      None => self.main.clone()
+    }
+  }
+
+  /// Whether a dog of `main` and `sub` is this breed.
+  pub fn matches(&self, main: &str, sub: Option<&str>) -> bool {
+    self.main.eq_ignore_ascii_case(main)
+      && match (&self.sub, sub) {
+        (None, _) => true,
+        (Some(wanted), Some(sub)) => wanted.eq_ignore_ascii_case(sub),
+        (Some(_), None) => false
+      }
+  }
+}
+
+/// Which breeds the viewer may show.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum BreedFilter {
+  #[default]
+  Any,
+  /// Only these breeds; a single breed is a list of one.
+  Only(Vec<BreedKey>),
+  /// Every breed but these.
+  Except(Vec<BreedKey>)
+}
+
+impl BreedFilter {
+  pub fn is_any(&self) -> bool {
+    *self == BreedFilter::Any
+  }
+
+  /// The breeds listed, whether kept or excluded.
+  pub fn breeds(&self) -> &[BreedKey] {
+    match self {
+      BreedFilter::Any => &[],
+      BreedFilter::Only(breeds) | BreedFilter::Except(breeds) => breeds
+    }
+  }
+
+  /// Whether a dog of `main` and `sub` passes.
+  pub fn matches(&self, main: &str, sub: Option<&str>) -> bool {
+    match self {
+      BreedFilter::Any => true,
+      BreedFilter::Only(breeds) => breeds.iter().any(|b| b.matches(main, sub)),
+      BreedFilter::Except(breeds) =>
+        !breeds.iter().any(|b| b.matches(main, sub)),
+    }
+  }
+
+  /// The catalog entries that pass, in catalog order.
+  pub fn allowed<'a>(
+    &self,
+    keys: &'a [(String, Option<String>)]
+  ) -> Vec<&'a (String, Option<String>)> {
+    keys
+      .iter()
+      .filter(|(main, sub)| self.matches(main, sub.as_deref()))
+      .collect()
+  }
+
+  /// Reads a query string such as `only=hound-afghan,pug` or `except=pug`.
+  ///
+  /// Unknown parameters and malformed breeds are ignored; an empty list
+  /// means no filter.
+  pub fn parse(query: &str) -> Self {
+    let query = query.trim_start_matches('?');
+    for (name, value) in query.split('&').filter_map(|p| p.split_once('=')) {
+      //~@ Commas may arrive percent-encoded
+      let value = value.replace("%2C", ",").replace("%2c", ",");
+      let breeds: Vec<BreedKey> = value
+        .split([',', '+', ' '])
+        .filter_map(BreedKey::parse)
+        .collect();
+      match name {
+        _ if breeds.is_empty() => {}
+        "only" => return BreedFilter::Only(breeds),
+        "except" => return BreedFilter::Except(breeds),
+        _ => {}
+      }
+    }
+    BreedFilter::Any
+  }
+}
+
+/// The query string [`BreedFilter::parse`] reads back, empty for
+/// [`BreedFilter::Any`].
+impl fmt::Display for BreedFilter {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let name = match self {
+      BreedFilter::Any => return Ok(()),
+      BreedFilter::Only(_) => "only",
+      BreedFilter::Except(_) => "except"
+    };
+    let labels: Vec<String> =
+      self.breeds().iter().map(BreedKey::label).collect();
+    write!(f, "{name}={}", labels.join(","))
+  }
+}
+
+//~@ Lets the router read the filter from the query string
+impl From<&str> for BreedFilter {
+  fn from(query: &str) -> Self {
+    Self::parse(query)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn key(label: &str) -> BreedKey {
+    BreedKey::parse(label).unwrap()
+  }
+
+  #[test]
+  fn test_filters_round_trip_through_the_query() {
+    for filter in [
+      BreedFilter::Any,
+      BreedFilter::Only(vec![key("hound-afghan")]),
+      BreedFilter::Only(vec![key("pug"), key("hound")]),
+      BreedFilter::Except(vec![key("poodle-toy")])
+    ] {
+      assert_eq!(BreedFilter::parse(&filter.to_string()), filter);
+    }
+    assert_eq!(
+      BreedFilter::parse("?page=2&only=Pug%2Cnot-a-breed!,"),
+      BreedFilter::Only(vec![key("pug")])
+    );
+    assert_eq!(BreedFilter::parse("only="), BreedFilter::Any);
+    assert_eq!(BreedKey::parse("hound-"), None);
+  }
+
+  #[test]
+  fn test_main_breeds_cover_their_sub_breeds() {
+    let hounds = BreedFilter::Only(vec![key("hound")]);
+    assert!(hounds.matches("hound", Some("afghan")));
+    assert!(hounds.matches("hound", None));
+    assert!(!hounds.matches("pug", None));
+
+    let afghans = BreedFilter::Only(vec![key("hound-afghan")]);
+    assert!(afghans.matches("Hound", Some("Afghan")));
+    assert!(!afghans.matches("hound", Some("basset")));
+    assert!(!afghans.matches("hound", None));
+
+    let no_pugs = BreedFilter::Except(vec![key("pug")]);
+    let keys = vec![
+      ("hound".to_string(), Some("afghan".to_string())),
+      ("pug".to_string(), None),
+    ];
+    assert_eq!(no_pugs.allowed(&keys), vec![&keys[0]]);
+  }
+}
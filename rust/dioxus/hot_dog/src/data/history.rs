@@ -0,0 +1,114 @@
+use super::prelude::*;
+use std::collections::{HashSet, VecDeque};
+
+/// How many served photos each scope remembers.
+pub const CAPACITY: usize = 500;
+
+/// Whose history a served photo is recorded in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Scope {
+  /// One visit to the app, forgotten when the page is reloaded.
+  Session(String),
+  /// Everything a user has been shown, kept across restarts.
+  User(String)
+}
+
+impl Scope {
+  /// The key the scope is stored under.
+  pub fn key(&self) -> String {
+    match self {
+      Scope::Session(id) => format!("session:{id}"),
+      Scope::User(id) => format!("user:{id}")
+    }
+  }
+}
+
+/// A bounded, least-recently-served set of photo URLs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct History {
+  capacity: usize,
+  order: VecDeque<String>,
+  urls: HashSet<String>
+}
+
+impl Default for History {
+  fn default() -> Self {
+    Self::new(CAPACITY)
+  }
+}
+
+impl History {
+  pub fn new(capacity: usize) -> Self {
+    Self {
+      capacity: capacity.max(1),
+      order: VecDeque::new(),
+      urls: HashSet::new()
+    }
+  }
+
+  /// Rebuilds a history from URLs ordered oldest first.
+  pub fn from_urls(
+    capacity: usize,
+    urls: impl IntoIterator<Item = String>
+  ) -> Self {
+    let mut history = Self::new(capacity);
+    for url in urls {
+      history.insert(url);
+    }
+    history
+  }
+
+  pub fn contains(&self, url: &str) -> bool {
+    self.urls.contains(url)
+  }
+
+  /// Records `url` as the most recent photo, evicting the oldest when full.
+  pub fn insert(&mut self, url: String) {
+    if self.urls.contains(&url) {
+      self.order.retain(|seen| *seen != url);
+    } else if self.order.len() == self.capacity {
+      if let Some(oldest) = self.order.pop_front() {
+        self.urls.remove(&oldest);
+      }
+    }
+    self.urls.insert(url.clone());
+    self.order.push_back(url);
+  }
+
+  /// The remembered URLs, oldest first.
+  pub fn urls(&self) -> impl Iterator<Item = String> + '_ {
+    self.order.iter().cloned()
+  }
+
+  pub fn len(&self) -> usize {
+    self.order.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.order.is_empty()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_history_evicts_least_recent() {
+    let mut history = History::from_urls(2, ["a".into(), "b".into()]);
+    assert!(history.contains("a") && history.contains("b"));
+
+    //~@ Seeing `a` again makes `b` the oldest
+    history.insert("a".into());
+    history.insert("c".into());
+    assert_eq!(history.len(), 2);
+    assert!(history.contains("a") && history.contains("c"));
+    assert!(!history.contains("b"));
+  }
+
+  #[test]
+  fn test_scope_key() {
+    assert_eq!(Scope::Session("42".into()).key(), "session:42");
+    assert_eq!(Scope::User("me".into()).key(), "user:me");
+  }
+}
@@ -0,0 +1,135 @@
+use super::prelude::*;
+
+/// Hash distance at or below which two photos count as the same picture.
+pub const DUPLICATE_DISTANCE: u32 = 6;
+
+/// Facts about a downloaded photo, extracted by `analysis::analyze`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageMeta {
+  pub width: u32,
+  pub height: u32,
+  /// Lower-case file extension of the container, e.g. `jpg`.
+  pub format: String,
+  pub bytes: u64,
+  /// Dominant colours as `#rrggbb`, most common first.
+  pub palette: Vec<String>,
+  /// 64-bit average hash.
+  pub ahash: u64,
+  /// 64-bit difference hash.
+  pub dhash: u64
+}
+
+impl ImageMeta {
+  /// CSS `aspect-ratio` value, so layouts can reserve space before loading.
+  pub fn aspect_ratio(&self) -> String {
+    format!("{} / {}", self.width, self.height)
+  }
+
+  /// Colour to show while the photo loads.
+  pub fn placeholder(&self) -> &str {
+    self.palette.first().map_or("#eeeeee", String::as_str)
+  }
+
+  /// Human-readable file size, e.g. `1.2 MB`.
+  pub fn size_label(&self) -> String {
+    match self.bytes {
+      b if b >= 1_000_000 => format!("{:.1} MB", b as f64 / 1_000_000.0),
+      b if b >= 1_000 => format!("{:.0} kB", b as f64 / 1_000.0),
+      b => format!("{b} B")
+    }
+  }
+
+  /// Smallest bit distance between the two photos' perceptual hashes.
+  pub fn distance(&self, other: &ImageMeta) -> u32 {
+    hamming(self.ahash, other.ahash).min(hamming(self.dhash, other.dhash))
+  }
+
+  /// Whether `other` is the same picture, possibly resized or re-encoded.
+  pub fn is_duplicate(&self, other: &ImageMeta) -> bool {
+    self.distance(other) <= DUPLICATE_DISTANCE
+  }
+}
+
+/// Number of differing bits between two hashes.
+pub fn hamming(a: u64, b: u64) -> u32 {
+  (a ^ b).count_ones()
+}
+
+/// Groups ids whose photos are near-duplicates of a keeper, the first of
+/// each group in input order.
+///
+/// Every member is compared with its keeper directly, never through a chain
+/// of look-alikes, so a group can't drift into unrelated photos. Photos
+/// without a duplicate are left out.
+pub fn duplicate_groups<'a>(
+  items: impl IntoIterator<Item = (i64, &'a ImageMeta)>
+) -> Vec<Vec<i64>> {
+  let mut ungrouped: Vec<(i64, &ImageMeta)> = items.into_iter().collect();
+  let mut groups = Vec::new();
+  while !ungrouped.is_empty() {
+    let (keeper, meta) = ungrouped.remove(0);
+    let mut group = vec![keeper];
+    ungrouped.retain(|(id, other)| {
+      let duplicate = meta.is_duplicate(other);
+      if duplicate {
+        group.push(*id);
+      }
+      !duplicate
+    });
+    if group.len() > 1 {
+      groups.push(group);
+    }
+  }
+  groups
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_labels_and_distance() {
+    let meta = ImageMeta {
+      width: 500,
+      height: 375,
+      format: "jpg".into(),
+      bytes: 48_213,
+      palette: vec!["#a07850".into()],
+      ahash: 0b1011,
+      dhash: 0xff
+    };
+    assert_eq!(meta.aspect_ratio(), "500 / 375");
+    assert_eq!(meta.placeholder(), "#a07850");
+    assert_eq!(meta.size_label(), "48 kB");
+
+    let other = ImageMeta {
+      ahash: 0b0011,
+      dhash: 0,
+      ..meta.clone()
+    };
+    assert_eq!(meta.distance(&other), 1);
+    assert!(meta.is_duplicate(&other));
+    assert_eq!(hamming(u64::MAX, 0), 64);
+  }
+
+  #[test]
+  fn test_duplicate_groups() {
+    let meta = |ahash: u64| ImageMeta {
+      width: 1,
+      height: 1,
+      format: "jpg".into(),
+      bytes: 1,
+      palette: Vec::new(),
+      ahash,
+      dhash: ahash
+    };
+    let (a, b, c, d) = (meta(0), meta(0b1111), meta(0xff), meta(u64::MAX));
+
+    //~@ `c` looks like `b` but is 8 bits from the keeper `a`, so it stays out
+    let groups = duplicate_groups([(1, &a), (2, &d), (3, &b), (4, &c)]);
+    assert_eq!(groups, vec![vec![1, 3]]);
+    let groups = duplicate_groups([(3, &b), (1, &a), (4, &c)]);
+    assert_eq!(groups, vec![vec![3, 1, 4]]);
+    assert!(duplicate_groups([(1, &a), (2, &d)]).is_empty());
+  }
+}
@@ -0,0 +1,216 @@
+use super::prelude::*;
+use crate::utils::rng::Rng;
+use std::collections::{BTreeMap, HashSet};
+
+/// A breed or sub-breed as `(main, sub)`.
+pub type BreedKey = (String, Option<String>);
+
+//~@ Random draws to try before scanning every pair
+const RANDOM_ATTEMPTS: usize = 64;
+
+/// An unordered pair of breeds, used to avoid repeats within a session.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Pair(BreedKey, BreedKey);
+
+/// One decided matchup.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Game {
+  pub winner: String,
+  pub loser: String
+}
+
+/// A breed's row in the round-robin table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Standing {
+  pub breed: String,
+  pub played: u32,
+  pub wins: u32,
+  pub losses: u32
+}
+
+/// A round-robin table of every matchup played this session.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Table {
+  pub games: Vec<Game>
+}
+
+impl Pair {
+  pub fn new(a: BreedKey, b: BreedKey) -> Self {
+    if a <= b { Self(a, b) } else { Self(b, a) }
+  }
+}
+
+impl Standing {
+  pub fn win_rate(&self) -> f64 {
+    if self.played == 0 {
+      0.0
+    } else {
+      f64::from(self.wins) / f64::from(self.played)
+    }
+  }
+}
+
+impl Table {
+  pub fn record(
+    &mut self,
+    winner: impl Into<String>,
+    loser: impl Into<String>
+  ) {
+    self.games.push(Game {
+      winner: winner.into(),
+      loser: loser.into()
+    });
+  }
+
+  /// Wins for `a` and `b` in their games against each other.
+  pub fn head_to_head(&self, a: &str, b: &str) -> (u32, u32) {
+    self.games.iter().fold((0, 0), |(wins_a, wins_b), game| {
+      match (game.winner.as_str(), game.loser.as_str()) {
+        (w, l) if w == a && l == b => (wins_a + 1, wins_b),
+        (w, l) if w == b && l == a => (wins_a, wins_b + 1),
+        _ => (wins_a, wins_b)
+      }
+    })
+  }
+
+  /// Every breed that played, by wins, then win rate, then name.
+  pub fn standings(&self) -> Vec<Standing> {
+    let mut rows: BTreeMap<&str, Standing> = BTreeMap::new();
+    for game in &self.games {
+      let winner = standing(&mut rows, &game.winner);
+      winner.played += 1;
+      winner.wins += 1;
+      let loser = standing(&mut rows, &game.loser);
+      loser.played += 1;
+      loser.losses += 1;
+    }
+
+    let mut standings: Vec<Standing> = rows.into_values().collect();
+    standings.sort_by(|a, b| {
+      b.wins
+        .cmp(&a.wins)
+        .then(b.win_rate().total_cmp(&a.win_rate()))
+        .then(a.breed.cmp(&b.breed))
+    });
+    standings
+  }
+
+  pub fn to_json(&self) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&serde_json::json!({
+      "standings": self.standings(),
+      "games": self.games
+    }))?)
+  }
+
+  /// The standings as CSV, one row per breed.
+  pub fn to_csv(&self) -> String {
+    let mut csv = String::from("breed,played,wins,losses,win_rate\n");
+    for s in self.standings() {
+      csv.push_str(&format!(
+        "{},{},{},{},{:.3}\n",
+        csv_field(&s.breed),
+        s.played,
+        s.wins,
+        s.losses,
+        s.win_rate()
+      ));
+    }
+    csv
+  }
+}
+
+// -- Private Helper --
+
+fn standing<'r, 'a>(
+  rows: &'r mut BTreeMap<&'a str, Standing>,
+  breed: &'a str
+) -> &'r mut Standing {
+  rows.entry(breed).or_insert_with(|| Standing {
+    breed: breed.to_string(),
+    played: 0,
+    wins: 0,
+    losses: 0
+  })
+}
+
+// -- Public API --
+
+/// Picks two different breeds whose pair isn't in `seen`.
+///
+/// Returns `None` once every pair has been played.
+pub fn next_pair(
+  breeds: &[BreedKey],
+  seen: &HashSet<Pair>,
+  rng: &mut Rng
+) -> Option<(BreedKey, BreedKey)> {
+  let n = breeds.len();
+  if n < 2 {
+    return None;
+  }
+  let unseen = |a: usize, b: usize| {
+    a != b && !seen.contains(&Pair::new(breeds[a].clone(), breeds[b].clone()))
+  };
+
+  //~@ Random draws are enough until the table fills up
+  for _ in 0..RANDOM_ATTEMPTS {
+    let (a, b) = (rng.below(n), rng.below(n));
+    if unseen(a, b) {
+      return Some((breeds[a].clone(), breeds[b].clone()));
+    }
+  }
+
+  //~@ Then scan from a random start so late pairs aren't always the same
+  let offset = rng.below(n);
+  (0..n)
+    .map(|i| (i + offset) % n)
+    .flat_map(|a| (0..n).map(move |b| (a, b)))
+    .find(|&(a, b)| unseen(a, b))
+    .map(|(a, b)| (breeds[a].clone(), breeds[b].clone()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn key(main: &str, sub: Option<&str>) -> BreedKey {
+    (main.to_string(), sub.map(str::to_string))
+  }
+
+  #[test]
+  fn test_next_pair_avoids_repeats() {
+    let breeds = vec![
+      key("pug", None),
+      key("hound", Some("afghan")),
+      key("akita", None),
+    ];
+    let mut rng = Rng::new(1);
+    let mut seen = HashSet::new();
+
+    for _ in 0..3 {
+      let (a, b) = next_pair(&breeds, &seen, &mut rng).unwrap();
+      assert_ne!(a, b);
+      assert!(seen.insert(Pair::new(a, b)));
+    }
+    assert!(next_pair(&breeds, &seen, &mut rng).is_none());
+    assert!(next_pair(&breeds[..1], &HashSet::new(), &mut rng).is_none());
+  }
+
+  #[test]
+  fn test_table_standings_and_export() {
+    let mut table = Table::default();
+    table.record("Pug", "Akita");
+    table.record("Pug", "Boxer");
+    table.record("Akita", "Boxer");
+    table.record("Akita", "Pug");
+
+    assert_eq!(table.head_to_head("Pug", "Akita"), (1, 1));
+    let names: Vec<_> =
+      table.standings().into_iter().map(|s| s.breed).collect();
+    assert_eq!(names, ["Akita", "Pug", "Boxer"]);
+
+    let csv = table.to_csv();
+    assert!(csv.starts_with("breed,played,wins,losses,win_rate\n"));
+    assert!(csv.contains("Akita,3,2,1,0.667\n"));
+    assert!(table.to_json().unwrap().contains("\"games\""));
+  }
+}
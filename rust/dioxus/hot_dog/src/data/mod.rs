@@ -1,5 +1,18 @@
 //|-> Modules
+pub mod bundle;
+pub mod daily;
 mod dog;
+pub mod favorite;
+pub mod filter;
+pub mod history;
+pub mod image;
+pub mod matchup;
+pub mod prefs;
+pub mod quiz;
+pub mod rating;
+pub mod sync;
+pub mod transfer;
+pub mod user;
 
 //|-> Internal Exports
 mod prelude {
@@ -10,4 +23,8 @@ mod prelude {
 }
 
 //|-> External Exports
-pub use dog::Dog;
+pub use dog::{Dog, DogId};
+pub use favorite::Favorite;
+pub use image::ImageMeta;
+pub use rating::{BreedScore, Vote};
+pub use user::User;
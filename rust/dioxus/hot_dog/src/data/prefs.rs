@@ -0,0 +1,102 @@
+use super::{filter::BreedFilter, prelude::*};
+
+/// Color scheme of the app, matching a `data-theme` in `main.css`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Theme {
+  #[default]
+  Light,
+  Dark,
+  HighContrast
+}
+
+impl Theme {
+  pub const ALL: [Theme; 3] = [Theme::Light, Theme::Dark, Theme::HighContrast];
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Theme::Light => "light",
+      Theme::Dark => "dark",
+      Theme::HighContrast => "high-contrast"
+    }
+  }
+
+  pub fn label(&self) -> &'static str {
+    match self {
+      Theme::Light => "Light",
+      Theme::Dark => "Dark",
+      Theme::HighContrast => "High contrast"
+    }
+  }
+
+  pub fn parse(value: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|theme| theme.as_str() == value)
+  }
+}
+
+/// Per-device settings, saved in the browser or a config file rather than
+/// on the server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+  pub theme: Theme,
+  /// Provider the client-side views fetch from: `dog_ceo`, `random` or a
+  /// custom base URL.
+  pub provider: String,
+  pub filter: BreedFilter,
+  /// Whether the gallery carousel moves on by itself.
+  pub autoplay: bool
+}
+
+impl Default for Preferences {
+  fn default() -> Self {
+    Self {
+      theme: Theme::default(),
+      provider: "dog_ceo".to_string(),
+      filter: BreedFilter::default(),
+      autoplay: true
+    }
+  }
+}
+
+impl Preferences {
+  /// Reads saved preferences, keeping the defaults for anything missing or
+  /// unreadable.
+  pub fn from_json(json: &str) -> Self {
+    serde_json::from_str(json).unwrap_or_else(|e| {
+      warn!("Ignoring unreadable preferences: {e}");
+      Self::default()
+    })
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(self).unwrap_or_default()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::data::filter::BreedKey;
+
+  #[test]
+  fn test_preferences_round_trip() {
+    let prefs = Preferences {
+      theme: Theme::HighContrast,
+      provider: "random".into(),
+      filter: BreedFilter::Only(vec![BreedKey::new("pug", None::<String>)]),
+      autoplay: false
+    };
+    assert_eq!(Preferences::from_json(&prefs.to_json()), prefs);
+    assert!(prefs.to_json().contains(r#""theme":"high-contrast""#));
+  }
+
+  #[test]
+  fn test_missing_fields_keep_their_defaults() {
+    let prefs = Preferences::from_json(r#"{"theme":"dark"}"#);
+    assert_eq!(prefs.theme, Theme::Dark);
+    assert!(prefs.autoplay);
+    assert_eq!(prefs.provider, "dog_ceo");
+    assert_eq!(Preferences::from_json("not json"), Preferences::default());
+  }
+}
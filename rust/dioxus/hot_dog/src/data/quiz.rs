@@ -0,0 +1,305 @@
+use super::prelude::*;
+use crate::{data::matchup::BreedKey, provider::Breed, utils::rng::Rng};
+
+/// Questions in one quiz round.
+pub const ROUNDS: u32 = 10;
+
+/// How hard the multiple-choice answers are to tell apart.
+#[derive(
+  Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+pub enum Difficulty {
+  Easy,
+  #[default]
+  Normal,
+  Hard
+}
+
+/// One photo with its shuffled answer options.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Question {
+  pub photo_url: String,
+  pub answer: BreedKey,
+  pub choices: Vec<BreedKey>
+}
+
+/// Running score for the current round.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Session {
+  pub difficulty: Difficulty,
+  pub score: u32,
+  pub answered: u32,
+  pub correct: u32,
+  pub streak: u32,
+  pub best_streak: u32
+}
+
+/// A finished round in the high-score table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HighScore {
+  pub name: String,
+  pub difficulty: Difficulty,
+  pub score: u32,
+  pub correct: u32,
+  pub best_streak: u32,
+  pub played_at: i64
+}
+
+impl Difficulty {
+  pub const ALL: [Difficulty; 3] =
+    [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Difficulty::Easy => "easy",
+      Difficulty::Normal => "normal",
+      Difficulty::Hard => "hard"
+    }
+  }
+
+  pub fn parse(value: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|d| d.as_str() == value)
+  }
+
+  pub fn label(&self) -> &'static str {
+    match self {
+      Difficulty::Easy => "Easy",
+      Difficulty::Normal => "Normal",
+      Difficulty::Hard => "Hard"
+    }
+  }
+
+  /// Number of options shown, including the right one.
+  pub fn choices(&self) -> usize {
+    match self {
+      Difficulty::Easy => 3,
+      Difficulty::Normal => 4,
+      Difficulty::Hard => 6
+    }
+  }
+
+  /// How many distractors should be look-alikes when the catalog has them.
+  fn similar(&self) -> usize {
+    match self {
+      Difficulty::Easy => 0,
+      Difficulty::Normal => 1,
+      Difficulty::Hard => usize::MAX
+    }
+  }
+
+  /// Points for a correct answer before the streak bonus.
+  pub fn points(&self) -> u32 {
+    match self {
+      Difficulty::Easy => 10,
+      Difficulty::Normal => 20,
+      Difficulty::Hard => 40
+    }
+  }
+}
+
+impl Question {
+  /// Builds a question for `photo_url`, drawing distractors from `catalog`.
+  pub fn new(
+    photo_url: impl Into<String>,
+    answer: BreedKey,
+    catalog: &[BreedKey],
+    difficulty: Difficulty,
+    rng: &mut Rng
+  ) -> Self {
+    let mut choices = distractors(&answer, catalog, difficulty, rng);
+    choices.push(answer.clone());
+    rng.shuffle(&mut choices);
+    Self {
+      photo_url: photo_url.into(),
+      answer,
+      choices
+    }
+  }
+
+  pub fn is_correct(&self, choice: &BreedKey) -> bool {
+    &self.answer == choice
+  }
+}
+
+impl Session {
+  pub fn new(difficulty: Difficulty) -> Self {
+    Self {
+      difficulty,
+      ..Default::default()
+    }
+  }
+
+  /// Scores one answer; every consecutive correct answer adds 10% on top.
+  pub fn answer(&mut self, correct: bool) -> u32 {
+    self.answered += 1;
+    if !correct {
+      self.streak = 0;
+      return 0;
+    }
+    self.correct += 1;
+    self.streak += 1;
+    self.best_streak = self.best_streak.max(self.streak);
+    let points = self.difficulty.points() * (9 + self.streak) / 10;
+    self.score += points;
+    points
+  }
+
+  pub fn is_over(&self) -> bool {
+    self.answered >= ROUNDS
+  }
+}
+
+impl HighScore {
+  /// Whether some run of [`ROUNDS`] answers at this difficulty ends with
+  /// exactly this score, number right and best streak.
+  ///
+  /// Replays every sequence of right and wrong answers, which is cheap at
+  /// ten rounds and leaves no room for a made-up score.
+  pub fn is_possible(&self) -> bool {
+    (0..1u32 << ROUNDS).any(|answers| {
+      let mut session = Session::new(self.difficulty);
+      for round in 0..ROUNDS {
+        session.answer(answers & (1 << round) != 0);
+      }
+      (session.score, session.correct, session.best_streak)
+        == (self.score, self.correct, self.best_streak)
+    })
+  }
+}
+
+/// Display name for a catalog key, e.g. `Afghan Hound`.
+pub fn display_name(key: &BreedKey) -> String {
+  Breed::format_name(&key.0, key.1.as_deref())
+}
+
+/// Breeds that are easy to confuse with `answer`: sub-breeds of the same
+/// main breed, and the main breed itself.
+fn is_similar(answer: &BreedKey, other: &BreedKey) -> bool {
+  answer != other && answer.0 == other.0
+}
+
+/// Picks wrong answers for `answer`, preferring look-alikes as difficulty
+/// rises and topping up with random breeds.
+pub fn distractors(
+  answer: &BreedKey,
+  catalog: &[BreedKey],
+  difficulty: Difficulty,
+  rng: &mut Rng
+) -> Vec<BreedKey> {
+  let wanted = difficulty.choices() - 1;
+
+  let mut similar: Vec<&BreedKey> =
+    catalog.iter().filter(|k| is_similar(answer, k)).collect();
+  let mut others: Vec<&BreedKey> = catalog
+    .iter()
+    .filter(|k| *k != answer && !is_similar(answer, k))
+    .collect();
+  rng.shuffle(&mut similar);
+  rng.shuffle(&mut others);
+
+  //~@ Look-alikes first, then random breeds, then any look-alikes left over
+  let from_similar = difficulty.similar().min(similar.len()).min(wanted);
+  let leftover = similar.split_off(from_similar);
+  similar
+    .into_iter()
+    .chain(others)
+    .chain(leftover)
+    .take(wanted)
+    .cloned()
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn catalog() -> Vec<BreedKey> {
+    let mut keys: Vec<BreedKey> = ["akita", "boxer", "pug", "husky", "beagle"]
+      .into_iter()
+      .map(|main| (main.to_string(), None))
+      .collect();
+    for sub in ["afghan", "basset", "blood", "ibizan"] {
+      keys.push(("hound".to_string(), Some(sub.to_string())));
+    }
+    keys
+  }
+
+  #[test]
+  fn test_distractors_by_difficulty() {
+    let catalog = catalog();
+    let answer = ("hound".to_string(), Some("afghan".to_string()));
+    let mut rng = Rng::new(3);
+
+    let easy = distractors(&answer, &catalog, Difficulty::Easy, &mut rng);
+    assert_eq!(easy.len(), 2);
+    assert!(easy.iter().all(|k| k.0 != "hound"));
+
+    let hard = distractors(&answer, &catalog, Difficulty::Hard, &mut rng);
+    assert_eq!(hard.len(), 5);
+    assert_eq!(hard.iter().filter(|k| k.0 == "hound").count(), 3);
+    assert!(!hard.contains(&answer));
+
+    let question = Question::new(
+      "x.jpg",
+      answer.clone(),
+      &catalog,
+      Difficulty::Normal,
+      &mut rng
+    );
+    assert_eq!(question.choices.len(), 4);
+    assert!(question.choices.iter().any(|k| question.is_correct(k)));
+    assert_eq!(display_name(&answer), "Afghan Hound");
+  }
+
+  #[test]
+  fn test_session_streaks() {
+    let mut session = Session::new(Difficulty::Normal);
+    assert_eq!(session.answer(true), 20);
+    assert_eq!(session.answer(true), 22);
+    assert_eq!(session.answer(false), 0);
+    assert_eq!(session.answer(true), 20);
+
+    assert_eq!(session.score, 62);
+    assert_eq!((session.correct, session.best_streak), (3, 2));
+    assert!(!session.is_over());
+    assert_eq!(Difficulty::parse("hard"), Some(Difficulty::Hard));
+  }
+
+  #[test]
+  fn test_only_possible_scores() {
+    let mut session = Session::new(Difficulty::Hard);
+    for round in 0..ROUNDS {
+      session.answer(round % 3 != 0);
+    }
+    let score = HighScore {
+      name: "rex".into(),
+      difficulty: session.difficulty,
+      score: session.score,
+      correct: session.correct,
+      best_streak: session.best_streak,
+      played_at: 0
+    };
+    assert!(score.is_possible());
+    assert!(
+      !HighScore {
+        score: score.score + 1,
+        ..score.clone()
+      }
+      .is_possible()
+    );
+    assert!(
+      !HighScore {
+        best_streak: score.correct + 1,
+        ..score.clone()
+      }
+      .is_possible()
+    );
+    assert!(
+      !HighScore {
+        difficulty: Difficulty::Easy,
+        ..score
+      }
+      .is_possible()
+    );
+  }
+}
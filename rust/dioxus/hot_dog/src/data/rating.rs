@@ -0,0 +1,159 @@
+use super::prelude::*;
+use crate::data::Dog;
+
+/// Score every breed starts from before its first matchup.
+pub const INITIAL_ELO: f64 = 1000.0;
+
+/// How far a single matchup can move a score.
+pub const K_FACTOR: f64 = 32.0;
+
+/// A hot-or-not decision on one photo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Vote {
+  Like,
+  Skip
+}
+
+/// Accumulated ratings for one breed or sub-breed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BreedScore {
+  pub breed: String,
+  pub sub_breed: Option<String>,
+  pub display_name: String,
+  pub likes: u32,
+  pub skips: u32,
+  pub elo: f64,
+  pub matches: u32
+}
+
+impl Vote {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Vote::Like => "like",
+      Vote::Skip => "skip"
+    }
+  }
+}
+
+impl BreedScore {
+  /// A fresh score for the breed of `dog`.
+  pub fn new(dog: &Dog) -> Self {
+    Self {
+      breed: dog.breed.clone(),
+      sub_breed: dog.sub_breed.clone(),
+      display_name: dog.display_name.clone(),
+      likes: 0,
+      skips: 0,
+      elo: INITIAL_ELO,
+      matches: 0
+    }
+  }
+
+  pub fn votes(&self) -> u32 {
+    self.likes + self.skips
+  }
+
+  /// Share of votes that were likes, or `None` before the first vote.
+  pub fn like_ratio(&self) -> Option<f64> {
+    (self.votes() > 0).then(|| f64::from(self.likes) / f64::from(self.votes()))
+  }
+
+  /// Like ratio with a Laplace prior, so one lucky like doesn't dominate.
+  pub fn preference(&self) -> f64 {
+    f64::from(self.likes + 1) / f64::from(self.votes() + 2)
+  }
+}
+
+/// Probability that a player rated `a` beats one rated `b`.
+pub fn expected(a: f64, b: f64) -> f64 {
+  1.0 / (1.0 + 10f64.powf((b - a) / 400.0))
+}
+
+/// Returns the new `(winner, loser)` ratings after one matchup.
+pub fn elo_update(winner: f64, loser: f64) -> (f64, f64) {
+  let delta = K_FACTOR * (1.0 - expected(winner, loser));
+  (winner + delta, loser - delta)
+}
+
+/// Orders scores for the leaderboard: Elo first, then like ratio.
+pub fn rank(scores: &mut [BreedScore]) {
+  scores.sort_by(|a, b| {
+    b.elo
+      .total_cmp(&a.elo)
+      .then(b.preference().total_cmp(&a.preference()))
+      .then(a.display_name.cmp(&b.display_name))
+  });
+}
+
+/// Picks a breed with probability proportional to its [`preference`], where
+/// `roll` is uniform in `[0, 1)`.
+///
+/// [`preference`]: BreedScore::preference
+pub fn pick_weighted(scores: &[BreedScore], roll: f64) -> Option<&BreedScore> {
+  let total: f64 = scores.iter().map(BreedScore::preference).sum();
+  let mut target = roll * total;
+  for score in scores {
+    target -= score.preference();
+    if target < 0.0 {
+      return Some(score);
+    }
+  }
+  scores.last()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn score(name: &str, likes: u32, skips: u32, elo: f64) -> BreedScore {
+    BreedScore {
+      breed: name.to_lowercase(),
+      sub_breed: None,
+      display_name: name.to_string(),
+      likes,
+      skips,
+      elo,
+      matches: 0
+    }
+  }
+
+  #[test]
+  fn test_elo_update() {
+    assert!((expected(1000.0, 1000.0) - 0.5).abs() < 1e-9);
+
+    let (winner, loser) = elo_update(INITIAL_ELO, INITIAL_ELO);
+    assert!((winner - 1016.0).abs() < 1e-9);
+    assert!((loser - 984.0).abs() < 1e-9);
+
+    //~@ Upsets move scores further than expected wins
+    let (upset, _) = elo_update(900.0, 1100.0);
+    let (favourite, _) = elo_update(1100.0, 900.0);
+    assert!(upset - 900.0 > favourite - 1100.0);
+  }
+
+  #[test]
+  fn test_ratios_and_ranking() {
+    let pug = score("Pug", 3, 1, 1000.0);
+    assert_eq!(pug.like_ratio(), Some(0.75));
+    assert_eq!(score("Akita", 0, 0, 1000.0).like_ratio(), None);
+
+    let mut scores = vec![
+      score("Akita", 0, 4, 1000.0),
+      pug.clone(),
+      score("Boxer", 0, 0, 1050.0),
+    ];
+    rank(&mut scores);
+    let names: Vec<_> =
+      scores.iter().map(|s| s.display_name.as_str()).collect();
+    assert_eq!(names, ["Boxer", "Pug", "Akita"]);
+  }
+
+  #[test]
+  fn test_pick_weighted() {
+    let scores = vec![score("Akita", 0, 8, 1000.0), score("Pug", 8, 0, 1000.0)];
+    assert_eq!(pick_weighted(&scores, 0.0).unwrap().display_name, "Akita");
+    assert_eq!(pick_weighted(&scores, 0.5).unwrap().display_name, "Pug");
+    assert_eq!(pick_weighted(&scores, 0.999).unwrap().display_name, "Pug");
+    assert!(pick_weighted(&[], 0.5).is_none());
+  }
+}
@@ -0,0 +1,186 @@
+use super::prelude::*;
+use crate::data::Favorite;
+use std::collections::{HashMap, VecDeque};
+
+//~@ Events kept per owner for replay; clients further behind start over
+pub const CAPACITY: usize = 256;
+
+//~@ Owners whose events are kept; the least recently active go first
+pub const OWNERS: usize = 1_024;
+
+/// Something that changed in a user's data, as pushed to their other open
+/// clients.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Change {
+  Saved(Box<Favorite>),
+  Deleted(Vec<i64>),
+  Tagged {
+    id: i64,
+    tags: Vec<String>
+  },
+  /// Many favorites changed at once, say after an import or merge.
+  Favorites,
+  /// Votes or matchups moved the user's breed ratings.
+  Ratings,
+  /// The client missed events that are no longer kept and should reload
+  /// everything.
+  Resync
+}
+
+impl Change {
+  pub fn touches_favorites(&self) -> bool {
+    !matches!(self, Change::Ratings)
+  }
+
+  pub fn touches_ratings(&self) -> bool {
+    matches!(self, Change::Ratings | Change::Resync)
+  }
+}
+
+/// A [`Change`] stamped with its place in the server's sequence.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+  pub seq: u64,
+  pub change: Change
+}
+
+/// The latest events of every user, numbered so reconnecting clients can
+/// ask for what they missed.
+///
+/// Each owner keeps their own [`CAPACITY`] events, so a busy user can't
+/// push out everyone else's.
+#[derive(Debug, Default)]
+pub struct Log {
+  last: u64,
+  /// Newest event of any owner forgotten to stay under [`OWNERS`].
+  forgotten: u64,
+  owners: HashMap<i64, Ring>
+}
+
+/// One owner's latest events.
+#[derive(Debug, Default)]
+struct Ring {
+  /// Newest event of the owner dropped to stay under [`CAPACITY`].
+  dropped: u64,
+  events: VecDeque<Event>
+}
+
+impl Log {
+  /// The sequence number of the newest event, 0 before any.
+  pub fn last(&self) -> u64 {
+    self.last
+  }
+
+  /// Records `change` for `owner`, dropping the owner's oldest event when
+  /// full.
+  pub fn push(&mut self, owner: i64, change: Change) -> Event {
+    self.last += 1;
+    let event = Event {
+      seq: self.last,
+      change
+    };
+    let ring = self.owners.entry(owner).or_default();
+    if ring.events.len() == CAPACITY {
+      ring.dropped = ring.events.pop_front().map_or(0, |e| e.seq);
+    }
+    ring.events.push_back(event.clone());
+    if self.owners.len() > OWNERS {
+      self.forget_idlest();
+    }
+    event
+  }
+
+  /// The events of `owner` after `seq`.
+  ///
+  /// Returns a single [`Change::Resync`] if some of them were dropped
+  /// already, or if `seq` comes from before a server restart.
+  pub fn since(&self, owner: i64, seq: u64) -> Vec<Event> {
+    let ring = self.owners.get(&owner);
+    let dropped = ring.map_or(self.forgotten, |ring| ring.dropped);
+    if seq > self.last || seq < dropped {
+      return vec![Event {
+        seq: self.last,
+        change: Change::Resync
+      }];
+    }
+    ring
+      .map(|ring| {
+        ring
+          .events
+          .iter()
+          .filter(|e| e.seq > seq)
+          .cloned()
+          .collect()
+      })
+      .unwrap_or_default()
+  }
+
+  // -- Private Helper --
+
+  /// Forgets the owner whose newest event is the oldest.
+  fn forget_idlest(&mut self) {
+    let idlest = self
+      .owners
+      .iter()
+      .filter_map(|(owner, ring)| Some((*owner, ring.events.back()?.seq)))
+      .min_by_key(|(_, seq)| *seq);
+    if let Some((owner, seq)) = idlest {
+      self.owners.remove(&owner);
+      self.forgotten = self.forgotten.max(seq);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_replays_only_the_owners_missed_events() {
+    let mut log = Log::default();
+    log.push(1, Change::Favorites);
+    log.push(2, Change::Ratings);
+    log.push(1, Change::Deleted(vec![7]));
+
+    let missed = log.since(1, 1);
+    assert_eq!(missed.len(), 1);
+    assert_eq!(missed[0].seq, 3);
+    assert_eq!(missed[0].change, Change::Deleted(vec![7]));
+    assert!(log.since(1, 3).is_empty());
+    assert!(log.since(3, 0).is_empty());
+  }
+
+  #[test]
+  fn test_clients_too_far_behind_resync() {
+    let mut log = Log::default();
+    for _ in 0..CAPACITY + 10 {
+      log.push(1, Change::Ratings);
+    }
+    let behind = log.since(1, 5);
+    assert_eq!(behind.len(), 1);
+    assert_eq!(behind[0].change, Change::Resync);
+
+    //~@ A sequence from before a restart is ahead of the new log
+    let restarted = Log::default();
+    assert_eq!(restarted.since(1, 42)[0].change, Change::Resync);
+    assert!(restarted.since(1, 0).is_empty());
+  }
+
+  #[test]
+  fn test_busy_owners_keep_to_their_own_ring() {
+    let mut log = Log::default();
+    log.push(1, Change::Favorites);
+    for _ in 0..CAPACITY * 2 {
+      log.push(2, Change::Ratings);
+    }
+    assert_eq!(log.since(1, 0)[0].change, Change::Favorites);
+    assert_eq!(log.since(2, 0)[0].change, Change::Resync);
+
+    //~@ Past the owner limit the idlest owner is forgotten and resyncs
+    for owner in 3..OWNERS as i64 + 3 {
+      log.push(owner, Change::Ratings);
+    }
+    assert_eq!(log.since(1, 0)[0].change, Change::Resync);
+    assert_eq!(log.since(3, 0).len(), 1);
+  }
+}
@@ -0,0 +1,461 @@
+use super::prelude::*;
+use crate::data::{Dog, Favorite, favorite::parse_tags};
+
+const CSV_HEADER: &str =
+  "photo_url,breed,sub_breed,display_name,reference_url,tags,saved_at";
+
+//~@ The HTML gallery carries its records here so it can be imported again
+const HTML_DATA_OPEN: &str =
+  r#"<script type="application/json" id="hot-dog-favorites">"#;
+const HTML_DATA_CLOSE: &str = "</script>";
+
+/// File formats favorites can be exported to and imported from.
+#[derive(
+  Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum Format {
+  #[default]
+  Json,
+  Csv,
+  /// One photo URL per line, like the original `dog.txt`.
+  Urls,
+  /// A self-contained page that also embeds the records as JSON.
+  Html
+}
+
+/// A favorite as it leaves or enters the app, without server-side ids.
+///
+/// On import only `photo_url`, `tags` and `saved_at` are trusted; the breed
+/// fields are derived from the URL again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+  pub photo_url: String,
+  #[serde(default)]
+  pub breed: String,
+  #[serde(default)]
+  pub sub_breed: Option<String>,
+  #[serde(default)]
+  pub display_name: String,
+  #[serde(default)]
+  pub reference_url: String,
+  #[serde(default)]
+  pub tags: Vec<String>,
+  #[serde(default)]
+  pub saved_at: Option<i64>
+}
+
+/// A parsed record with its position in the input.
+pub type Numbered = (usize, Record);
+
+/// An input entry that couldn't be imported, and why.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rejected {
+  /// 1-based line in CSV and URL lists, entry number in JSON and HTML.
+  pub position: usize,
+  pub input: String,
+  pub reason: String
+}
+
+/// What an import did, or would do on a dry run.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportReport {
+  pub dry_run: bool,
+  /// Photos that weren't saved yet.
+  pub added: Vec<Record>,
+  /// Saved photos that gain tags, listed with the tags they'd end up with.
+  pub retagged: Vec<Record>,
+  pub unchanged: usize,
+  pub rejected: Vec<Rejected>
+}
+
+impl Format {
+  pub const ALL: [Format; 4] =
+    [Format::Json, Format::Csv, Format::Urls, Format::Html];
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Format::Json => "json",
+      Format::Csv => "csv",
+      Format::Urls => "urls",
+      Format::Html => "html"
+    }
+  }
+
+  pub fn parse(s: &str) -> Option<Format> {
+    Self::ALL.into_iter().find(|f| f.as_str() == s)
+  }
+
+  pub fn label(&self) -> &'static str {
+    match self {
+      Format::Json => "JSON",
+      Format::Csv => "CSV",
+      Format::Urls => "URL list",
+      Format::Html => "HTML gallery"
+    }
+  }
+
+  pub fn extension(&self) -> &'static str {
+    match self {
+      Format::Json => "json",
+      Format::Csv => "csv",
+      Format::Urls => "txt",
+      Format::Html => "html"
+    }
+  }
+
+  pub fn mime(&self) -> &'static str {
+    match self {
+      Format::Json => "application/json",
+      Format::Csv => "text/csv",
+      Format::Urls => "text/plain",
+      Format::Html => "text/html"
+    }
+  }
+
+  /// Recognizes an exported file from its contents.
+  pub fn detect(input: &str) -> Format {
+    let start = input.trim_start();
+    if start.starts_with('[') {
+      Format::Json
+    } else if start.starts_with('<') {
+      Format::Html
+    } else if start.starts_with("photo_url,") {
+      Format::Csv
+    } else {
+      Format::Urls
+    }
+  }
+}
+
+impl Record {
+  /// A bare record, as read from a URL list.
+  pub fn from_url(url: &str) -> Self {
+    Self {
+      photo_url: url.to_string(),
+      breed: String::new(),
+      sub_breed: None,
+      display_name: String::new(),
+      reference_url: String::new(),
+      tags: Vec::new(),
+      saved_at: None
+    }
+  }
+
+  /// Takes the breed fields from `dog` in place of the untrusted ones read
+  /// from the input, keeping the tags and save time.
+  pub fn with_dog(self, dog: Dog) -> Self {
+    Self {
+      photo_url: dog.photo_url,
+      breed: dog.breed,
+      sub_breed: dog.sub_breed,
+      display_name: dog.display_name,
+      reference_url: dog.reference_url,
+      ..self
+    }
+  }
+}
+
+impl From<&Favorite> for Record {
+  fn from(favorite: &Favorite) -> Self {
+    let dog = &favorite.dog;
+    Self {
+      photo_url: dog.photo_url.clone(),
+      breed: dog.breed.clone(),
+      sub_breed: dog.sub_breed.clone(),
+      display_name: dog.display_name.clone(),
+      reference_url: dog.reference_url.clone(),
+      tags: favorite.tags.clone(),
+      saved_at: Some(favorite.saved_at)
+    }
+  }
+}
+
+// -- Public API --
+
+/// Renders `records` in `format`.
+pub fn export(records: &[Record], format: Format) -> String {
+  match format {
+    Format::Json => serde_json::to_string_pretty(records).unwrap_or_default(),
+    Format::Csv => {
+      let mut csv = format!("{CSV_HEADER}\n");
+      for r in records {
+        let row = [
+          csv_field(&r.photo_url),
+          csv_field(&r.breed),
+          csv_field(r.sub_breed.as_deref().unwrap_or_default()),
+          csv_field(&r.display_name),
+          csv_field(&r.reference_url),
+          csv_field(&r.tags.join(", ")),
+          r.saved_at.map(|t| t.to_string()).unwrap_or_default()
+        ];
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+      }
+      csv
+    }
+    Format::Urls => records
+      .iter()
+      .map(|r| format!("{}\n", r.photo_url))
+      .collect(),
+    Format::Html => html_gallery(records)
+  }
+}
+
+/// Reads records back from any export format, each with its position for
+/// error reports.
+///
+/// Lines that can't be read are rejected individually; only a JSON document
+/// that doesn't parse at all fails the whole import.
+pub fn parse(
+  input: &str,
+  format: Format
+) -> Result<(Vec<Numbered>, Vec<Rejected>)> {
+  let numbered = |records: Vec<Record>| (1..).zip(records).collect();
+  match format {
+    Format::Json => Ok((numbered(serde_json::from_str(input)?), Vec::new())),
+    Format::Html => {
+      let json = input
+        .split_once(HTML_DATA_OPEN)
+        .and_then(|(_, rest)| rest.split_once(HTML_DATA_CLOSE))
+        .map(|(json, _)| json)
+        .ok_or_else(|| Error::NotFound("favorites data in the HTML".into()))?;
+      Ok((numbered(serde_json::from_str(json)?), Vec::new()))
+    }
+    Format::Urls => Ok((
+      lines(input)
+        .map(|(line, url)| (line, Record::from_url(url)))
+        .collect(),
+      Vec::new()
+    )),
+    Format::Csv => {
+      let (mut records, mut rejected) = (Vec::new(), Vec::new());
+      for (line, input) in lines(input) {
+        if input.starts_with("photo_url,") {
+          continue;
+        }
+        match csv_row(input) {
+          Ok(record) => records.push((line, record)),
+          Err(reason) => rejected.push(Rejected {
+            position: line,
+            input: input.to_string(),
+            reason
+          })
+        }
+      }
+      Ok((records, rejected))
+    }
+  }
+}
+
+/// Compares incoming records against what is already saved.
+///
+/// Records for the same photo are folded together, collecting their tags.
+pub fn plan(existing: &[Favorite], incoming: Vec<Record>) -> ImportReport {
+  let mut merged: Vec<Record> = Vec::new();
+  for record in incoming {
+    match merged.iter_mut().find(|r| r.photo_url == record.photo_url) {
+      Some(known) => union_tags(&mut known.tags, &record.tags),
+      None => merged.push(record)
+    }
+  }
+
+  let mut report = ImportReport::default();
+  for mut record in merged {
+    match existing
+      .iter()
+      .find(|f| f.dog.photo_url == record.photo_url)
+    {
+      None => report.added.push(record),
+      Some(saved) if record.tags.iter().all(|t| saved.has_tag(t)) =>
+        report.unchanged += 1,
+      Some(saved) => {
+        let mut tags = saved.tags.clone();
+        union_tags(&mut tags, &record.tags);
+        record.tags = tags;
+        report.retagged.push(record);
+      }
+    }
+  }
+  report
+}
+
+// -- Private Helper --
+
+/// Non-blank, trimmed lines with their 1-based line numbers.
+fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+  input
+    .lines()
+    .enumerate()
+    .map(|(i, line)| (i + 1, line.trim()))
+    .filter(|(_, line)| !line.is_empty())
+}
+
+fn csv_row(line: &str) -> std::result::Result<Record, String> {
+  let fields = csv_record(line).ok_or("unterminated quote")?;
+  let [photo_url, _, _, _, _, tags, saved_at] = fields.as_slice() else {
+    return Err(format!("expected 7 columns, found {}", fields.len()));
+  };
+  let saved_at = match saved_at.trim() {
+    "" => None,
+    t => Some(t.parse().map_err(|_| format!("invalid saved_at: {t}"))?)
+  };
+  Ok(Record {
+    tags: parse_tags(tags),
+    saved_at,
+    ..Record::from_url(photo_url.trim())
+  })
+}
+
+fn union_tags(tags: &mut Vec<String>, more: &[String]) {
+  for tag in more {
+    if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+      tags.push(tag.clone());
+    }
+  }
+}
+
+fn html_gallery(records: &[Record]) -> String {
+  let mut figures = String::new();
+  for r in records {
+    let tags: String = r
+      .tags
+      .iter()
+      .map(|t| format!(" #{}", html_escape(t)))
+      .collect();
+    figures.push_str(&format!(
+      r#"<figure><a href="{reference}"><img src="{photo}" alt="{name}" loading="lazy"></a><figcaption>{name}<small>{tags}</small></figcaption></figure>
+"#,
+      reference = html_escape(&r.reference_url),
+      photo = html_escape(&r.photo_url),
+      name = html_escape(&r.display_name),
+    ));
+  }
+
+  //~@ `</` can't appear inside the script block, so escape the slash
+  let data = serde_json::to_string(records)
+    .unwrap_or_default()
+    .replace("</", "<\\/");
+  format!(
+    r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Favorite dogs</title>
+<style>
+body {{ font-family: sans-serif; margin: 2rem; background: #f9f9f9; }}
+main {{ column-width: 220px; column-gap: 1rem; }}
+figure {{ break-inside: avoid; margin: 0 0 1rem; background: white; border-radius: 8px; overflow: hidden; }}
+img {{ display: block; width: 100%; }}
+figcaption {{ padding: 0.4rem 0.6rem; }}
+small {{ color: #777; }}
+</style>
+</head>
+<body>
+<h1>Favorite dogs</h1>
+<main>
+{figures}</main>
+{HTML_DATA_OPEN}{data}{HTML_DATA_CLOSE}
+</body>
+</html>
+"#
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn favorite(url: &str, tags: &[&str]) -> Favorite {
+    Favorite {
+      id: 1,
+      dog: Dog {
+        photo_url: url.into(),
+        breed: "hound".into(),
+        sub_breed: Some("afghan".into()),
+        display_name: "Afghan \"Hound\"".into(),
+        reference_url: "https://dog.ceo/api/breed/hound/afghan".into()
+      },
+      tags: tags.iter().map(|t| t.to_string()).collect(),
+      saved_at: 42,
+      meta: None
+    }
+  }
+
+  #[test]
+  fn test_every_format_round_trips() {
+    let records: Vec<Record> =
+      [favorite("https://a/1.jpg", &["fluffy", "big"])]
+        .iter()
+        .map(Record::from)
+        .collect();
+    for format in Format::ALL {
+      let exported = export(&records, format);
+      assert_eq!(Format::detect(&exported), format);
+
+      let (parsed, rejected) = parse(&exported, format).unwrap();
+      assert!(rejected.is_empty());
+      let (position, record) = &parsed[0];
+      assert_eq!(record.photo_url, "https://a/1.jpg");
+      if format != Format::Urls {
+        assert_eq!(record.tags, vec!["fluffy", "big"]);
+        assert_eq!(record.saved_at, Some(42));
+      }
+      if format == Format::Csv {
+        assert_eq!(*position, 2);
+      }
+    }
+  }
+
+  #[test]
+  fn test_csv_rejects_bad_rows() {
+    let input = format!("{CSV_HEADER}\nhttps://a/1.jpg,,,,,,x\n\"open\n");
+    let (records, rejected) = parse(&input, Format::Csv).unwrap();
+    assert!(records.is_empty());
+    assert_eq!(rejected.len(), 2);
+    assert_eq!(rejected[0].position, 2);
+  }
+
+  #[test]
+  fn test_plan_diffs_against_saved() {
+    let existing = [
+      favorite("https://a/1.jpg", &["fluffy"]),
+      favorite("https://a/2.jpg", &[])
+    ];
+    let incoming = vec![
+      Record::from_url("https://a/1.jpg"),
+      Record {
+        tags: vec!["sleepy".into()],
+        ..Record::from_url("https://a/2.jpg")
+      },
+      Record::from_url("https://a/3.jpg"),
+      Record {
+        tags: vec!["tiny".into()],
+        ..Record::from_url("https://a/3.jpg")
+      },
+    ];
+
+    let report = plan(&existing, incoming);
+    assert_eq!(report.unchanged, 1);
+    assert_eq!(report.retagged[0].tags, vec!["sleepy"]);
+    assert_eq!(report.added.len(), 1);
+    assert_eq!(report.added[0].tags, vec!["tiny"]);
+  }
+
+  #[test]
+  fn test_breed_fields_come_from_the_dog() {
+    let dog = favorite("https://a/1.jpg", &[]).dog;
+    let record = Record {
+      display_name: "<b>Not a dog</b>".into(),
+      tags: vec!["fluffy".into()],
+      saved_at: Some(7),
+      ..Record::from_url("https://a/1.jpg")
+    }
+    .with_dog(dog.clone());
+    assert_eq!(record.display_name, dog.display_name);
+    assert_eq!(record.breed, dog.breed);
+    assert_eq!(
+      (record.tags, record.saved_at),
+      (vec!["fluffy".into()], Some(7))
+    );
+  }
+}
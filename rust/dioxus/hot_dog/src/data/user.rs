@@ -0,0 +1,71 @@
+use super::prelude::*;
+
+pub const MIN_PASSWORD: usize = 8;
+pub const MAX_USERNAME: usize = 32;
+
+/// Whoever is behind the current session.
+///
+/// Visitors start out anonymous, without a username, and keep the same id
+/// when they sign up.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct User {
+  pub id: i64,
+  pub username: Option<String>
+}
+
+impl User {
+  pub fn is_anonymous(&self) -> bool {
+    self.username.is_none()
+  }
+
+  pub fn display_name(&self) -> &str {
+    self.username.as_deref().unwrap_or("Guest")
+  }
+}
+
+/// Trims `username` and checks it is 3 to [`MAX_USERNAME`] letters, digits,
+/// `-`, `_` or `.`.
+pub fn validate_username(username: &str) -> Result<String> {
+  let username = username.trim();
+  let allowed =
+    |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.');
+  if !(3..=MAX_USERNAME).contains(&username.len()) {
+    return Err(Error::Auth(format!(
+      "usernames are 3 to {MAX_USERNAME} characters"
+    )));
+  }
+  if !username.chars().all(allowed) {
+    return Err(Error::Auth(
+      "usernames may only use letters, digits, '-', '_' and '.'".into()
+    ));
+  }
+  Ok(username.to_string())
+}
+
+pub fn validate_password(password: &str) -> Result<()> {
+  if password.chars().count() < MIN_PASSWORD {
+    return Err(Error::Auth(format!(
+      "passwords need at least {MIN_PASSWORD} characters"
+    )));
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_validate_username() {
+    assert_eq!(validate_username("  rex_99 ").unwrap(), "rex_99");
+    assert!(validate_username("ab").is_err());
+    assert!(validate_username("no spaces").is_err());
+    assert!(validate_username(&"a".repeat(MAX_USERNAME + 1)).is_err());
+  }
+
+  #[test]
+  fn test_validate_password() {
+    assert!(validate_password("hunter2").is_err());
+    assert!(validate_password("correct horse").is_ok());
+  }
+}
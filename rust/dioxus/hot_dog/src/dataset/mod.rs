@@ -0,0 +1,303 @@
+//|-> Modules
+mod source;
+mod split;
+
+//|-> Internal Exports
+mod prelude {
+  pub use crate::prelude::*;
+  pub use serde::Serialize;
+}
+
+//|-> External Exports
+pub use source::{Item, Source};
+pub use split::{Options, Ratios, Sample, Split, assign, label};
+
+use crate::{analysis, mirror};
+use clap::{Arg, Command, value_parser};
+use futures::{StreamExt, stream};
+use prelude::*;
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf}
+};
+use tokio::fs;
+
+const CSV_HEADER: &str =
+  "path,split,label,breed,sub_breed,display_name,photo_url,sha256";
+
+/// What to export and where.
+#[derive(Debug, Clone)]
+pub struct Config {
+  pub out: PathBuf,
+  pub source: Source,
+  pub options: Options,
+  /// Photos fetched at once.
+  pub concurrency: usize
+}
+
+/// One line of `manifest.csv` and `manifest.jsonl`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Row {
+  pub path: String,
+  pub split: Split,
+  pub label: String,
+  pub breed: String,
+  pub sub_breed: Option<String>,
+  pub display_name: String,
+  pub photo_url: String,
+  pub sha256: String
+}
+
+/// How an export went.
+#[derive(Debug, Default)]
+pub struct Summary {
+  pub rows: Vec<Row>,
+  /// Photos that couldn't be fetched, with the reason.
+  pub failed: Vec<(String, String)>
+}
+
+impl Row {
+  fn new(sample: Sample, sha256: String) -> Self {
+    Self {
+      path: sample.path,
+      split: sample.split,
+      label: sample.label,
+      breed: sample.dog.breed,
+      sub_breed: sample.dog.sub_breed,
+      display_name: sample.dog.display_name,
+      photo_url: sample.dog.photo_url,
+      sha256
+    }
+  }
+
+  fn to_csv(&self) -> String {
+    [
+      self.path.as_str(),
+      self.split.as_str(),
+      self.label.as_str(),
+      self.breed.as_str(),
+      self.sub_breed.as_deref().unwrap_or_default(),
+      self.display_name.as_str(),
+      self.photo_url.as_str(),
+      self.sha256.as_str()
+    ]
+    .map(csv_field)
+    .join(",")
+  }
+}
+
+/// Writes an ImageFolder dataset, `<out>/<split>/<label>/<file>`, plus a
+/// `manifest.csv` and `manifest.jsonl` describing every photo.
+///
+/// Photos from a mirror are copied; the rest are downloaded. A photo that
+/// can't be fetched is left out of the manifest and reported.
+pub async fn run(config: &Config) -> Result<Summary> {
+  let items = config.source.items().await?;
+  let mut local: HashMap<String, PathBuf> = items
+    .iter()
+    .filter_map(|item| Some((item.dog.photo_url.clone(), item.local.clone()?)))
+    .collect();
+  let samples = assign(
+    items.into_iter().map(|item| item.dog).collect(),
+    &config.options
+  );
+  info!(
+    "Exporting {} photos to {}",
+    samples.len(),
+    config.out.display()
+  );
+
+  let mut summary = Summary::default();
+  let mut copies = stream::iter(samples)
+    .map(|sample| {
+      let from = local.remove(&sample.dog.photo_url);
+      async move {
+        let result = export_photo(&config.out, &sample, from.as_deref()).await;
+        (sample, result)
+      }
+    })
+    .buffered(config.concurrency.max(1));
+  while let Some((sample, result)) = copies.next().await {
+    match result {
+      Ok(sha256) => summary.rows.push(Row::new(sample, sha256)),
+      Err(e) => {
+        warn!("Leaving {} out of the dataset: {e}", sample.dog.photo_url);
+        summary.failed.push((sample.dog.photo_url, e.to_string()));
+      }
+    }
+  }
+
+  write_manifests(&config.out, &summary.rows).await?;
+  Ok(summary)
+}
+
+/// The `dataset` subcommand and its arguments.
+pub fn command() -> Command {
+  Command::new("dataset")
+    .about("Export breed-labelled photos as an ImageFolder dataset")
+    .arg(
+      Arg::new("out")
+        .required(true)
+        .value_parser(value_parser!(PathBuf))
+        .help("Folder to write the dataset into")
+    )
+    .arg(
+      Arg::new("from")
+        .long("from")
+        .required(true)
+        .value_parser(|value: &str| {
+          Source::parse(value)
+            .ok_or("expected favorites:<user>, mirror:<dir> or live:<count>")
+        })
+        .help("favorites:<user>, mirror:<dir> or live:<count>")
+    )
+    .arg(
+      Arg::new("split")
+        .long("split")
+        .value_parser(|value: &str| {
+          Ratios::parse(value).ok_or("expected train,val,test weights")
+        })
+        .default_value("0.8,0.1,0.1")
+        .help("train,val,test weights")
+    )
+    .arg(
+      Arg::new("seed")
+        .long("seed")
+        .value_parser(value_parser!(u64))
+        .default_value("0")
+    )
+    .arg(
+      Arg::new("cap")
+        .long("cap")
+        .value_parser(value_parser!(usize))
+        .help("Most photos kept per breed")
+    )
+    .arg(
+      Arg::new("concurrency")
+        .long("concurrency")
+        .short('c')
+        .value_parser(value_parser!(usize))
+        .default_value("8")
+    )
+}
+
+/// Runs `hot_dog dataset ...` if that's what the process was started as,
+/// returning whether it was.
+pub fn run_cli() -> Result<bool> {
+  if std::env::args().nth(1).as_deref() != Some("dataset") {
+    return Ok(false);
+  }
+  let matches = command().get_matches_from(std::env::args().skip(1));
+  let config = Config {
+    out: matches
+      .get_one::<PathBuf>("out")
+      .cloned()
+      .unwrap_or_default(),
+    source: matches
+      .get_one::<Source>("from")
+      .cloned()
+      .ok_or_else(|| Error::Provider("missing --from".into()))?,
+    options: Options {
+      seed: matches.get_one::<u64>("seed").copied().unwrap_or_default(),
+      ratios: matches
+        .get_one::<Ratios>("split")
+        .copied()
+        .unwrap_or_default(),
+      cap: matches.get_one::<usize>("cap").copied()
+    },
+    concurrency: matches
+      .get_one::<usize>("concurrency")
+      .copied()
+      .unwrap_or(8)
+  };
+
+  let summary = tokio::runtime::Runtime::new()?.block_on(run(&config))?;
+  for split in [Split::Train, Split::Val, Split::Test] {
+    let count = summary.rows.iter().filter(|r| r.split == split).count();
+    info!("{}: {count} photos", split.as_str());
+  }
+  for (url, reason) in &summary.failed {
+    warn!("Not exported: {url}: {reason}");
+  }
+  Ok(true)
+}
+
+// -- Private Helper --
+
+/// Copies or downloads the photo of `sample` into place, returning its
+/// SHA-256.
+async fn export_photo(
+  out: &Path,
+  sample: &Sample,
+  from: Option<&Path>
+) -> Result<String> {
+  let bytes = match from {
+    Some(path) => fs::read(path).await?,
+    None => analysis::download(&sample.dog.photo_url).await?
+  };
+  let path = out.join(&sample.path);
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).await?;
+  }
+  fs::write(&path, &bytes).await?;
+  Ok(mirror::sha256(&bytes))
+}
+
+async fn write_manifests(out: &Path, rows: &[Row]) -> Result<()> {
+  fs::create_dir_all(out).await?;
+  let mut csv = String::from(CSV_HEADER);
+  let mut jsonl = String::new();
+  for row in rows {
+    csv.push('\n');
+    csv.push_str(&row.to_csv());
+    jsonl.push_str(&serde_json::to_string(row)?);
+    jsonl.push('\n');
+  }
+  csv.push('\n');
+  fs::write(out.join("manifest.csv"), csv).await?;
+  fs::write(out.join("manifest.jsonl"), jsonl).await?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempfile::tempdir;
+
+  #[tokio::test]
+  async fn test_mirrors_export_as_image_folders() {
+    let mirror = tempdir().unwrap();
+    let breeds = mirror.path().join("images.dog.ceo/breeds");
+    for (breed, count) in [("hound-afghan", 10), ("pug", 2)] {
+      std::fs::create_dir_all(breeds.join(breed)).unwrap();
+      for i in 0..count {
+        std::fs::write(breeds.join(breed).join(format!("{i}.jpg")), "jpg")
+          .unwrap();
+      }
+    }
+
+    let out = tempdir().unwrap();
+    let config = Config {
+      out: out.path().into(),
+      source: Source::Mirror(mirror.path().into()),
+      options: Options::default(),
+      concurrency: 2
+    };
+    let summary = run(&config).await.unwrap();
+    assert_eq!(summary.rows.len(), 12);
+    assert!(summary.failed.is_empty());
+
+    for row in &summary.rows {
+      assert!(out.path().join(&row.path).exists());
+    }
+    let csv = std::fs::read_to_string(out.path().join("manifest.csv")).unwrap();
+    assert_eq!(csv.lines().next(), Some(CSV_HEADER));
+    assert_eq!(csv.lines().count(), 13);
+    assert!(csv.contains(",val,hound-afghan,hound,afghan,Afghan Hound,"));
+    let jsonl =
+      std::fs::read_to_string(out.path().join("manifest.jsonl")).unwrap();
+    let first: serde_json::Value =
+      serde_json::from_str(jsonl.lines().next().unwrap()).unwrap();
+    assert_eq!(first["split"], "train");
+  }
+}
@@ -0,0 +1,151 @@
+use super::prelude::*;
+use crate::{
+  data::Dog,
+  mirror,
+  provider::Provider,
+  storage::{favorites, users, with_db}
+};
+use std::path::{Path, PathBuf};
+
+//~@ dog.ceo serves at most this many random photos per request
+const BATCH: usize = 50;
+
+/// Where the photos of a dataset come from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+  /// Everything a user saved.
+  Favorites { username: String },
+  /// A folder written by the `mirror` command.
+  Mirror(PathBuf),
+  /// `count` random photos fetched from dog.ceo in batches.
+  Live { count: usize }
+}
+
+/// A photo to export, with the file it can be copied from when it's
+/// already on disk.
+#[derive(Debug, Clone)]
+pub struct Item {
+  pub dog: Dog,
+  pub local: Option<PathBuf>
+}
+
+impl Source {
+  /// Reads `favorites:<user>`, `mirror:<dir>` or `live:<count>`.
+  pub fn parse(value: &str) -> Option<Self> {
+    let (kind, arg) = value.split_once(':')?;
+    match kind {
+      "favorites" if !arg.is_empty() => Some(Source::Favorites {
+        username: arg.to_string()
+      }),
+      "mirror" if !arg.is_empty() => Some(Source::Mirror(PathBuf::from(arg))),
+      "live" => arg.parse().ok().map(|count| Source::Live { count }),
+      _ => None
+    }
+  }
+
+  /// Lists every photo this source offers.
+  pub async fn items(&self) -> Result<Vec<Item>> {
+    match self {
+      Source::Favorites { username } => {
+        let user = with_db(|db| users::find_by_name(db, username))?
+          .ok_or_else(|| Error::NotFound(format!("user {username}")))?;
+        let saved = with_db(|db| favorites::list(db, user.id))?;
+        Ok(saved.into_iter().map(|f| remote(f.dog)).collect())
+      }
+      Source::Mirror(root) => mirrored(root),
+      Source::Live { count } => {
+        let mut items = Vec::with_capacity(*count);
+        while items.len() < *count {
+          let batch = BATCH.min(count - items.len());
+          let dogs = Provider::dog_ceo().dogs(batch).await?;
+          if dogs.is_empty() {
+            break;
+          }
+          items.extend(dogs.into_iter().map(remote));
+        }
+        Ok(items)
+      }
+    }
+  }
+}
+
+fn remote(dog: Dog) -> Item {
+  Item { dog, local: None }
+}
+
+/// Every photo under a mirror's `images.dog.ceo/breeds` folder, labelled
+/// from its URL like any other dog.ceo photo.
+fn mirrored(root: &Path) -> Result<Vec<Item>> {
+  const PHOTOS: &str = "https://images.dog.ceo/breeds";
+  let breeds = mirror::local_path(root, PHOTOS)
+    .ok_or_else(|| Error::Url(PHOTOS.to_string()))?;
+
+  let mut items = Vec::new();
+  for breed in std::fs::read_dir(&breeds)? {
+    let breed = breed?;
+    if !breed.file_type()?.is_dir() {
+      continue;
+    }
+    for photo in std::fs::read_dir(breed.path())? {
+      let photo = photo?;
+      let (Some(dir), Some(file)) = (
+        breed.file_name().to_str().map(str::to_string),
+        photo.file_name().to_str().map(str::to_string)
+      ) else {
+        continue;
+      };
+      if file.ends_with(".part") {
+        continue;
+      }
+      match favorites::dog_from_url(&format!("{PHOTOS}/{dir}/{file}")) {
+        Ok(dog) => items.push(Item {
+          dog,
+          local: Some(photo.path())
+        }),
+        Err(e) => warn!("Skipping {}: {e}", photo.path().display())
+      }
+    }
+  }
+  Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempfile::tempdir;
+
+  #[test]
+  fn test_sources_parse() {
+    assert_eq!(
+      Source::parse("favorites:rex"),
+      Some(Source::Favorites {
+        username: "rex".into()
+      })
+    );
+    assert_eq!(
+      Source::parse("mirror:./mirror"),
+      Some(Source::Mirror("./mirror".into()))
+    );
+    assert_eq!(Source::parse("live:200"), Some(Source::Live { count: 200 }));
+    assert_eq!(Source::parse("live:lots"), None);
+    assert_eq!(Source::parse("favorites:"), None);
+    assert_eq!(Source::parse("mirror"), None);
+  }
+
+  #[tokio::test]
+  async fn test_mirrors_are_labelled_from_their_layout() {
+    let dir = tempdir().unwrap();
+    let breed = dir.path().join("images.dog.ceo/breeds/hound-afghan");
+    std::fs::create_dir_all(&breed).unwrap();
+    std::fs::write(breed.join("1.jpg"), "jpg").unwrap();
+    std::fs::write(breed.join("2.jpg.part"), "half").unwrap();
+
+    let items = Source::Mirror(dir.path().into()).items().await.unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].dog.display_name, "Afghan Hound");
+    assert_eq!(
+      items[0].local.as_deref(),
+      Some(breed.join("1.jpg").as_path())
+    );
+  }
+}
@@ -0,0 +1,277 @@
+use super::prelude::*;
+use crate::data::Dog;
+use std::collections::BTreeMap;
+
+/// The subsets of an ImageFolder dataset.
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Split {
+  Train,
+  Val,
+  Test
+}
+
+impl Split {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Split::Train => "train",
+      Split::Val => "val",
+      Split::Test => "test"
+    }
+  }
+}
+
+/// Share of every class held out for validation and testing; training
+/// keeps the rest.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ratios {
+  pub val: f64,
+  pub test: f64
+}
+
+impl Default for Ratios {
+  fn default() -> Self {
+    Self {
+      val: 0.1,
+      test: 0.1
+    }
+  }
+}
+
+impl Ratios {
+  /// Reads `train,val,test` weights such as `0.8,0.1,0.1` or `8,1,1`.
+  pub fn parse(value: &str) -> Option<Self> {
+    let weights: Vec<f64> = value
+      .split(',')
+      .map(|w| w.trim().parse().ok())
+      .collect::<Option<_>>()?;
+    let [train, val, test] = weights.as_slice() else {
+      return None;
+    };
+    let total = train + val + test;
+    let valid = [*train, *val, *test]
+      .iter()
+      .all(|w| w.is_finite() && *w >= 0.0);
+    (valid && total > 0.0).then(|| Self {
+      val: val / total,
+      test: test / total
+    })
+  }
+}
+
+/// How photos are spread over the splits.
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+  /// The same seed and photos always give the same splits.
+  pub seed: u64,
+  pub ratios: Ratios,
+  /// Most photos kept per class, dropping the rest after shuffling.
+  pub cap: Option<usize>
+}
+
+/// One labelled photo and where it goes in the dataset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+  pub split: Split,
+  pub label: String,
+  /// Path inside the dataset, `<split>/<label>/<file>`.
+  pub path: String,
+  pub dog: Dog
+}
+
+/// The class of `dog`, named like dog.ceo's breed folders (`hound-afghan`).
+pub fn label(dog: &Dog) -> String {
+  let label = match &dog.sub_breed {
+    Some(sub) => format!("{}-{sub}", dog.breed),
+    None => dog.breed.clone()
+  };
+  let label = sanitize(&label);
+  if label.trim_matches('.').is_empty() {
+    "unknown".to_string()
+  } else {
+    label
+  }
+}
+
+/// Spreads `dogs` over the splits, stratified by [`label`].
+///
+/// Each class is shuffled on its own stream of the seed, so adding a breed
+/// doesn't reshuffle the others. Any class with photos keeps at least one
+/// for training.
+pub fn assign(dogs: Vec<Dog>, options: &Options) -> Vec<Sample> {
+  let mut classes: BTreeMap<String, Vec<Dog>> = BTreeMap::new();
+  for dog in dogs {
+    classes.entry(label(&dog)).or_default().push(dog);
+  }
+
+  let mut samples = Vec::new();
+  for (label, mut dogs) in classes {
+    dogs.sort_by(|a, b| a.photo_url.cmp(&b.photo_url));
+    dogs.dedup_by(|a, b| a.photo_url == b.photo_url);
+    rng::Rng::keyed(options.seed, &label).shuffle(&mut dogs);
+    if let Some(cap) = options.cap {
+      dogs.truncate(cap);
+    }
+
+    let (test, val) = held_out(dogs.len(), options.ratios);
+    let mut files = Vec::with_capacity(dogs.len());
+    for (i, dog) in dogs.into_iter().enumerate() {
+      let split = match i {
+        i if i < test => Split::Test,
+        i if i < test + val => Split::Val,
+        _ => Split::Train
+      };
+      let file = unique(&mut files, file_name(&dog));
+      samples.push(Sample {
+        split,
+        path: format!("{}/{label}/{file}", split.as_str()),
+        label: label.clone(),
+        dog
+      });
+    }
+  }
+  samples.sort_by(|a, b| (a.split, &a.path).cmp(&(b.split, &b.path)));
+  samples
+}
+
+// -- Private Helper --
+
+/// How many of `n` photos go to testing and validation.
+fn held_out(n: usize, ratios: Ratios) -> (usize, usize) {
+  let mut test = (n as f64 * ratios.test).round() as usize;
+  let mut val = (n as f64 * ratios.val).round() as usize;
+  while n > 0 && test + val >= n {
+    if val > 0 {
+      val -= 1;
+    } else {
+      test -= 1;
+    }
+  }
+  (test, val)
+}
+
+/// The last path segment of the photo URL, safe to use as a file name.
+fn file_name(dog: &Dog) -> String {
+  let path = dog.photo_url.split(['?', '#']).next().unwrap_or_default();
+  let name = sanitize(path.rsplit('/').next().unwrap_or_default());
+  if name.trim_matches(['.', '_']).is_empty() {
+    "photo.jpg".to_string()
+  } else {
+    name
+  }
+}
+
+/// `name`, suffixed with a counter if a photo in the class already took it.
+fn unique(taken: &mut Vec<String>, name: String) -> String {
+  let mut candidate = name.clone();
+  let mut n = 1;
+  while taken.contains(&candidate) {
+    candidate = match name.rsplit_once('.') {
+      Some((stem, ext)) => format!("{stem}-{n}.{ext}"),
+      None => format!("{name}-{n}")
+    };
+    n += 1;
+  }
+  taken.push(candidate.clone());
+  candidate
+}
+
+fn sanitize(name: &str) -> String {
+  name
+    .chars()
+    .map(|c| match c {
+      'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c,
+      _ => '_'
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::storage::favorites::dog_from_url;
+
+  fn dogs(breed: &str, count: usize) -> Vec<Dog> {
+    (0..count)
+      .map(|i| {
+        dog_from_url(&format!("https://images.dog.ceo/breeds/{breed}/{i}.jpg"))
+          .unwrap()
+      })
+      .collect()
+  }
+
+  fn count(samples: &[Sample], label: &str, split: Split) -> usize {
+    samples
+      .iter()
+      .filter(|s| s.label == label && s.split == split)
+      .count()
+  }
+
+  #[test]
+  fn test_splits_are_stratified_and_capped() {
+    let mut all = dogs("hound-afghan", 20);
+    all.extend(dogs("pug", 3));
+    let options = Options {
+      seed: 7,
+      cap: Some(10),
+      ..Options::default()
+    };
+    let samples = assign(all, &options);
+
+    assert_eq!(count(&samples, "hound-afghan", Split::Test), 1);
+    assert_eq!(count(&samples, "hound-afghan", Split::Val), 1);
+    assert_eq!(count(&samples, "hound-afghan", Split::Train), 8);
+    assert_eq!(count(&samples, "pug", Split::Train), 3);
+    assert!(
+      samples
+        .iter()
+        .any(|s| s.path.starts_with("train/hound-afghan/")
+          && s.path.ends_with(".jpg"))
+    );
+  }
+
+  #[test]
+  fn test_seeds_decide_the_split() {
+    let options = |seed| Options {
+      seed,
+      ratios: Ratios::parse("1,1,1").unwrap(),
+      cap: None
+    };
+    let first = assign(dogs("pug", 30), &options(1));
+    assert_eq!(first, assign(dogs("pug", 30), &options(1)));
+    assert_ne!(first, assign(dogs("pug", 30), &options(2)));
+
+    //~@ Other classes don't move a class around
+    let mut mixed = dogs("pug", 30);
+    mixed.extend(dogs("hound-afghan", 5));
+    let pugs: Vec<Sample> = assign(mixed, &options(1))
+      .into_iter()
+      .filter(|s| s.label == "pug")
+      .collect();
+    assert_eq!(first, pugs);
+  }
+
+  #[test]
+  fn test_ratios_are_normalized() {
+    assert_eq!(
+      Ratios::parse("8, 1, 1"),
+      Some(Ratios {
+        val: 0.1,
+        test: 0.1
+      })
+    );
+    assert_eq!(Ratios::parse("1,1"), None);
+    assert_eq!(Ratios::parse("0,0,0"), None);
+    assert_eq!(Ratios::parse("1,-1,1"), None);
+  }
+
+  #[test]
+  fn test_file_names_stay_unique() {
+    let mut taken = Vec::new();
+    assert_eq!(unique(&mut taken, "a.jpg".into()), "a.jpg");
+    assert_eq!(unique(&mut taken, "a.jpg".into()), "a-1.jpg");
+    assert_eq!(unique(&mut taken, "a.jpg".into()), "a-2.jpg");
+  }
+}
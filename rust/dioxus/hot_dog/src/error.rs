@@ -22,7 +22,71 @@ pub enum Error {
   UnrecognizedBreed(String),
 
   #[error("Provider-specific issue: {0}")]
-  Provider(String)
+  Provider(String),
+
+  #[error("Logging setup failed: {0}")]
+  Logging(String),
+
+  #[cfg(feature = "server")]
+  #[error("Database operation failed: {0}")]
+  Database(#[from] rusqlite::Error),
+
+  #[cfg(feature = "server")]
+  #[error("Image processing failed: {0}")]
+  Image(String),
+
+  #[cfg(feature = "server")]
+  #[error("Archive operation failed: {0}")]
+  Archive(String),
+
+  #[error("Not found: {0}")]
+  NotFound(String),
+
+  #[error("Authentication failed: {0}")]
+  Auth(String),
+
+  #[error("Invalid input: {0}")]
+  Invalid(String)
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(feature = "server")]
+impl From<image::ImageError> for Error {
+  fn from(e: image::ImageError) -> Self {
+    Error::Image(e.to_string())
+  }
+}
+
+#[cfg(feature = "server")]
+impl From<zip::result::ZipError> for Error {
+  fn from(e: zip::result::ZipError) -> Self {
+    Error::Archive(e.to_string())
+  }
+}
+
+impl Error {
+  /// A stable, label-friendly name for the variant, used in metrics.
+  pub fn kind(&self) -> &'static str {
+    match self {
+      Error::Network(_) => "network",
+      Error::EmptyResponse => "empty_response",
+      Error::Filesystem(_) => "filesystem",
+      Error::Json(_) => "json",
+      Error::Url(_) => "url",
+      Error::BreedFormat(_) => "breed_format",
+      Error::UnrecognizedBreed(_) => "unrecognized_breed",
+      Error::Provider(_) => "provider",
+      Error::Logging(_) => "logging",
+      #[cfg(feature = "server")]
+      Error::Database(_) => "database",
+      #[cfg(feature = "server")]
+      Error::Image(_) => "image",
+      #[cfg(feature = "server")]
+      Error::Archive(_) => "archive",
+      Error::NotFound(_) => "not_found",
+      Error::Auth(_) => "auth",
+      Error::Invalid(_) => "invalid"
+    }
+  }
+}
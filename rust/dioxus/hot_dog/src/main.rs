@@ -1,17 +1,33 @@
 //|-> Modules
+#[cfg(feature = "server")]
+mod analysis;
+mod api;
+#[cfg(feature = "server")]
+mod auth;
 mod data;
+#[cfg(feature = "server")]
+mod dataset;
 mod error;
+#[cfg(feature = "server")]
+mod mirror;
 mod prelude;
 mod provider;
+#[cfg(feature = "server")]
+mod server;
+#[cfg(feature = "server")]
+mod storage;
 mod utils;
-// mod views;
+mod views;
 
 use prelude::*;
 
-#[tokio::main]
-async fn main() -> Result<()> {
-  log::init();
-  // views::launch();
+fn main() -> Result<()> {
+  log::init()?;
+  #[cfg(feature = "server")]
+  if mirror::run_cli()? || dataset::run_cli()? {
+    return Ok(());
+  }
+  views::launch();
 
   Ok(())
 }
@@ -0,0 +1,115 @@
+use super::prelude::*;
+use sha2::{Digest, Sha256};
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf}
+};
+use tokio::{fs, io::AsyncWriteExt};
+
+/// Name of the checksum list at the mirror root, readable by `sha256sum -c`.
+pub const FILE_NAME: &str = "SHA256SUMS";
+
+/// SHA-256 digest of `bytes` as lowercase hex.
+pub fn sha256(bytes: &[u8]) -> String {
+  Sha256::digest(bytes)
+    .iter()
+    .map(|b| format!("{b:02x}"))
+    .collect()
+}
+
+/// Parses one `<hex>  <path>` line as written by `sha256sum`.
+pub fn parse_line(line: &str) -> Option<(String, String)> {
+  let (digest, path) = line.split_once("  ")?;
+  let valid =
+    digest.len() == 64 && digest.bytes().all(|b| b.is_ascii_hexdigit());
+  (valid && !path.is_empty())
+    .then(|| (path.to_string(), digest.to_ascii_lowercase()))
+}
+
+/// The checksums of every file already mirrored, appended to as downloads
+/// finish so an interrupted run can pick up where it stopped.
+#[derive(Debug, Default)]
+pub struct Checksums {
+  path: PathBuf,
+  known: HashMap<String, String>
+}
+
+impl Checksums {
+  /// Loads the list under `root`, starting empty if there is none yet.
+  pub async fn load(root: &Path) -> Result<Self> {
+    let path = root.join(FILE_NAME);
+    let known = match fs::read_to_string(&path).await {
+      Ok(content) => content.lines().filter_map(parse_line).collect(),
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+      Err(e) => return Err(e.into())
+    };
+    Ok(Self { path, known })
+  }
+
+  /// The recorded digest of `relative`, if it was mirrored before.
+  pub fn get(&self, relative: &str) -> Option<&str> {
+    self.known.get(relative).map(String::as_str)
+  }
+
+  /// Records the digest of `relative`, persisting it right away.
+  ///
+  /// A file that changed is appended again; the last line wins on load.
+  pub async fn record(&mut self, relative: &str, digest: &str) -> Result<()> {
+    if self.get(relative) == Some(digest) {
+      return Ok(());
+    }
+    let mut file = fs::OpenOptions::new()
+      .create(true)
+      .append(true)
+      .open(&self.path)
+      .await?;
+    file
+      .write_all(format!("{digest}  {relative}\n").as_bytes())
+      .await?;
+    self.known.insert(relative.to_string(), digest.to_string());
+    Ok(())
+  }
+
+  pub fn len(&self) -> usize {
+    self.known.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.known.is_empty()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempfile::tempdir;
+
+  const EMPTY: &str =
+    "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+  #[test]
+  fn test_lines_follow_sha256sum() {
+    assert_eq!(sha256(b""), EMPTY);
+    assert_eq!(
+      parse_line(&format!("{EMPTY}  images.dog.ceo/breeds/a.jpg")),
+      Some(("images.dog.ceo/breeds/a.jpg".into(), EMPTY.into()))
+    );
+    assert_eq!(parse_line("abc  a.jpg"), None);
+    assert_eq!(parse_line(EMPTY), None);
+  }
+
+  #[tokio::test]
+  async fn test_records_survive_a_reload() {
+    let dir = tempdir().unwrap();
+    let mut sums = Checksums::load(dir.path()).await.unwrap();
+    assert!(sums.is_empty());
+    sums.record("a.jpg", EMPTY).await.unwrap();
+    sums.record("a.jpg", EMPTY).await.unwrap();
+
+    let reloaded = Checksums::load(dir.path()).await.unwrap();
+    assert_eq!(reloaded.len(), 1);
+    assert_eq!(reloaded.get("a.jpg"), Some(EMPTY));
+    let content = std::fs::read_to_string(dir.path().join(FILE_NAME)).unwrap();
+    assert_eq!(content.lines().count(), 1);
+  }
+}
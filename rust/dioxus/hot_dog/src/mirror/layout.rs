@@ -0,0 +1,96 @@
+use super::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// Where `url` is stored under `root`: `<root>/<host>/<path>`, so a static
+/// file server rooted at `<root>/<host>` answers the same URLs as the
+/// original host.
+///
+/// Query strings and fragments are dropped. URLs whose path could step
+/// outside the tree are refused.
+pub fn local_path(root: &Path, url: &str) -> Option<PathBuf> {
+  let host = proxy::host(url)?;
+  let rest = url.split_once("://")?.1;
+  let path = rest.split_once('/')?.1;
+  let path = path.split(['?', '#']).next()?;
+
+  let mut local = root.join(host);
+  for segment in path.split('/') {
+    if segment.is_empty() || segment == "." || segment == ".." {
+      return None;
+    }
+    if segment.contains('\\') {
+      return None;
+    }
+    local.push(segment);
+  }
+  Some(local)
+}
+
+/// `path` relative to `root` with forward slashes, as written to the
+/// checksum list.
+pub fn relative(root: &Path, path: &Path) -> Option<String> {
+  let relative = path.strip_prefix(root).ok()?;
+  let parts: Option<Vec<&str>> = relative
+    .components()
+    .map(|c| c.as_os_str().to_str())
+    .collect();
+  Some(parts?.join("/"))
+}
+
+/// The dog.ceo endpoint listing every photo of a breed or sub-breed.
+pub fn listing_url(api_url: &str, main: &str, sub: Option<&str>) -> String {
+  let api_url = api_url.trim_end_matches('/');
+  match sub {
+    Some(sub) => format!("{api_url}/api/breed/{main}/{sub}/images"),
+    None => format!("{api_url}/api/breed/{main}/images")
+  }
+}
+
+/// The dog.ceo endpoint listing the whole breed catalog.
+pub fn catalog_url(api_url: &str) -> String {
+  format!("{}/api/breeds/list/all", api_url.trim_end_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_urls_map_into_host_folders() {
+    let root = Path::new("/mirror");
+    assert_eq!(
+      local_path(
+        root,
+        "https://images.dog.ceo/breeds/hound-afghan/n02088094_1003.jpg"
+      ),
+      Some(PathBuf::from(
+        "/mirror/images.dog.ceo/breeds/hound-afghan/n02088094_1003.jpg"
+      ))
+    );
+    assert_eq!(
+      local_path(root, "https://dog.ceo/api/breed/hound/afghan/images?x=1"),
+      Some(PathBuf::from(
+        "/mirror/dog.ceo/api/breed/hound/afghan/images"
+      ))
+    );
+  }
+
+  #[test]
+  fn test_escaping_paths_are_refused() {
+    let root = Path::new("/mirror");
+    assert_eq!(local_path(root, "https://dog.ceo/breeds/../../etc"), None);
+    assert_eq!(local_path(root, "https://dog.ceo/breeds//x.jpg"), None);
+    assert_eq!(local_path(root, "https://dog.ceo"), None);
+    assert_eq!(local_path(root, "file:///etc/passwd"), None);
+  }
+
+  #[test]
+  fn test_relative_paths_use_forward_slashes() {
+    let root = Path::new("/mirror");
+    let path = root.join("images.dog.ceo").join("breeds").join("a.jpg");
+    assert_eq!(
+      relative(root, &path).as_deref(),
+      Some("images.dog.ceo/breeds/a.jpg")
+    );
+  }
+}
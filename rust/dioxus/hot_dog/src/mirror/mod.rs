@@ -0,0 +1,406 @@
+//|-> Modules
+mod checksums;
+mod layout;
+
+//|-> Internal Exports
+mod prelude {
+  pub use crate::prelude::*;
+}
+
+//|-> External Exports
+pub use checksums::{Checksums, sha256};
+pub use layout::{catalog_url, listing_url, local_path};
+
+use crate::{
+  analysis,
+  provider::{BreedsApiResponse, DogCeo}
+};
+use clap::{Arg, ArgAction, Command, value_parser};
+use futures::{StreamExt, stream};
+use prelude::*;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+//~@ Mirrored unless another API is given
+const API_URL: &str = "https://dog.ceo";
+
+/// What to mirror and how hard to lean on the upstream while doing it.
+#[derive(Debug, Clone)]
+pub struct Config {
+  pub root: PathBuf,
+  pub api_url: String,
+  /// Requests in flight at once.
+  pub concurrency: usize,
+  /// Re-hash files already on disk instead of trusting the checksum list.
+  pub verify: bool
+}
+
+impl Config {
+  pub fn new(root: impl Into<PathBuf>) -> Self {
+    Self {
+      root: root.into(),
+      api_url: API_URL.to_string(),
+      concurrency: 8,
+      verify: false
+    }
+  }
+}
+
+/// How a mirror run went.
+#[derive(Debug, Default)]
+pub struct Summary {
+  pub breeds: usize,
+  pub images: usize,
+  pub downloaded: usize,
+  pub skipped: usize,
+  pub bytes: u64,
+  /// URLs that couldn't be listed or fetched, with the reason.
+  pub failed: Vec<(String, String)>
+}
+
+/// What happened to one photo.
+enum Outcome {
+  /// On disk and in the checksum list already.
+  Skipped,
+  /// On disk but not yet recorded, now hashed.
+  Kept { relative: String, digest: String },
+  Fetched {
+    relative: String,
+    digest: String,
+    bytes: u64
+  }
+}
+
+/// One photo to mirror, with the digest recorded by an earlier run.
+struct Job {
+  url: String,
+  path: PathBuf,
+  relative: String,
+  expected: Option<String>
+}
+
+/// Mirrors every photo of every breed in `catalog` under `config.root`.
+///
+/// The catalog and each breed listing are stored next to the photos under
+/// the same URL layout. Photos already recorded in the checksum list are
+/// skipped, so an interrupted run resumes where it stopped; a failed photo
+/// is reported and the rest carry on.
+pub async fn run(
+  config: &Config,
+  catalog: &BreedsApiResponse
+) -> Result<Summary> {
+  let root = config.root.as_path();
+  let concurrency = config.concurrency.max(1);
+  fs::create_dir_all(root).await?;
+  let mut sums = Checksums::load(root).await?;
+  let mut summary = Summary::default();
+
+  write_json(root, &catalog_url(&config.api_url), catalog).await?;
+
+  //{ List every breed's photos }
+  let keys = catalog.keys();
+  summary.breeds = keys.len();
+  let mut listings = stream::iter(keys)
+    .map(|(main, sub)| async move {
+      let url = listing_url(&config.api_url, &main, sub.as_deref());
+      let photos =
+        DogCeo::breed_photos(&main, sub.as_deref(), Some(&url)).await;
+      (url, photos)
+    })
+    .buffer_unordered(concurrency);
+  let mut photos = Vec::new();
+  while let Some((url, listed)) = listings.next().await {
+    match listed {
+      Ok(listed) => {
+        let body =
+          serde_json::json!({ "message": listed, "status": "success" });
+        write_json(root, &url, &body).await?;
+        photos.extend(listed);
+      }
+      Err(e) => {
+        warn!("Failed to list {url}: {e}");
+        summary.failed.push((url, e.to_string()));
+      }
+    }
+  }
+  photos.sort();
+  photos.dedup();
+  summary.images = photos.len();
+
+  //{ Work out where each photo goes and what an earlier run recorded }
+  let mut jobs = Vec::with_capacity(photos.len());
+  for url in photos {
+    let located = local_path(root, &url)
+      .and_then(|path| Some((layout::relative(root, &path)?, path)));
+    match located {
+      Some((relative, path)) => jobs.push(Job {
+        expected: sums.get(&relative).map(str::to_string),
+        url,
+        path,
+        relative
+      }),
+      None => summary.failed.push((url, "unsupported URL".into()))
+    }
+  }
+
+  //{ Fetch concurrently, record checksums one at a time }
+  let mut fetches = stream::iter(jobs)
+    .map(|job| async move {
+      let outcome = mirror_photo(&job, config.verify).await;
+      (job.url, outcome)
+    })
+    .buffer_unordered(concurrency);
+  while let Some((url, outcome)) = fetches.next().await {
+    match outcome {
+      Ok(Outcome::Skipped) => summary.skipped += 1,
+      Ok(Outcome::Kept { relative, digest }) => {
+        sums.record(&relative, &digest).await?;
+        summary.skipped += 1;
+      }
+      Ok(Outcome::Fetched {
+        relative,
+        digest,
+        bytes
+      }) => {
+        sums.record(&relative, &digest).await?;
+        summary.downloaded += 1;
+        summary.bytes += bytes;
+      }
+      Err(e) => {
+        warn!("Failed to mirror {url}: {e}");
+        summary.failed.push((url, e.to_string()));
+      }
+    }
+  }
+  Ok(summary)
+}
+
+/// The `mirror` subcommand and its arguments.
+pub fn command() -> Command {
+  Command::new("mirror")
+    .about("Download every dog.ceo photo into a local folder")
+    .arg(
+      Arg::new("dir")
+        .required(true)
+        .value_parser(value_parser!(PathBuf))
+        .help("Folder to mirror into; rerun on it to resume")
+    )
+    .arg(
+      Arg::new("concurrency")
+        .long("concurrency")
+        .short('c')
+        .value_parser(value_parser!(usize))
+        .default_value("8")
+        .help("Requests in flight at once")
+    )
+    .arg(
+      Arg::new("verify")
+        .long("verify")
+        .action(ArgAction::SetTrue)
+        .help("Re-hash mirrored files and fetch any that changed")
+    )
+    .arg(
+      Arg::new("api")
+        .long("api")
+        .default_value(API_URL)
+        .help("Base URL of the dog.ceo API")
+    )
+}
+
+/// Runs `hot_dog mirror ...` if that's what the process was started as,
+/// returning whether it was.
+pub fn run_cli() -> Result<bool> {
+  if std::env::args().nth(1).as_deref() != Some("mirror") {
+    return Ok(false);
+  }
+  let matches = command().get_matches_from(std::env::args().skip(1));
+  let config = Config {
+    root: matches
+      .get_one::<PathBuf>("dir")
+      .cloned()
+      .unwrap_or_default(),
+    api_url: matches
+      .get_one::<String>("api")
+      .cloned()
+      .unwrap_or_else(|| API_URL.to_string()),
+    concurrency: matches
+      .get_one::<usize>("concurrency")
+      .copied()
+      .unwrap_or(8),
+    verify: matches.get_flag("verify")
+  };
+
+  let summary = tokio::runtime::Runtime::new()?.block_on(async {
+    let catalog =
+      DogCeo::get_breeds(None::<PathBuf>, Some(&catalog_url(&config.api_url)))
+        .await?;
+    run(&config, &catalog).await
+  })?;
+
+  info!(
+    "Mirrored {} breeds into {}: {} photos, {} downloaded ({} bytes), {} \
+     already there, {} failed",
+    summary.breeds,
+    config.root.display(),
+    summary.images,
+    summary.downloaded,
+    summary.bytes,
+    summary.skipped,
+    summary.failed.len()
+  );
+  for (url, reason) in &summary.failed {
+    warn!("Not mirrored: {url}: {reason}");
+  }
+  Ok(true)
+}
+
+// -- Private Helper --
+
+async fn mirror_photo(job: &Job, verify: bool) -> Result<Outcome> {
+  if fs::try_exists(&job.path).await? {
+    if job.expected.is_some() && !verify {
+      return Ok(Outcome::Skipped);
+    }
+    let digest = checksums::sha256(&fs::read(&job.path).await?);
+    match &job.expected {
+      Some(expected) if *expected == digest => return Ok(Outcome::Skipped),
+      Some(_) => warn!("{} changed on disk, fetching it again", job.relative),
+      None =>
+        return Ok(Outcome::Kept {
+          relative: job.relative.clone(),
+          digest
+        }),
+    }
+  }
+
+  let bytes = analysis::fetch(&job.url).await?;
+  write_atomic(&job.path, &bytes).await?;
+  Ok(Outcome::Fetched {
+    relative: job.relative.clone(),
+    digest: checksums::sha256(&bytes),
+    bytes: bytes.len() as u64
+  })
+}
+
+/// Writes through a `.part` file so an interrupted run never leaves a
+/// truncated file under the final name.
+async fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).await?;
+  }
+  let mut partial = path.as_os_str().to_owned();
+  partial.push(".part");
+  fs::write(&partial, bytes).await?;
+  fs::rename(&partial, path).await?;
+  Ok(())
+}
+
+async fn write_json(
+  root: &Path,
+  url: &str,
+  value: &impl serde::Serialize
+) -> Result<()> {
+  let path = local_path(root, url)
+    .ok_or_else(|| Error::Url(format!("Can't mirror {url}")))?;
+  write_atomic(&path, &serde_json::to_vec_pretty(value)?).await
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use mockito::{Mock, Server, ServerGuard};
+  use std::collections::HashMap;
+  use tempfile::tempdir;
+
+  const PHOTOS: [&str; 2] = ["n02088094_1003.jpg", "n02088094_1007.jpg"];
+
+  fn catalog() -> BreedsApiResponse {
+    BreedsApiResponse {
+      message: HashMap::from([("hound".into(), vec!["afghan".into()])]),
+      status: "success".into()
+    }
+  }
+
+  async fn upstream(server: &mut ServerGuard, hits: usize) -> Vec<Mock> {
+    let urls: Vec<String> = PHOTOS
+      .iter()
+      .map(|file| format!("{}/breeds/hound-afghan/{file}", server.url()))
+      .collect();
+    let mut mocks = vec![
+      server
+        .mock("GET", "/api/breed/hound/afghan/images")
+        .expect_at_least(1)
+        .with_body(
+          serde_json::json!({ "message": urls, "status": "success" })
+            .to_string()
+        )
+        .create_async()
+        .await,
+    ];
+    for file in PHOTOS {
+      mocks.push(
+        server
+          .mock("GET", &*format!("/breeds/hound-afghan/{file}"))
+          .with_body(file)
+          .expect(hits)
+          .create_async()
+          .await
+      );
+    }
+    mocks
+  }
+
+  #[tokio::test]
+  async fn test_reruns_resume_instead_of_refetching() {
+    let mut server = Server::new_async().await;
+    let dir = tempdir().unwrap();
+    let config = Config {
+      api_url: server.url(),
+      ..Config::new(dir.path())
+    };
+    let mocks = upstream(&mut server, 1).await;
+
+    let first = run(&config, &catalog()).await.unwrap();
+    assert_eq!((first.images, first.downloaded), (2, 2));
+    let second = run(&config, &catalog()).await.unwrap();
+    assert_eq!((second.downloaded, second.skipped), (0, 2));
+    for mock in mocks {
+      mock.assert_async().await;
+    }
+
+    let host = proxy::host(&server.url()).unwrap().to_string();
+    let photo = dir
+      .path()
+      .join(&host)
+      .join("breeds/hound-afghan")
+      .join(PHOTOS[0]);
+    assert_eq!(std::fs::read_to_string(photo).unwrap(), PHOTOS[0]);
+    assert!(dir.path().join(&host).join("api/breeds/list/all").exists());
+    let sums = Checksums::load(dir.path()).await.unwrap();
+    assert_eq!(sums.len(), 2);
+  }
+
+  #[tokio::test]
+  async fn test_verify_refetches_changed_files() {
+    let mut server = Server::new_async().await;
+    let dir = tempdir().unwrap();
+    let config = Config {
+      api_url: server.url(),
+      verify: true,
+      ..Config::new(dir.path())
+    };
+    let _mocks = upstream(&mut server, 2).await;
+    run(&config, &catalog()).await.unwrap();
+
+    let host = proxy::host(&server.url()).unwrap().to_string();
+    let photo = dir
+      .path()
+      .join(&host)
+      .join("breeds/hound-afghan")
+      .join(PHOTOS[0]);
+    std::fs::write(&photo, "corrupted").unwrap();
+    let rerun = run(&config, &catalog()).await.unwrap();
+    assert_eq!((rerun.downloaded, rerun.skipped), (1, 1));
+    assert_eq!(std::fs::read_to_string(photo).unwrap(), PHOTOS[0]);
+  }
+}
@@ -14,6 +14,14 @@ pub use crate::{
 //|-> Constants
 pub static TITLE: GlobalSignal<&'static str> = Signal::global(|| "HotDogs");
 pub const CSS: Asset = asset!("/assets/styles/main.css");
+pub const DOG_CSS: Asset = asset!("/assets/styles/dog.css");
+pub const BREEDS_CSS: Asset = asset!("/assets/styles/breeds.css");
+pub const CAROUSEL_CSS: Asset = asset!("/assets/styles/carousel.css");
+pub const FAVORITES_CSS: Asset = asset!("/assets/styles/favorites.css");
+pub const MATCHUP_CSS: Asset = asset!("/assets/styles/matchup.css");
+pub const RATE_CSS: Asset = asset!("/assets/styles/rate.css");
+pub const QUIZ_CSS: Asset = asset!("/assets/styles/quiz.css");
+pub const DOG_TXT: &str = "assets/data/dog.txt";
 pub const ICON: Asset = asset!("/assets/favicon.ico");
 pub const LOGO: Asset = asset!(
   "/assets/logo.png",
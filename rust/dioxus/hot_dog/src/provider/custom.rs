@@ -79,6 +79,10 @@ impl Provider {
 
 #[async_trait]
 impl Content for Provider {
+  fn name(&self) -> &'static str {
+    "custom"
+  }
+
   async fn photo(&self, source: DataSource<'_>) -> Result<String> {
     // Try parsing as JSON first
     match self.parse_photo_response(source.clone()).await {
@@ -1,17 +1,32 @@
 use super::{prelude::*, *};
+use crate::data::{
+  BreedScore, Dog, DogId, filter::BreedFilter, history::History, rating
+};
+use futures::future::try_join;
 use std::{
   collections::hash_map::DefaultHasher,
   hash::{Hash, Hasher},
   path::{Path, PathBuf},
+  sync::{Arc, Mutex},
   time::{SystemTime, UNIX_EPOCH}
 };
+use tracing::Instrument;
+
+//~@ Share of biased picks that ignore preferences, so new breeds still show up
+const EXPLORATION: f64 = 0.3;
+
+//~@ Photos tried per dog before a breed filter gives up on a provider
+const FILTER_RETRIES: usize = 10;
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub enum Provider {
   #[default]
   DogCeo,
   Random,
-  Custom(String)
+  Custom(String),
+  /// Serves everything from the mounted [`offline::Bundle`].
+  #[cfg(feature = "server")]
+  Offline
 }
 
 impl Provider {
@@ -38,14 +53,52 @@ impl Provider {
     known[index].clone()
   }
 
+  pub fn name(&self) -> &'static str {
+    match self {
+      Provider::DogCeo => "dog_ceo",
+      Provider::Random => "random",
+      Provider::Custom(_) => "custom",
+      #[cfg(feature = "server")]
+      Provider::Offline => "offline"
+    }
+  }
+
+  /// The provider the server should use: the mounted offline bundle if
+  /// there is one, otherwise the default.
+  pub fn configured() -> Self {
+    #[cfg(feature = "server")]
+    if offline::mounted().is_some() {
+      return Provider::Offline;
+    }
+    Provider::default()
+  }
+
   pub async fn photo(&self, url: Option<&str>) -> Result<String> {
+    let span = log::request_span(self.name(), "photo");
+    self.fetch_photo(url).instrument(span).await
+  }
+
+  pub async fn breed<P: AsRef<Path> + Send + Sync>(
+    &self,
+    photo_url: Option<&str>,
+    breeds_url: Option<&str>,
+    breeds_path: Option<P>
+  ) -> Result<Breed> {
+    let span = log::request_span(self.name(), "breed");
+    self
+      .fetch_breed(photo_url, breeds_url, breeds_path)
+      .instrument(span)
+      .await
+  }
+
+  async fn fetch_photo(&self, url: Option<&str>) -> Result<String> {
     match self {
       Provider::DogCeo => {
         let source = match url {
           Some(url_str) => DataSource::Url(url_str),
           None => DataSource::Url("https://dog.ceo/api/breeds/image/random")
         };
-        dog_ceo::Provider.photo(source).await
+        Observed(dog_ceo::Provider).photo(source).await
       }
       Provider::Random => {
         let random_provider = Self::select_random();
@@ -55,14 +108,16 @@ impl Provider {
               Some(url_str) => DataSource::Url(url_str),
               None => DataSource::Url("https://dog.ceo/api/breeds/image/random")
             };
-            dog_ceo::Provider.photo(source).await
+            Observed(dog_ceo::Provider).photo(source).await
           }
           Provider::Custom(base_url) => {
             let source = match url {
               Some(url_str) => DataSource::Url(url_str),
               None => DataSource::Url(&base_url)
             };
-            custom::Provider::new(base_url.clone()).photo(source).await
+            Observed(custom::Provider::new(base_url.clone()))
+              .photo(source)
+              .await
           }
           Provider::Random => {
             // Prevent infinite recursion - fallback to DogCeo
@@ -70,8 +125,10 @@ impl Provider {
               Some(url_str) => DataSource::Url(url_str),
               None => DataSource::Url("https://dog.ceo/api/breeds/image/random")
             };
-            dog_ceo::Provider.photo(source).await
+            Observed(dog_ceo::Provider).photo(source).await
           }
+          #[cfg(feature = "server")]
+          Provider::Offline => self.fetch_offline_photo(url).await
         }
       }
       Provider::Custom(base_url) => {
@@ -79,12 +136,22 @@ impl Provider {
           Some(url_str) => DataSource::Url(url_str),
           None => DataSource::Url(base_url)
         };
-        custom::Provider::new(base_url.clone()).photo(source).await
+        Observed(custom::Provider::new(base_url.clone()))
+          .photo(source)
+          .await
       }
+      #[cfg(feature = "server")]
+      Provider::Offline => self.fetch_offline_photo(url).await
     }
   }
 
-  pub async fn breed<P: AsRef<Path> + Send + Sync>(
+  #[cfg(feature = "server")]
+  async fn fetch_offline_photo(&self, url: Option<&str>) -> Result<String> {
+    let source = DataSource::Url(url.unwrap_or_default());
+    Observed(offline::Provider).photo(source).await
+  }
+
+  async fn fetch_breed<P: AsRef<Path> + Send + Sync>(
     &self,
     photo_url: Option<&str>,
     breeds_url: Option<&str>,
@@ -96,13 +163,13 @@ impl Provider {
           Some(url_str) => DataSource::Url(url_str),
           None => DataSource::Url("https://dog.ceo/api/breeds/image/random")
         };
-        dog_ceo::Provider.breed(source).await
+        Observed(dog_ceo::Provider).breed(source).await
       }
       Provider::Random => {
         if let Some(url) = photo_url {
           if url.contains("dog.ceo") {
             let source = DataSource::Url(url);
-            return dog_ceo::Provider.breed(source).await;
+            return Observed(dog_ceo::Provider).breed(source).await;
           }
         }
         Ok(Breed::new(
@@ -117,11 +184,302 @@ impl Provider {
           Some(url_str) => DataSource::Url(url_str),
           None => DataSource::Url(base_url)
         };
-        custom::Provider::new(base_url.clone()).breed(source).await
+        Observed(custom::Provider::new(base_url.clone()))
+          .breed(source)
+          .await
+      }
+      #[cfg(feature = "server")]
+      Provider::Offline => {
+        let source = DataSource::Url(photo_url.unwrap_or_default());
+        Observed(offline::Provider).breed(source).await
       }
     }
   }
 
+  /// Fetches a photo and resolves its breed in one go.
+  pub async fn dog(&self) -> Result<Dog> {
+    let photo_url = self.photo(None).await?;
+    let breed = self.breed(Some(&photo_url), None, None::<PathBuf>).await?;
+    Ok(Dog::new(photo_url, breed))
+  }
+
+  /// Fetches a dog, leaning toward breeds with a higher like ratio in
+  /// `scores`.
+  ///
+  /// Only dog.ceo can serve a photo of a chosen breed; other providers, and
+  /// a share of [`EXPLORATION`] picks, fall back to [`Provider::dog`].
+  pub async fn preferred_dog(&self, scores: &[BreedScore]) -> Result<Dog> {
+    let mut rng = rng::Rng::from_time();
+    let preferred = match self {
+      Provider::DogCeo if rng.next_f64() >= EXPLORATION =>
+        rating::pick_weighted(scores, rng.next_f64()),
+      _ => None
+    };
+    let Some(score) = preferred else {
+      return self.dog().await;
+    };
+
+    let span = log::request_span(self.name(), "preferred_dog");
+    Self::breed_dog(&score.breed, score.sub_breed.as_deref())
+      .instrument(span)
+      .await
+  }
+
+  /// Fetches one dog of each breed concurrently, for head-to-head modes.
+  ///
+  /// Providers without per-breed endpoints return two random dogs instead.
+  pub async fn pair(
+    &self,
+    first: (&str, Option<&str>),
+    second: (&str, Option<&str>)
+  ) -> Result<(Dog, Dog)> {
+    let span = log::request_span(self.name(), "pair");
+    let fetch = async {
+      match self {
+        Provider::DogCeo =>
+          try_join(
+            Self::breed_dog(first.0, first.1),
+            Self::breed_dog(second.0, second.1)
+          )
+          .await,
+        _ => try_join(self.dog(), self.dog()).await
+      }
+    };
+    fetch.instrument(span).await
+  }
+
+  /// Fetches a dog passing `filter`.
+  ///
+  /// dog.ceo is asked for an allowed breed directly; other providers are
+  /// retried until a photo passes, giving up after a handful of tries.
+  pub async fn filtered_dog(&self, filter: &BreedFilter) -> Result<Dog> {
+    if filter.is_any() {
+      return self.dog().await;
+    }
+    let span = log::request_span(self.name(), "filtered_dog");
+    let fetch = async {
+      if let Provider::DogCeo = self {
+        let (main, sub) = Self::pick_breed(filter).await?;
+        return Self::breed_dog(&main, sub.as_deref()).await;
+      }
+      for _ in 0..=FILTER_RETRIES {
+        let dog = self.dog().await?;
+        if filter.matches(&dog.breed, dog.sub_breed.as_deref()) {
+          return Ok(dog);
+        }
+        debug!("Skipping {}, {} is filtered out", dog.photo_url, dog.breed);
+      }
+      Err(Error::NotFound(format!("no photo matching {filter}")))
+    };
+    fetch.instrument(span).await
+  }
+
+  /// Fetches a dog passing `filter` whose photo is neither in `history` nor
+  /// looks like any in `seen`.
+  ///
+  /// Repeated URLs are skipped before any photo is downloaded for hashing.
+  /// The random provider has no single [`Content`] to wrap, so it falls back
+  /// to [`Provider::filtered_dog`].
+  #[cfg(feature = "server")]
+  pub async fn unseen_dog(
+    &self,
+    seen: &SeenPhotos,
+    history: Arc<Mutex<History>>,
+    filter: &BreedFilter
+  ) -> Result<Dog> {
+    const RETRIES: usize = 3;
+    let span = log::request_span(self.name(), "unseen_dog");
+    let fetch = async {
+      match self {
+        Provider::DogCeo => {
+          let content = Distinct::new(
+            Fresh::new(Observed(dog_ceo::Provider), history, RETRIES),
+            seen.clone(),
+            RETRIES
+          );
+          let api_url = match filter {
+            BreedFilter::Any =>
+              "https://dog.ceo/api/breeds/image/random".to_string(),
+            _ => {
+              let (main, sub) = Self::pick_breed(filter).await?;
+              dog_ceo::Provider::breed_photo_url(&main, sub.as_deref())
+            }
+          };
+          let photo_url = content.photo(DataSource::Url(&api_url)).await?;
+          let breed = content.breed(DataSource::Url(&photo_url)).await?;
+          Ok(Dog::new(photo_url, breed))
+        }
+        Provider::Custom(base_url) => {
+          let content = Distinct::new(
+            Fresh::new(
+              Filtered::new(
+                Observed(custom::Provider::new(base_url.clone())),
+                filter.clone(),
+                FILTER_RETRIES
+              ),
+              history,
+              RETRIES
+            ),
+            seen.clone(),
+            RETRIES
+          );
+          let photo_url = content.photo(DataSource::Url(base_url)).await?;
+          let breed = content.breed(DataSource::Url(&photo_url)).await?;
+          Ok(Dog::new(photo_url, breed))
+        }
+        Provider::Offline => {
+          let content = Distinct::new(
+            Fresh::new(
+              Filtered::new(
+                Observed(offline::Provider),
+                filter.clone(),
+                FILTER_RETRIES
+              ),
+              history,
+              RETRIES
+            ),
+            seen.clone(),
+            RETRIES
+          );
+          let photo_url = content.photo(DataSource::Url("")).await?;
+          let breed = content.breed(DataSource::Url(&photo_url)).await?;
+          Ok(Dog::new(photo_url, breed))
+        }
+        Provider::Random => self.filtered_dog(filter).await
+      }
+    };
+    fetch.instrument(span).await
+  }
+
+  /// Fetches the dog picked by `seed`: the same seed always lands on the same
+  /// breed and photo while the dog.ceo catalog is unchanged.
+  ///
+  /// The offline provider picks from its bundle instead. Other providers
+  /// can't be steered, so they return a random dog and rely on the caller
+  /// caching it.
+  pub async fn seeded_dog(&self, seed: u64) -> Result<Dog> {
+    #[cfg(feature = "server")]
+    if let Provider::Offline = self {
+      return offline::seeded_dog(seed);
+    }
+    let Provider::DogCeo = self else {
+      return self.dog().await;
+    };
+    let span = log::request_span(self.name(), "seeded_dog");
+    let fetch = async {
+      let mut rng = rng::Rng::new(seed);
+      let breeds = dog_ceo::Provider::get_breeds(None::<PathBuf>, None)
+        .await?
+        .keys();
+      if breeds.is_empty() {
+        return Err(Error::EmptyResponse);
+      }
+      let (main, sub) = &breeds[rng.below(breeds.len())];
+
+      let mut photos =
+        dog_ceo::Provider::breed_photos(main, sub.as_deref(), None).await?;
+      if photos.is_empty() {
+        return Err(Error::EmptyResponse);
+      }
+      photos.sort();
+      let photo_url = photos.swap_remove(rng.below(photos.len()));
+
+      let content = Observed(dog_ceo::Provider);
+      let breed = content.breed(DataSource::Url(&photo_url)).await?;
+      Ok(Dog::new(photo_url, breed))
+    };
+    fetch.instrument(span).await
+  }
+
+  /// Rebuilds the full [`Dog`] behind a permalink id, resolving the breed
+  /// from the photo URL through the id's provider.
+  ///
+  /// Ids are client input, so custom ones must decode to a host the image
+  /// proxy allows, and their breed is read from the URL without fetching.
+  pub async fn find_dog(id: &DogId) -> Result<Dog> {
+    let photo_url = id.photo_url()?;
+    if !proxy::is_allowed(&photo_url) {
+      return Err(Error::NotFound(format!("no dog with id {}", id.id)));
+    }
+    let breed = match id.provider.as_str() {
+      "dog_ceo" => {
+        let span = log::request_span(&id.provider, "find_dog");
+        Observed(dog_ceo::Provider)
+          .breed(DataSource::Url(&photo_url))
+          .instrument(span)
+          .await?
+      }
+      _ => {
+        //~@ Reference links point at the photo's own origin
+        let origin = photo_url
+          .splitn(4, '/')
+          .take(3)
+          .collect::<Vec<_>>()
+          .join("/");
+        custom::Provider::new(origin).parse_custom_format(&photo_url)?
+      }
+    };
+    Ok(Dog::new(photo_url, breed))
+  }
+
+  /// A random dog.ceo breed passing `filter`, straight from the list when
+  /// it names the breeds to keep.
+  async fn pick_breed(
+    filter: &BreedFilter
+  ) -> Result<(String, Option<String>)> {
+    let mut rng = rng::Rng::from_time();
+    if let BreedFilter::Only(breeds) = filter {
+      if !breeds.is_empty() {
+        let breed = &breeds[rng.below(breeds.len())];
+        return Ok((breed.main.clone(), breed.sub.clone()));
+      }
+    }
+    let keys = dog_ceo::Provider::get_breeds(None::<PathBuf>, None)
+      .await?
+      .keys();
+    let allowed = filter.allowed(&keys);
+    if allowed.is_empty() {
+      return Err(Error::NotFound(format!("no breed matching {filter}")));
+    }
+    Ok(allowed[rng.below(allowed.len())].clone())
+  }
+
+  /// Fetches a random dog.ceo photo of one breed through [`Content`].
+  async fn breed_dog(main: &str, sub: Option<&str>) -> Result<Dog> {
+    let content = Observed(dog_ceo::Provider);
+    let api_url = dog_ceo::Provider::breed_photo_url(main, sub);
+    let photo_url = content.photo(DataSource::Url(&api_url)).await?;
+    let breed = content.breed(DataSource::Url(&photo_url)).await?;
+    Ok(Dog::new(photo_url, breed))
+  }
+
+  /// Fetches a batch of `count` dogs, using a single request where the
+  /// provider supports it.
+  pub async fn dogs(&self, count: usize) -> Result<Vec<Dog>> {
+    let span = log::request_span(self.name(), "dogs");
+    self.fetch_dogs(count).instrument(span).await
+  }
+
+  async fn fetch_dogs(&self, count: usize) -> Result<Vec<Dog>> {
+    let photos = match self {
+      Provider::DogCeo => dog_ceo::Provider::random_photos(count, None).await?,
+      _ => {
+        let mut photos = Vec::with_capacity(count);
+        for _ in 0..count {
+          photos.push(self.photo(None).await?);
+        }
+        photos
+      }
+    };
+
+    let mut dogs = Vec::with_capacity(photos.len());
+    for photo_url in photos {
+      let breed = self.breed(Some(&photo_url), None, None::<PathBuf>).await?;
+      dogs.push(Dog::new(photo_url, breed));
+    }
+    Ok(dogs)
+  }
+
   pub fn dog_ceo() -> Self {
     Provider::DogCeo
   }
@@ -133,11 +491,23 @@ impl Provider {
   pub fn custom<S: Into<String>>(base_url: S) -> Self {
     Provider::Custom(base_url.into())
   }
+
+  /// The provider a saved preference names: `dog_ceo`, `random` or a custom
+  /// base URL. Anything else falls back to the default.
+  pub fn from_name(name: &str) -> Self {
+    match name.trim() {
+      "random" => Provider::Random,
+      url if url.starts_with("https://") || url.starts_with("http://") =>
+        Provider::custom(url),
+      _ => Provider::default()
+    }
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::{prelude::*, *};
+  use crate::data::Dog;
   use mockito::Server;
   use std::fs;
   use tempfile::tempdir;
@@ -205,6 +575,19 @@ mod tests {
     // );
   }
 
+  #[tokio::test]
+  async fn test_find_dog_refuses_other_hosts() {
+    let internal = DogId::from_photo_url("http://169.254.169.254/latest.jpg");
+    let result = Provider::find_dog(&internal).await;
+    assert!(matches!(result, Err(Error::NotFound(_))));
+
+    //~@ Custom ids on an allowed host resolve without a request
+    let custom =
+      DogId::from_photo_url("https://images.dog.ceo/x/afghan-hound.jpg");
+    let dog = Provider::find_dog(&custom).await.unwrap();
+    assert_eq!(dog.display_name, "Afghan Hound");
+  }
+
   // #[tokio::test]
   // async fn test_random_provider_photo() {
   //   let provider = Provider::random();
@@ -0,0 +1,176 @@
+use super::prelude::*;
+use crate::{analysis, data::ImageMeta};
+use std::{
+  collections::VecDeque,
+  sync::{Arc, Mutex}
+};
+
+//~@ Oldest hashes are forgotten first once this many photos have been seen
+const CAPACITY: usize = 1_024;
+
+//~@ Least recently active owners are forgotten first past this many
+const OWNERS: usize = 256;
+
+/// Perceptual hashes of photos one owner has been shown or saved, shared
+/// between their requests.
+#[derive(Debug, Clone, Default)]
+pub struct SeenPhotos(Arc<Mutex<VecDeque<ImageMeta>>>);
+
+impl SeenPhotos {
+  pub fn new(metas: impl IntoIterator<Item = ImageMeta>) -> Self {
+    let seen = Self::default();
+    for meta in metas {
+      seen.insert(meta);
+    }
+    seen
+  }
+
+  pub fn insert(&self, meta: ImageMeta) {
+    let mut seen = self.0.lock().expect("seen photos lock poisoned");
+    if seen.len() == CAPACITY {
+      seen.pop_front();
+    }
+    seen.push_back(meta);
+  }
+
+  /// Whether a photo that looks like `meta` has been seen before.
+  pub fn contains(&self, meta: &ImageMeta) -> bool {
+    let seen = self.0.lock().expect("seen photos lock poisoned");
+    seen.iter().any(|known| known.is_duplicate(meta))
+  }
+}
+
+/// The [`SeenPhotos`] of every recently active owner, so one visitor's
+/// photos never hold back another's.
+#[derive(Debug, Default)]
+pub struct SeenByOwner(Mutex<VecDeque<(String, SeenPhotos)>>);
+
+impl SeenByOwner {
+  /// The photos `owner` has seen, starting from `seed` the first time the
+  /// owner turns up or after they were forgotten.
+  pub fn get(
+    &self,
+    owner: &str,
+    seed: impl FnOnce() -> Result<Vec<ImageMeta>>
+  ) -> Result<SeenPhotos> {
+    let mut owners = self.0.lock().expect("seen photos lock poisoned");
+    let known = owners.iter().position(|(key, _)| key == owner);
+    let seen = match known.and_then(|index| owners.remove(index)) {
+      Some((_, seen)) => seen,
+      None => SeenPhotos::new(seed()?)
+    };
+    if owners.len() == OWNERS {
+      owners.pop_front();
+    }
+    owners.push_back((owner.to_string(), seen.clone()));
+    Ok(seen)
+  }
+}
+
+/// Wraps a [`Content`] provider so `photo` skips pictures that are
+/// near-duplicates of ones in [`SeenPhotos`].
+///
+/// Each candidate is downloaded and hashed before it is returned, unless
+/// [`analysis::analyze_url`] hashed it recently. After `retries` duplicates
+/// in a row the last candidate is served anyway, and a photo that can't be
+/// analyzed is served as-is.
+pub struct Distinct<C> {
+  inner: C,
+  seen: SeenPhotos,
+  retries: usize
+}
+
+impl<C> Distinct<C> {
+  pub fn new(inner: C, seen: SeenPhotos, retries: usize) -> Self {
+    Self {
+      inner,
+      seen,
+      retries
+    }
+  }
+}
+
+#[async_trait]
+impl<C: Content + Send + Sync> Content for Distinct<C> {
+  fn name(&self) -> &'static str {
+    self.inner.name()
+  }
+
+  async fn photo(&self, source: DataSource<'_>) -> Result<String> {
+    let mut attempt = 0;
+    loop {
+      let url = self.inner.photo(source.clone()).await?;
+      let meta = match analysis::analyze_url(&url).await {
+        Ok(meta) => meta,
+        Err(e) => {
+          warn!("Serving {url} unchecked, analysis failed: {e}");
+          return Ok(url);
+        }
+      };
+
+      if !self.seen.contains(&meta) || attempt == self.retries {
+        self.seen.insert(meta);
+        return Ok(url);
+      }
+      debug!("Skipping {url}, already seen a near-duplicate");
+      attempt += 1;
+    }
+  }
+
+  async fn breed(&self, source: DataSource<'_>) -> Result<Breed> {
+    self.inner.breed(source).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn meta(hash: u64) -> ImageMeta {
+    ImageMeta {
+      width: 1,
+      height: 1,
+      format: "jpeg".into(),
+      bytes: 1,
+      palette: Vec::new(),
+      ahash: hash,
+      dhash: hash
+    }
+  }
+
+  #[test]
+  fn test_seen_photos() {
+    let seen = SeenPhotos::new([meta(0)]);
+    assert!(seen.contains(&meta(0b11)));
+    assert!(!seen.contains(&meta(u64::MAX)));
+
+    for _ in 0..CAPACITY {
+      seen.insert(meta(u64::MAX));
+    }
+    assert!(!seen.contains(&meta(0)));
+  }
+
+  #[test]
+  fn test_seen_photos_per_owner() {
+    let owners = SeenByOwner::default();
+    let rex = owners.get("user:1", || Ok(vec![meta(0)])).unwrap();
+    assert!(rex.contains(&meta(0)));
+
+    let fido = owners.get("user:2", || Ok(Vec::new())).unwrap();
+    fido.insert(meta(u64::MAX));
+    assert!(!fido.contains(&meta(0)));
+    assert!(!rex.contains(&meta(u64::MAX)));
+
+    //~@ Known owners are not seeded again
+    let again = owners.get("user:2", || Err(Error::EmptyResponse)).unwrap();
+    assert!(again.contains(&meta(u64::MAX)));
+
+    for owner in 0..OWNERS {
+      owners
+        .get(&format!("guest:{owner}"), || Ok(Vec::new()))
+        .unwrap();
+    }
+    let forgotten = owners.get("user:1", || Ok(Vec::new())).unwrap();
+    assert!(!forgotten.contains(&meta(0)));
+  }
+}
@@ -19,11 +19,66 @@ pub struct PhotoApiResponse {
   pub status: String
 }
 
+#[derive(Deserialize, Debug)]
+pub struct PhotosApiResponse {
+  pub message: Vec<String>,
+  pub status: String
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct BreedsApiResponse {
   pub message: HashMap<String, Vec<String>>,
   pub status: String
 }
+impl BreedsApiResponse {
+  /// Lists main breeds alphabetically with their sorted sub-breeds, keeping
+  /// only entries whose main or sub-breed name contains `query`.
+  ///
+  /// When only some sub-breeds match, just those are kept.
+  pub fn search(&self, query: &str) -> Vec<(String, Vec<String>)> {
+    let query = query.trim().to_lowercase();
+    let mut results: Vec<(String, Vec<String>)> = self
+      .message
+      .iter()
+      .filter_map(|(main, subs)| {
+        let mut subs = subs.clone();
+        subs.sort();
+
+        if query.is_empty() || main.contains(&query) {
+          return Some((main.clone(), subs));
+        }
+
+        let matching: Vec<String> = subs
+          .into_iter()
+          .filter(|sub| sub.contains(&query))
+          .collect();
+        (!matching.is_empty()).then(|| (main.clone(), matching))
+      })
+      .collect();
+    results.sort();
+    results
+  }
+
+  /// Every rateable breed as `(main, sub)`, one entry per sub-breed or a
+  /// single `(main, None)` for breeds without any.
+  pub fn keys(&self) -> Vec<(String, Option<String>)> {
+    self
+      .search("")
+      .into_iter()
+      .flat_map(|(main, subs)| {
+        if subs.is_empty() {
+          vec![(main, None)]
+        } else {
+          subs
+            .into_iter()
+            .map(|sub| (main.clone(), Some(sub)))
+            .collect()
+        }
+      })
+      .collect()
+  }
+}
+
 //~@ Define and use a static cache for breeds data to avoid repeated API calls
 static BREEDS_CACHE: OnceLock<BreedsApiResponse> = OnceLock::new();
 
@@ -66,6 +121,7 @@ impl Provider {
     //~@ Check static cache first
     if let Some(cached) = BREEDS_CACHE.get() {
       debug!("Using cached breeds data");
+      metrics::record_cache("breeds", "memory");
       return Ok(
         //~@ Clone and return the cached data
         (*cached).clone()
@@ -73,8 +129,8 @@ impl Provider {
     }
 
     //~@ Prepare paths and URLs
-    let json_cache = format! {"{AST}/{AST_BREEDS}"};
-    let api_breeds = format! {"{URL}/{API_BREEDS}"};
+    let json_cache = format!("{AST}/{AST_BREEDS}");
+    let api_breeds = format!("{URL}/{API_BREEDS}");
     let path = breeds_path
       .map_or_else(|| PathBuf::from(json_cache), |p| p.as_ref().to_path_buf());
     let url = breeds_url.unwrap_or(&api_breeds);
@@ -83,6 +139,7 @@ impl Provider {
     //~@ Check if the breeds list is cached
     if fs::metadata(&path).is_ok() {
       debug!("Using cached breeds file");
+      metrics::record_cache("breeds", "disk");
       let content = fs::read_to_string(&path)?;
       let breeds_data: BreedsApiResponse = serde_json::from_str(&content)?;
 
@@ -93,21 +150,80 @@ impl Provider {
 
     //~@ Cache the response both in memory and on disk
     debug!("Breeds file not found, downloading...");
+    metrics::record_cache("breeds", "miss");
     let response = reqwest::get(url).await?;
     let breeds_data: BreedsApiResponse = response.json().await?;
     let _ = BREEDS_CACHE.set(breeds_data.clone());
 
-    //~@ Save to file for persistence
-    if let Some(parent) = path.parent() {
-      fs::create_dir_all(parent)?;
+    //~@ Save to file for persistence (not available on every platform)
+    if let Err(e) = Self::save_breeds(&path, &breeds_data) {
+      warn!("Failed to cache breeds file: {e}");
     }
-    let content = serde_json::to_string_pretty(&breeds_data)?;
-    fs::write(&path, content)?;
 
     //~@ Return the fetched data
     Ok(breeds_data)
   }
 
+  /// Seeds the breed cache with `breeds` so [`Provider::get_breeds`] never
+  /// goes to disk or the network; a no-op once the cache is filled.
+  pub fn prime_breeds(breeds: BreedsApiResponse) {
+    if BREEDS_CACHE.set(breeds).is_err() {
+      warn!("Breeds already loaded, keeping the cached list");
+    }
+  }
+
+  fn save_breeds(path: &Path, breeds_data: &BreedsApiResponse) -> Result<()> {
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    let content = serde_json::to_string_pretty(breeds_data)?;
+    fs::write(path, content)?;
+    Ok(())
+  }
+
+  /// Fetches every photo URL listed for a breed or sub-breed.
+  pub async fn breed_photos(
+    main: &str,
+    sub: Option<&str>,
+    photos_url: Option<&str>
+  ) -> Result<Vec<String>> {
+    let api_photos = match sub {
+      Some(sub) => format!("{URL}/api/breed/{main}/{sub}/images"),
+      None => format!("{URL}/api/breed/{main}/images")
+    };
+    let url = photos_url.unwrap_or(&api_photos);
+    debug!("Fetching breed photos from: {url}");
+
+    let response = reqwest::get(url).await?;
+    let photos: PhotosApiResponse = response.json().await?;
+    Ok(photos.message)
+  }
+
+  /// API endpoint serving one random photo of a breed or sub-breed.
+  pub fn breed_photo_url(main: &str, sub: Option<&str>) -> String {
+    match sub {
+      Some(sub) => format!("{URL}/api/breed/{main}/{sub}/images/random"),
+      None => format!("{URL}/api/breed/{main}/images/random")
+    }
+  }
+
+  /// Fetches `count` random photo URLs in a single request.
+  pub async fn random_photos(
+    count: usize,
+    photos_url: Option<&str>
+  ) -> Result<Vec<String>> {
+    let api_photos = format!("{URL}/{API_RANDOM}/{count}");
+    let url = photos_url.unwrap_or(&api_photos);
+    debug!("Fetching {count} photos from: {url}");
+
+    let response = reqwest::get(url).await?;
+    let photos: PhotosApiResponse = response.json().await?;
+    if photos.message.is_empty() {
+      return Err(Error::EmptyResponse);
+    }
+    Ok(photos.message)
+  }
+
   pub fn verify_breed(
     main: &str,
     sub: &Option<String>,
@@ -139,12 +255,10 @@ impl Provider {
     breed: &str,
     sub_breed: &Option<String>
   ) -> String {
-    let url_ref = match sub_breed {
+    match sub_breed {
       Some(sub) => format!("https://dog.ceo/api/breed/{breed}/{sub}"),
       None => format!("https://dog.ceo/api/breed/{breed}")
-    };
-    error!("{:#?}", &url_ref);
-    url_ref
+    }
   }
 
   async fn fetch_photo_from_url(&self, url: &str) -> Result<String> {
@@ -178,6 +292,10 @@ impl Provider {
 
 #[async_trait]
 impl Content for Provider {
+  fn name(&self) -> &'static str {
+    "dog_ceo"
+  }
+
   async fn photo(&self, source: DataSource<'_>) -> Result<String> {
     match source {
       DataSource::Url(url) => {
@@ -279,4 +397,52 @@ mod tests {
     //~@ Ensure the mock was called
     mock.assert_async().await;
   }
+
+  #[test]
+  fn test_search_breeds() {
+    let breeds: BreedsApiResponse = serde_json::from_str(
+      r#"{"message":{"hound":["plott","afghan"],"pug":[],"sheepdog":["english"]},"status":"success"}"#
+    )
+    .unwrap();
+
+    let all = breeds.search("");
+    assert_eq!(all.len(), 3);
+    assert_eq!(
+      all[0],
+      ("hound".into(), vec!["afghan".into(), "plott".into()])
+    );
+
+    let by_main = breeds.search("PUG");
+    assert_eq!(by_main, vec![("pug".into(), vec![])]);
+
+    let by_sub = breeds.search("afg");
+    assert_eq!(by_sub, vec![("hound".into(), vec!["afghan".into()])]);
+  }
+
+  #[tokio::test]
+  async fn test_random_photos_from_api() {
+    //~@ Initialize the logger
+    log::testing::init();
+
+    //~@ Request a new server from the pool
+    let mut server = Server::new_async().await;
+    let url = format!("{}/{}/2", server.url(), API_RANDOM);
+
+    //~@ Mock the batch endpoint
+    let mock = server
+      .mock("GET", &*format!("/{API_RANDOM}/2"))
+      .with_status(200)
+      .with_header("content-type", "application/json")
+      .with_body(format!(
+        r#"{{"message":["{TEST_PHOTO_1}","{TEST_PHOTO_2}"],"status":"success"}}"#
+      ))
+      .create_async()
+      .await;
+
+    //~@ Call the provider with the mock server URL
+    let photos = Provider::random_photos(2, Some(&url)).await.unwrap();
+
+    mock.assert_async().await;
+    assert_eq!(photos, vec![TEST_PHOTO_1, TEST_PHOTO_2]);
+  }
 }
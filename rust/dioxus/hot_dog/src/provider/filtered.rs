@@ -0,0 +1,115 @@
+use super::prelude::*;
+use crate::data::filter::BreedFilter;
+
+/// Wraps a [`Content`] provider so `photo` retries, up to `retries` times,
+/// while the photo's breed doesn't pass the [`BreedFilter`].
+///
+/// For providers that can't be asked for a breed directly. Unlike
+/// [`super::Fresh`], running out of retries is an error: serving a breed the
+/// user filtered out would be wrong, not just repetitive.
+pub struct Filtered<C> {
+  inner: C,
+  filter: BreedFilter,
+  retries: usize
+}
+
+impl<C> Filtered<C> {
+  pub fn new(inner: C, filter: BreedFilter, retries: usize) -> Self {
+    Self {
+      inner,
+      filter,
+      retries
+    }
+  }
+}
+
+#[async_trait]
+impl<C: Content + Send + Sync> Content for Filtered<C> {
+  fn name(&self) -> &'static str {
+    self.inner.name()
+  }
+
+  async fn photo(&self, source: DataSource<'_>) -> Result<String> {
+    if self.filter.is_any() {
+      return self.inner.photo(source).await;
+    }
+    for _ in 0..=self.retries {
+      let url = self.inner.photo(source.clone()).await?;
+      let breed = self.inner.breed(DataSource::Url(&url)).await?;
+      if self
+        .filter
+        .matches(&breed.main_breed, breed.sub_breed.as_deref())
+      {
+        return Ok(url);
+      }
+      debug!("Skipping {url}, {} is filtered out", breed.display_name);
+    }
+    Err(Error::NotFound(format!(
+      "no photo matching {} after {} tries",
+      self.filter,
+      self.retries + 1
+    )))
+  }
+
+  async fn breed(&self, source: DataSource<'_>) -> Result<Breed> {
+    self.inner.breed(source).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::data::filter::BreedKey;
+  use std::sync::atomic::{AtomicUsize, Ordering};
+
+  /// Serves `photos` in turn, named after their breed.
+  struct Scripted {
+    photos: Vec<&'static str>,
+    calls: AtomicUsize
+  }
+
+  #[async_trait]
+  impl Content for Scripted {
+    fn name(&self) -> &'static str {
+      "scripted"
+    }
+
+    async fn photo(&self, _: DataSource<'_>) -> Result<String> {
+      let call = self.calls.fetch_add(1, Ordering::SeqCst);
+      Ok(self.photos[call.min(self.photos.len() - 1)].to_string())
+    }
+
+    async fn breed(&self, source: DataSource<'_>) -> Result<Breed> {
+      let DataSource::Url(url) = source else {
+        return Err(Error::EmptyResponse);
+      };
+      Ok(Breed::new(url, None::<String>, "", capitalize(url)))
+    }
+  }
+
+  fn filtered(photos: Vec<&'static str>, retries: usize) -> Filtered<Scripted> {
+    let inner = Scripted {
+      photos,
+      calls: AtomicUsize::new(0)
+    };
+    let filter =
+      BreedFilter::Except(vec![BreedKey::new("pug", None::<String>)]);
+    Filtered::new(inner, filter, retries)
+  }
+
+  #[tokio::test]
+  async fn test_filtered_skips_excluded_breeds() {
+    let content = filtered(vec!["pug", "pug", "boxer"], 3);
+    let photo = content.photo(DataSource::Url("")).await.unwrap();
+    assert_eq!(photo, "boxer");
+    assert_eq!(content.inner.calls.load(Ordering::SeqCst), 3);
+  }
+
+  #[tokio::test]
+  async fn test_filtered_fails_after_retries() {
+    let content = filtered(vec!["pug"], 2);
+    let result = content.photo(DataSource::Url("")).await;
+    assert!(matches!(result, Err(Error::NotFound(_))));
+    assert_eq!(content.inner.calls.load(Ordering::SeqCst), 3);
+  }
+}
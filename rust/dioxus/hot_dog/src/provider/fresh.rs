@@ -0,0 +1,111 @@
+use super::prelude::*;
+use crate::data::history::History;
+use std::sync::{Arc, Mutex};
+
+/// Wraps a [`Content`] provider so `photo` retries, up to `retries` times,
+/// while it returns a URL already in the [`History`].
+///
+/// Every photo served is added to the history. When the retries run out the
+/// last repeat is served rather than failing.
+pub struct Fresh<C> {
+  inner: C,
+  history: Arc<Mutex<History>>,
+  retries: usize
+}
+
+impl<C> Fresh<C> {
+  pub fn new(inner: C, history: Arc<Mutex<History>>, retries: usize) -> Self {
+    Self {
+      inner,
+      history,
+      retries
+    }
+  }
+
+  fn seen(&self, url: &str) -> bool {
+    self
+      .history
+      .lock()
+      .expect("history lock poisoned")
+      .contains(url)
+  }
+}
+
+#[async_trait]
+impl<C: Content + Send + Sync> Content for Fresh<C> {
+  fn name(&self) -> &'static str {
+    self.inner.name()
+  }
+
+  async fn photo(&self, source: DataSource<'_>) -> Result<String> {
+    let mut attempt = 0;
+    loop {
+      let url = self.inner.photo(source.clone()).await?;
+      if !self.seen(&url) || attempt == self.retries {
+        let mut history = self.history.lock().expect("history lock poisoned");
+        history.insert(url.clone());
+        return Ok(url);
+      }
+      debug!("Skipping {url}, already served");
+      attempt += 1;
+    }
+  }
+
+  async fn breed(&self, source: DataSource<'_>) -> Result<Breed> {
+    self.inner.breed(source).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::sync::atomic::{AtomicUsize, Ordering};
+
+  /// Serves `photos` in turn, then repeats the last one.
+  struct Scripted {
+    photos: Vec<&'static str>,
+    calls: AtomicUsize
+  }
+
+  #[async_trait]
+  impl Content for Scripted {
+    fn name(&self) -> &'static str {
+      "scripted"
+    }
+
+    async fn photo(&self, _: DataSource<'_>) -> Result<String> {
+      let call = self.calls.fetch_add(1, Ordering::SeqCst);
+      Ok(self.photos[call.min(self.photos.len() - 1)].to_string())
+    }
+
+    async fn breed(&self, _: DataSource<'_>) -> Result<Breed> {
+      Err(Error::EmptyResponse)
+    }
+  }
+
+  fn fresh(photos: Vec<&'static str>, retries: usize) -> Fresh<Scripted> {
+    let history = History::from_urls(10, ["a".to_string()]);
+    let inner = Scripted {
+      photos,
+      calls: AtomicUsize::new(0)
+    };
+    Fresh::new(inner, Arc::new(Mutex::new(history)), retries)
+  }
+
+  #[tokio::test]
+  async fn test_fresh_skips_seen_photos() {
+    let content = fresh(vec!["a", "a", "b"], 3);
+    let photo = content.photo(DataSource::Url("")).await.unwrap();
+    assert_eq!(photo, "b");
+    assert_eq!(content.inner.calls.load(Ordering::SeqCst), 3);
+    assert!(content.seen("b"));
+  }
+
+  #[tokio::test]
+  async fn test_fresh_gives_up_after_retries() {
+    let content = fresh(vec!["a"], 2);
+    let photo = content.photo(DataSource::Url("")).await.unwrap();
+    assert_eq!(photo, "a");
+    assert_eq!(content.inner.calls.load(Ordering::SeqCst), 3);
+  }
+}
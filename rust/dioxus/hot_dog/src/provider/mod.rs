@@ -1,14 +1,27 @@
 //|-> Modules
 mod custom;
 mod default;
+#[cfg(feature = "server")]
+mod distinct;
 mod dog_ceo;
+mod filtered;
+mod fresh;
+mod observe;
+#[cfg(feature = "server")]
+pub mod offline;
 mod prelude;
 
 //|-> Internal Exports
+pub use observe::Observed;
 pub use prelude::*;
 
 //|-> External Exports
 pub use default::Provider;
+#[cfg(feature = "server")]
+pub use distinct::{Distinct, SeenByOwner, SeenPhotos};
+pub use dog_ceo::{BreedsApiResponse, Provider as DogCeo};
+pub use filtered::Filtered;
+pub use fresh::Fresh;
 pub use prelude::DataSource;
 // pub use default::Config;
 // pub use provider::Provider;
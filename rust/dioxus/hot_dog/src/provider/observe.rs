@@ -0,0 +1,67 @@
+use super::prelude::*;
+use std::future::Future;
+use tracing::{Instrument, Span, field, info_span};
+use web_time::Instant;
+
+/// Wraps a [`Content`] provider so every call is traced and measured.
+///
+/// Each `photo`/`breed` invocation runs inside a `content` span carrying the
+/// provider, operation, source kind and outcome, and feeds the request,
+/// failure and latency metrics in [`metrics`].
+pub struct Observed<C>(pub C);
+
+async fn observe<T, F>(
+  provider: &'static str,
+  operation: &'static str,
+  source: &'static str,
+  call: F
+) -> Result<T>
+where
+  F: Future<Output = Result<T>>
+{
+  let span = info_span!(
+    "content",
+    provider,
+    operation,
+    source,
+    outcome = field::Empty
+  );
+
+  async move {
+    metrics::record_request(provider, operation);
+    let start = Instant::now();
+    let result = call.await;
+    metrics::record_latency(provider, operation, start.elapsed());
+
+    match &result {
+      Ok(_) => {
+        Span::current().record("outcome", "ok");
+      }
+      Err(e) => {
+        Span::current().record("outcome", e.kind());
+        metrics::record_failure(provider, operation, e.kind());
+        debug!("{provider} {operation} failed: {e}");
+      }
+    }
+    result
+  }
+  .instrument(span)
+  .await
+}
+
+#[async_trait]
+impl<C: Content + Send + Sync> Content for Observed<C> {
+  fn name(&self) -> &'static str {
+    self.0.name()
+  }
+
+  async fn photo(&self, source: DataSource<'_>) -> Result<String> {
+    let kind = source.kind();
+    observe(self.name(), "photo", kind, self.0.photo(source)).await
+  }
+
+  async fn breed(&self, source: DataSource<'_>) -> Result<Breed> {
+    let kind = source.kind();
+    observe(self.name(), "breed", kind, self.0.breed(source)).await
+  }
+}
@@ -0,0 +1,258 @@
+use super::{dog_ceo, prelude::*};
+use crate::data::{
+  Dog,
+  bundle::{self, Manifest}
+};
+use std::{
+  collections::HashMap,
+  io::{Cursor, Read, Write},
+  path::Path,
+  sync::OnceLock
+};
+use zip::{CompressionMethod, ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+//~@ The bundle the whole app runs from, if one was mounted at startup
+static MOUNTED: OnceLock<Bundle> = OnceLock::new();
+
+/// Favorites packed for use without a network: their photos, a manifest of
+/// their metadata and a snapshot of the dog.ceo breed list.
+pub struct Bundle {
+  pub manifest: Manifest,
+  pub breeds: dog_ceo::BreedsApiResponse,
+  images: HashMap<String, Vec<u8>>
+}
+
+impl Bundle {
+  pub fn new(breeds: dog_ceo::BreedsApiResponse, created_at: i64) -> Self {
+    Self {
+      manifest: Manifest::new(created_at),
+      breeds,
+      images: HashMap::new()
+    }
+  }
+
+  /// Adds `dog` with its photo bytes; photos already bundled are skipped.
+  pub fn add(&mut self, dog: Dog, photo: Vec<u8>) {
+    if let Some(file) = self.manifest.push(dog) {
+      self.images.insert(file, photo);
+    }
+  }
+
+  /// The bundled photo of `photo_url`, if there is one.
+  pub fn image(&self, photo_url: &str) -> Option<&[u8]> {
+    let entry = self.manifest.find(photo_url)?;
+    self.images.get(&entry.file).map(Vec::as_slice)
+  }
+
+  /// Packs the bundle into a zip archive.
+  ///
+  /// Photos are stored as-is since they're compressed already; only the
+  /// JSON entries are deflated.
+  pub fn to_zip(&self) -> Result<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let json = SimpleFileOptions::default()
+      .compression_method(CompressionMethod::Deflated);
+    let stored = SimpleFileOptions::default()
+      .compression_method(CompressionMethod::Stored);
+
+    zip.start_file(bundle::MANIFEST, json)?;
+    zip.write_all(&serde_json::to_vec_pretty(&self.manifest)?)?;
+    zip.start_file(bundle::BREEDS, json)?;
+    zip.write_all(&serde_json::to_vec_pretty(&self.breeds)?)?;
+    for entry in &self.manifest.dogs {
+      if let Some(bytes) = self.images.get(&entry.file) {
+        zip.start_file(entry.file.as_str(), stored)?;
+        zip.write_all(bytes)?;
+      }
+    }
+    Ok(zip.finish()?.into_inner())
+  }
+
+  /// Unpacks an archive written by [`Bundle::to_zip`].
+  pub fn from_zip(bytes: &[u8]) -> Result<Self> {
+    let mut zip = ZipArchive::new(Cursor::new(bytes))?;
+    let mut read = |name: &str| -> Result<Vec<u8>> {
+      let mut file = zip.by_name(name)?;
+      let mut bytes = Vec::with_capacity(file.size() as usize);
+      file.read_to_end(&mut bytes)?;
+      Ok(bytes)
+    };
+
+    let manifest: Manifest = serde_json::from_slice(&read(bundle::MANIFEST)?)?;
+    if manifest.version > bundle::VERSION {
+      return Err(Error::Archive(format!(
+        "bundle version {} is newer than supported {}",
+        manifest.version,
+        bundle::VERSION
+      )));
+    }
+    let breeds = serde_json::from_slice(&read(bundle::BREEDS)?)?;
+
+    let mut images = HashMap::with_capacity(manifest.len());
+    for entry in &manifest.dogs {
+      match read(&entry.file) {
+        Ok(bytes) => {
+          images.insert(entry.file.clone(), bytes);
+        }
+        Err(e) => warn!("Bundle is missing {}: {e}", entry.file)
+      }
+    }
+    Ok(Self {
+      manifest,
+      breeds,
+      images
+    })
+  }
+
+  pub async fn open(path: impl AsRef<Path>) -> Result<Self> {
+    let bytes = tokio::fs::read(path).await?;
+    Self::from_zip(&bytes)
+  }
+
+  /// The bundled dog whose photo is `photo_url`.
+  pub fn dog(&self, photo_url: &str) -> Result<&Dog> {
+    self
+      .manifest
+      .find(photo_url)
+      .map(|entry| &entry.dog)
+      .ok_or_else(|| Error::NotFound(format!("{photo_url} is not bundled")))
+  }
+
+  /// A bundled dog drawn from `rng`.
+  fn pick(&self, rng: &mut rng::Rng) -> Result<&Dog> {
+    let dogs = &self.manifest.dogs;
+    if dogs.is_empty() {
+      return Err(Error::EmptyResponse);
+    }
+    Ok(&dogs[rng.below(dogs.len())].dog)
+  }
+}
+
+/// Mounts the bundle at `path` for the rest of the process.
+///
+/// Its breed snapshot replaces the dog.ceo breed list, and its photos are
+/// served wherever the server would otherwise download them. Only the
+/// first mount takes effect.
+pub async fn mount(path: impl AsRef<Path>) -> Result<&'static Bundle> {
+  if let Some(bundle) = MOUNTED.get() {
+    return Ok(bundle);
+  }
+  let bundle = Bundle::open(path.as_ref()).await?;
+  dog_ceo::Provider::prime_breeds(bundle.breeds.clone());
+  info!(
+    "Mounted offline bundle {} with {} dogs",
+    path.as_ref().display(),
+    bundle.manifest.len()
+  );
+  Ok(MOUNTED.get_or_init(|| bundle))
+}
+
+/// The mounted bundle, if the app is running offline.
+pub fn mounted() -> Option<&'static Bundle> {
+  MOUNTED.get()
+}
+
+/// The mounted bundle's dog for `seed`, stable for as long as the same
+/// bundle is mounted.
+pub fn seeded_dog(seed: u64) -> Result<Dog> {
+  let bundle = mounted().ok_or_else(not_mounted)?;
+  bundle.pick(&mut rng::Rng::new(seed)).cloned()
+}
+
+fn not_mounted() -> Error {
+  Error::Provider("no offline bundle mounted".into())
+}
+
+/// Serves dogs from an offline [`Bundle`].
+///
+/// A `File` source mounts the archive at that path, `Raw` reads an archive
+/// from memory, and a `Url` means the mounted bundle. Photos are drawn at
+/// random; a breed is looked up by photo URL, and only bundled photos have
+/// one.
+pub struct Provider;
+
+impl Provider {
+  /// A random dog from the bundle `source` names.
+  async fn random_dog(source: DataSource<'_>) -> Result<Dog> {
+    let mut rng = rng::Rng::from_time();
+    match source {
+      DataSource::File(path) => mount(path).await?.pick(&mut rng).cloned(),
+      DataSource::Raw(bytes) =>
+        Bundle::from_zip(bytes)?.pick(&mut rng).cloned(),
+      DataSource::Url(_) =>
+        mounted().ok_or_else(not_mounted)?.pick(&mut rng).cloned(),
+    }
+  }
+}
+
+#[async_trait]
+impl Content for Provider {
+  fn name(&self) -> &'static str {
+    "offline"
+  }
+
+  async fn photo(&self, source: DataSource<'_>) -> Result<String> {
+    Ok(Self::random_dog(source).await?.photo_url)
+  }
+
+  async fn breed(&self, source: DataSource<'_>) -> Result<Breed> {
+    let dog = match source {
+      DataSource::Url(url) =>
+        mounted().ok_or_else(not_mounted)?.dog(url)?.clone(),
+      _ => Self::random_dog(source).await?
+    };
+    Ok(Breed::new(
+      dog.breed,
+      dog.sub_breed,
+      dog.reference_url,
+      dog.display_name
+    ))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PHOTO: &str =
+    "https://images.dog.ceo/breeds/hound-afghan/n02088094_1003.jpg";
+
+  fn bundle() -> Bundle {
+    let breeds = dog_ceo::BreedsApiResponse {
+      message: HashMap::from([("hound".into(), vec!["afghan".into()])]),
+      status: "success".into()
+    };
+    let mut bundle = Bundle::new(breeds, 1_700_000_000);
+    let dog = Dog {
+      photo_url: PHOTO.into(),
+      breed: "hound".into(),
+      sub_breed: Some("afghan".into()),
+      display_name: "Afghan Hound".into(),
+      reference_url: "https://dog.ceo/api/breed/hound/afghan".into()
+    };
+    bundle.add(dog, vec![0xff, 0xd8, 0xff]);
+    bundle
+  }
+
+  #[test]
+  fn test_zips_round_trip() {
+    let zip = bundle().to_zip().unwrap();
+    let read = Bundle::from_zip(&zip).unwrap();
+    assert_eq!(read.manifest, bundle().manifest);
+    assert_eq!(read.breeds.message, bundle().breeds.message);
+    assert_eq!(read.image(PHOTO), Some(&[0xff, 0xd8, 0xff][..]));
+    assert_eq!(read.dog(PHOTO).unwrap().display_name, "Afghan Hound");
+    let unknown = read.dog("https://images.dog.ceo/breeds/pug/1.jpg");
+    assert!(matches!(unknown, Err(Error::NotFound(_))));
+  }
+
+  #[tokio::test]
+  async fn test_serves_dogs_from_raw_archives() {
+    let zip = bundle().to_zip().unwrap();
+    let photo = Provider.photo(DataSource::Raw(&zip)).await.unwrap();
+    assert_eq!(photo, PHOTO);
+    let breed = Provider.breed(DataSource::Raw(&zip)).await.unwrap();
+    assert_eq!(breed.display_name, "Afghan Hound");
+    assert_eq!(breed.sub_breed.as_deref(), Some("afghan"));
+  }
+}
@@ -1,5 +1,6 @@
 pub use crate::prelude::*;
 use crate::utils::format::capitalize_first_letter;
+pub use crate::utils::metrics;
 pub use async_trait::async_trait;
 pub use std::path::Path;
 use tokio::fs;
@@ -12,9 +13,21 @@ pub enum DataSource<'a> {
   Raw(&'a [u8])
 }
 
+impl DataSource<'_> {
+  /// A short label for the source variant, used in spans and metrics.
+  pub fn kind(&self) -> &'static str {
+    match self {
+      DataSource::Url(_) => "url",
+      DataSource::File(_) => "file",
+      DataSource::Raw(_) => "raw"
+    }
+  }
+}
+
 // Simplified Content trait - providers handle their own parsing
 #[async_trait]
 pub trait Content {
+  fn name(&self) -> &'static str;
   async fn photo(&self, source: DataSource<'_>) -> Result<String>;
   async fn breed(&self, source: DataSource<'_>) -> Result<Breed>;
 }
@@ -50,12 +63,54 @@ impl Breed {
   }
 }
 
+//~@ Provider responses larger than this are refused
+const MAX_RESPONSE_BYTES: usize = 4 * 1024 * 1024;
+
+/// Reads `response`'s body a chunk at a time, giving up with `None` as soon
+/// as it passes `limit` bytes rather than buffering the rest.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn read_limited(
+  mut response: reqwest::Response,
+  limit: usize
+) -> Result<Option<Vec<u8>>> {
+  if response
+    .content_length()
+    .is_some_and(|len| len as usize > limit)
+  {
+    return Ok(None);
+  }
+  let mut body = Vec::new();
+  while let Some(chunk) = response.chunk().await? {
+    if body.len() + chunk.len() > limit {
+      return Ok(None);
+    }
+    body.extend_from_slice(&chunk);
+  }
+  Ok(Some(body))
+}
+
+//~@ The browser's fetch only hands over whole bodies
+#[cfg(target_arch = "wasm32")]
+pub async fn read_limited(
+  response: reqwest::Response,
+  limit: usize
+) -> Result<Option<Vec<u8>>> {
+  let bytes = response.bytes().await?;
+  Ok((bytes.len() <= limit).then(|| bytes.to_vec()))
+}
+
 // Helper functions for common parsing patterns
 pub async fn fetch_data(source: DataSource<'_>) -> Result<Vec<u8>> {
   match source {
     DataSource::Url(url) => {
       let response = reqwest::get(url).await?;
-      Ok(response.bytes().await?.to_vec())
+      read_limited(response, MAX_RESPONSE_BYTES)
+        .await?
+        .ok_or_else(|| {
+          Error::Provider(format!(
+            "{url} is larger than {MAX_RESPONSE_BYTES} bytes"
+          ))
+        })
     }
     DataSource::File(path) => Ok(fs::read(path).await?),
     DataSource::Raw(data) => Ok(data.to_vec())
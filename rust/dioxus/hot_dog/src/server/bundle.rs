@@ -0,0 +1,136 @@
+use super::prelude::*;
+use crate::{
+  analysis, auth,
+  data::bundle::{self, DOWNLOAD_PATH, PHOTO_PATH},
+  provider::{DogCeo, offline},
+  storage::{favorites, with_db},
+  utils::{proxy, time}
+};
+use axum::{extract::Query, http::HeaderMap};
+use futures::{StreamExt, stream};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+//~@ Path of a bundle to run from instead of the network
+const MOUNT_ENV: &str = "HOT_DOG_BUNDLE";
+
+//~@ Photos downloaded at once while packing
+const CONCURRENCY: usize = 8;
+
+//~@ Bundled photos never change, so browsers may keep them forever
+const CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+#[derive(Debug, Deserialize)]
+struct Params {
+  ids: Option<String>
+}
+
+#[derive(Debug, Deserialize)]
+struct PhotoParams {
+  url: String
+}
+
+pub fn routes() -> Router {
+  Router::new()
+    .route(DOWNLOAD_PATH, get(download))
+    .route(PHOTO_PATH, get(photo))
+}
+
+/// Mounts the bundle named by `HOT_DOG_BUNDLE`, if set.
+///
+/// A bundle that fails to open is logged and the app stays online.
+pub async fn mount_from_env() {
+  let Ok(path) = std::env::var(MOUNT_ENV) else {
+    return;
+  };
+  if let Err(e) = offline::mount(&path).await {
+    error!("Failed to mount offline bundle {path}: {e}");
+  }
+}
+
+/// Serves the mounted bundle's copy of the photo at `url`.
+async fn photo(Query(params): Query<PhotoParams>) -> Response {
+  let Some(bytes) = offline::mounted().and_then(|b| b.image(&params.url))
+  else {
+    return (StatusCode::NOT_FOUND, "Photo not bundled").into_response();
+  };
+  let mime = image::guess_format(bytes)
+    .map_or("application/octet-stream", |format| format.to_mime_type());
+  (
+    [
+      (header::CONTENT_TYPE, mime),
+      (header::CACHE_CONTROL, CACHE_CONTROL)
+    ],
+    bytes.to_vec()
+  )
+    .into_response()
+}
+
+/// Packs the signed-in user's favorites listed in `ids`, or all of them,
+/// into a zip archive.
+async fn download(
+  headers: HeaderMap,
+  Query(params): Query<Params>
+) -> Response {
+  match pack(&headers, params.ids.as_deref()).await {
+    Ok(zip) => (
+      [
+        (header::CONTENT_TYPE, "application/zip"),
+        (
+          header::CONTENT_DISPOSITION,
+          "attachment; filename=\"hot-dog-bundle.zip\""
+        )
+      ],
+      zip
+    )
+      .into_response(),
+    Err(Error::Auth(e)) => (StatusCode::UNAUTHORIZED, e).into_response(),
+    Err(Error::NotFound(e)) =>
+      (StatusCode::NOT_FOUND, format!("Nothing to bundle: {e}")).into_response(),
+    Err(e) => {
+      warn!("Failed to pack a bundle: {e}");
+      (StatusCode::BAD_GATEWAY, e.to_string()).into_response()
+    }
+  }
+}
+
+async fn pack(headers: &HeaderMap, ids: Option<&str>) -> Result<Vec<u8>> {
+  let owner = auth::user_from(headers)?
+    .ok_or_else(|| Error::Auth("no session, reload the page".into()))?
+    .id;
+  let ids = ids.map(bundle::parse_ids).unwrap_or_default();
+  //~@ Photo URLs came from clients, so only fetch from allowed hosts
+  let chosen: Vec<_> = with_db(|db| favorites::list(db, owner))?
+    .into_iter()
+    .filter(|f| ids.is_empty() || ids.contains(&f.id))
+    .filter(|f| proxy::is_allowed(&f.dog.photo_url))
+    .collect();
+  if chosen.is_empty() {
+    return Err(Error::NotFound("no matching favorites".into()));
+  }
+
+  let breeds = DogCeo::get_breeds(None::<PathBuf>, None).await?;
+  let mut archive = offline::Bundle::new(breeds, time::now());
+  let mut photos = stream::iter(chosen)
+    .map(|favorite| async move {
+      let photo = analysis::download(&favorite.dog.photo_url).await;
+      (favorite, photo)
+    })
+    .buffered(CONCURRENCY);
+  while let Some((favorite, photo)) = photos.next().await {
+    match photo {
+      Ok(bytes) => archive.add(favorite.dog, bytes),
+      Err(e) =>
+        warn!("Leaving {} out of the bundle: {e}", favorite.dog.photo_url),
+    }
+  }
+  if archive.manifest.is_empty() {
+    return Err(Error::Archive(
+      "none of the photos could be downloaded".into()
+    ));
+  }
+
+  tokio::task::spawn_blocking(move || archive.to_zip())
+    .await
+    .map_err(|e| Error::Archive(e.to_string()))?
+}
@@ -0,0 +1,186 @@
+use super::prelude::*;
+use crate::{
+  analysis,
+  utils::{
+    metrics,
+    proxy::{self, Format, PROXY_PATH}
+  }
+};
+use axum::extract::Query;
+use image::{
+  DynamicImage, ImageEncoder,
+  codecs::{avif::AvifEncoder, jpeg::JpegEncoder, webp::WebPEncoder},
+  imageops::FilterType
+};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+//~@ Where rendered variants are kept between requests
+const CACHE_DIR: &str = "assets/cache/images";
+
+//~@ Variants never change for a given URL, so browsers may keep them forever
+const CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+#[derive(Debug, Deserialize)]
+struct Params {
+  url: String,
+  w: Option<u32>,
+  format: Option<String>
+}
+
+pub fn routes() -> Router {
+  Router::new().route(PROXY_PATH, get(serve))
+}
+
+/// Serves `url` resized to `w` pixels and re-encoded as `format`.
+async fn serve(Query(params): Query<Params>) -> Response {
+  if !proxy::is_allowed(&params.url) {
+    return (StatusCode::FORBIDDEN, "Host not allowed").into_response();
+  }
+  let Some(format) = params
+    .format
+    .as_deref()
+    .map_or(Some(Format::default()), Format::parse)
+  else {
+    return (StatusCode::BAD_REQUEST, "Unsupported format").into_response();
+  };
+  let width = proxy::clamp_width(params.w.unwrap_or(proxy::WIDTHS[1]));
+
+  match variant(&params.url, width, format).await {
+    Ok(bytes) => (
+      [
+        (header::CONTENT_TYPE, format.mime()),
+        (header::CACHE_CONTROL, CACHE_CONTROL)
+      ],
+      bytes
+    )
+      .into_response(),
+    Err(e) => {
+      warn!("Failed to proxy {}: {e}", params.url);
+      (StatusCode::BAD_GATEWAY, e.to_string()).into_response()
+    }
+  }
+}
+
+/// Returns the cached variant, rendering and caching it on a miss.
+async fn variant(url: &str, width: u32, format: Format) -> Result<Vec<u8>> {
+  let path = cache_path(url, width, format);
+  if let Ok(bytes) = tokio::fs::read(&path).await {
+    metrics::record_cache("images", "disk");
+    return Ok(bytes);
+  }
+  metrics::record_cache("images", "miss");
+
+  let original = analysis::download(url).await?;
+  let bytes =
+    tokio::task::spawn_blocking(move || transform(&original, width, format))
+      .await
+      .map_err(|e| Error::Image(e.to_string()))??;
+
+  //~@ Caching is best-effort; a read-only disk still serves the image
+  if let Err(e) = store(&path, &bytes).await {
+    warn!("Failed to cache {}: {e}", path.display());
+  }
+  Ok(bytes)
+}
+
+async fn store(path: &Path, bytes: &[u8]) -> Result<()> {
+  if let Some(parent) = path.parent() {
+    tokio::fs::create_dir_all(parent).await?;
+  }
+  tokio::fs::write(path, bytes).await?;
+  Ok(())
+}
+
+fn cache_path(url: &str, width: u32, format: Format) -> PathBuf {
+  let digest = Sha256::digest(url.as_bytes());
+  let key: String = digest[..12].iter().map(|b| format!("{b:02x}")).collect();
+  PathBuf::from(CACHE_DIR).join(format!("{key}-{width}.{}", format.as_str()))
+}
+
+/// Decodes `original`, shrinks it to at most `width` pixels wide and encodes
+/// it as `format`.
+///
+/// Re-encoding from raw pixels drops EXIF and every other metadata block.
+pub fn transform(
+  original: &[u8],
+  width: u32,
+  format: Format
+) -> Result<Vec<u8>> {
+  let mut image = image::load_from_memory(original)?;
+  if image.width() > width {
+    image = image.resize(width, u32::MAX, FilterType::Lanczos3);
+  }
+  //~@ Photos have no alpha, and JPEG can't store it anyway
+  let image = DynamicImage::ImageRgb8(image.to_rgb8());
+
+  let mut bytes = Vec::new();
+  let (w, h, color) = (image.width(), image.height(), image.color().into());
+  match format {
+    Format::Webp => WebPEncoder::new_lossless(&mut bytes).write_image(
+      image.as_bytes(),
+      w,
+      h,
+      color
+    )?,
+    Format::Avif => AvifEncoder::new_with_speed_quality(&mut bytes, 8, 70)
+      .write_image(image.as_bytes(), w, h, color)?,
+    Format::Jpeg => JpegEncoder::new_with_quality(&mut bytes, 80).write_image(
+      image.as_bytes(),
+      w,
+      h,
+      color
+    )?
+  }
+  Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use image::{ImageFormat, RgbImage};
+  use std::io::Cursor;
+
+  fn jpeg(width: u32, height: u32) -> Vec<u8> {
+    let image = RgbImage::from_fn(width, height, |x, y| {
+      image::Rgb([(x % 256) as u8, (y % 256) as u8, 128])
+    });
+    let mut bytes = Cursor::new(Vec::new());
+    image.write_to(&mut bytes, ImageFormat::Jpeg).unwrap();
+    bytes.into_inner()
+  }
+
+  #[test]
+  fn test_transform_resizes_and_converts() {
+    let original = jpeg(800, 600);
+
+    for format in Format::ALL {
+      let bytes = transform(&original, 320, format).unwrap();
+      let decoded = image::load_from_memory(&bytes);
+      //~@ The avif feature only encodes; just check the container
+      if format == Format::Avif {
+        assert_eq!(&bytes[4..8], b"ftyp");
+        continue;
+      }
+      let decoded = decoded.unwrap();
+      assert_eq!((decoded.width(), decoded.height()), (320, 240));
+    }
+
+    //~@ Small images are never upscaled
+    let bytes = transform(&jpeg(100, 50), 640, Format::Jpeg).unwrap();
+    assert_eq!(image::load_from_memory(&bytes).unwrap().width(), 100);
+  }
+
+  #[test]
+  fn test_cache_path_is_stable() {
+    let a = cache_path("https://images.dog.ceo/a.jpg", 320, Format::Webp);
+    let b = cache_path("https://images.dog.ceo/a.jpg", 640, Format::Webp);
+    assert_eq!(
+      a,
+      cache_path("https://images.dog.ceo/a.jpg", 320, Format::Webp)
+    );
+    assert_ne!(a, b);
+    assert!(a.to_string_lossy().ends_with("-320.webp"));
+  }
+}
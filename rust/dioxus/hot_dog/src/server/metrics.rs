@@ -0,0 +1,15 @@
+use super::prelude::*;
+use crate::utils::metrics;
+
+pub fn routes() -> Router {
+  Router::new().route("/metrics", get(prometheus))
+}
+
+/// Serves the provider metrics in the Prometheus text format.
+async fn prometheus() -> Response {
+  (
+    [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+    metrics::snapshot().to_prometheus()
+  )
+    .into_response()
+}
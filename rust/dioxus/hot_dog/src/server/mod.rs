@@ -0,0 +1,88 @@
+//|-> Modules
+mod bundle;
+mod images;
+mod metrics;
+pub mod sync;
+
+//|-> Internal Exports
+mod prelude {
+  pub use crate::prelude::*;
+  pub use axum::{
+    Router,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get
+  };
+}
+
+//|-> External Exports
+use crate::{
+  storage::{self, history, users, with_db},
+  utils::time
+};
+use prelude::*;
+use std::time::Duration;
+
+//~@ How often stale sessions and guests are cleaned up
+const CLEANUP_EVERY: Duration = Duration::from_secs(3600);
+
+//~@ Seconds a guest may go without a session before it is deleted
+const ABANDONED_AFTER: i64 = 86_400;
+
+//~@ Seconds a browser session's served photos are remembered
+const SESSION_HISTORY: i64 = 86_400;
+
+/// Extra HTTP routes served next to the Dioxus application.
+pub fn routes() -> Router {
+  Router::new()
+    .merge(bundle::routes())
+    .merge(images::routes())
+    .merge(metrics::routes())
+    .merge(sync::routes())
+}
+
+/// Serves the app together with [`routes`], replacing `dioxus::launch`.
+///
+/// Set `HOT_DOG_BUNDLE` to a bundle's path to run from it without a network.
+pub fn launch(app: fn() -> Element) {
+  tokio::runtime::Runtime::new()
+    .expect("Failed to start the server runtime")
+    .block_on(async move {
+      storage::init().expect("Failed to open the database");
+      bundle::mount_from_env().await;
+      tokio::spawn(clean_up());
+
+      let address = dioxus::cli_config::fullstack_address_or_localhost();
+      let config = ServeConfig::new().expect("Failed to load index.html");
+      let router = routes().serve_dioxus_application(config, app);
+
+      info!("Serving on http://{address}");
+      let listener = tokio::net::TcpListener::bind(address)
+        .await
+        .expect("Failed to bind the server address");
+      axum::serve(listener, router.into_make_service())
+        .await
+        .expect("Server stopped unexpectedly");
+    });
+}
+
+// -- Private Helper --
+
+/// Deletes expired sessions, the guests nobody can reach any more and old
+/// session history, once at startup and then every [`CLEANUP_EVERY`].
+async fn clean_up() {
+  let mut ticks = tokio::time::interval(CLEANUP_EVERY);
+  loop {
+    ticks.tick().await;
+    let now = time::now();
+    match with_db(|db| users::prune(db, now, now - ABANDONED_AFTER)) {
+      Ok(0) => {}
+      Ok(removed) => info!("Removed {removed} abandoned guests"),
+      Err(e) => warn!("Failed to clean up sessions: {e}")
+    }
+    let seen_before = now - SESSION_HISTORY;
+    if let Err(e) = with_db(|db| history::prune_sessions(db, seen_before)) {
+      warn!("Failed to clean up session history: {e}");
+    }
+  }
+}
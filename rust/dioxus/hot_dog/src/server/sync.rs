@@ -0,0 +1,162 @@
+use super::prelude::*;
+use crate::{
+  auth,
+  data::sync::{Change, Event, Log}
+};
+use axum::{
+  extract::Query,
+  http::HeaderMap,
+  response::sse::{self, KeepAlive, Sse}
+};
+use futures::{Stream, StreamExt, stream};
+use serde::Deserialize;
+use std::{
+  collections::HashMap,
+  sync::{Mutex, OnceLock}
+};
+use tokio::sync::broadcast::{self, Receiver, error::RecvError};
+
+pub const EVENTS_PATH: &str = "/sync/events";
+
+//~@ Live events buffered per client before it's told to resync
+const BACKLOG: usize = 64;
+
+/// Every change since the server started, and a channel per owner for their
+/// live clients, so one busy user can't push another's clients behind.
+struct Hub {
+  log: Mutex<Log>,
+  channels: Mutex<HashMap<i64, broadcast::Sender<Event>>>
+}
+
+fn hub() -> &'static Hub {
+  static HUB: OnceLock<Hub> = OnceLock::new();
+  HUB.get_or_init(|| Hub {
+    log: Mutex::new(Log::default()),
+    channels: Mutex::new(HashMap::new())
+  })
+}
+
+impl Hub {
+  /// Listens for `owner`'s live events, opening their channel if needed.
+  fn subscribe(&self, owner: i64) -> Receiver<Event> {
+    let mut channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+    channels
+      .entry(owner)
+      .or_insert_with(|| broadcast::channel(BACKLOG).0)
+      .subscribe()
+  }
+}
+
+#[derive(Debug, Deserialize)]
+struct Params {
+  since: Option<u64>
+}
+
+pub fn routes() -> Router {
+  Router::new().route(EVENTS_PATH, get(events))
+}
+
+/// Tells every open client of `owner` about `change`.
+pub fn publish(owner: i64, change: Change) {
+  let hub = hub();
+  //~@ Sent under the lock so live events arrive in sequence order
+  let mut log = hub.log.lock().unwrap_or_else(|e| e.into_inner());
+  let event = log.push(owner, change);
+  let mut channels = hub.channels.lock().unwrap_or_else(|e| e.into_inner());
+  //~@ Fails only when nobody is listening, so the channel can go
+  if let Some(sender) = channels.get(&owner) {
+    if sender.send(event).is_err() {
+      channels.remove(&owner);
+    }
+  }
+}
+
+/// Streams the signed-in user's changes as server-sent events.
+///
+/// A client that reconnects with `Last-Event-ID` or `?since=` first gets the
+/// events it missed, or a resync when they're gone.
+async fn events(headers: HeaderMap, Query(params): Query<Params>) -> Response {
+  let owner = match auth::user_from(&headers) {
+    Ok(Some(user)) => user.id,
+    Ok(None) =>
+      return (StatusCode::UNAUTHORIZED, "no session, reload the page")
+        .into_response(),
+    Err(e) => {
+      warn!("Failed to look up a sync session: {e}");
+      return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        .into_response();
+    }
+  };
+  let since = headers
+    .get("last-event-id")
+    .and_then(|id| id.to_str().ok()?.parse().ok())
+    .or(params.since);
+
+  //{ Subscribe before reading the log so nothing falls in between }
+  let hub = hub();
+  let receiver = hub.subscribe(owner);
+  let (missed, last) = {
+    let log = hub.log.lock().unwrap_or_else(|e| e.into_inner());
+    let missed = since.map(|seq| log.since(owner, seq)).unwrap_or_default();
+    (missed, log.last())
+  };
+
+  let stream = stream::iter(missed)
+    .chain(live(receiver, last))
+    .map(|event| {
+      sse::Event::default()
+        .id(event.seq.to_string())
+        .json_data(&event)
+    });
+  Sse::new(stream)
+    .keep_alive(KeepAlive::default())
+    .into_response()
+}
+
+// -- Private Helper --
+
+/// Events on `receiver` published after `last`, with a resync in place of
+/// any that were dropped because the client fell behind.
+fn live(receiver: Receiver<Event>, last: u64) -> impl Stream<Item = Event> {
+  stream::unfold((receiver, last), move |(mut receiver, last)| async move {
+    loop {
+      match receiver.recv().await {
+        Ok(event) if event.seq > last => {
+          let seq = event.seq;
+          return Some((event, (receiver, seq)));
+        }
+        Ok(_) => continue,
+        Err(RecvError::Lagged(skipped)) => {
+          debug!("A sync client fell {skipped} events behind");
+          let seq = hub().log.lock().unwrap_or_else(|e| e.into_inner()).last();
+          let resync = Event {
+            seq,
+            change: Change::Resync
+          };
+          return Some((resync, (receiver, seq)));
+        }
+        Err(RecvError::Closed) => return None
+      }
+    }
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_other_owners_dont_lag_a_client() {
+    //~@ Owners of their own so other tests' events stay out of the way
+    let (busy, quiet) = (-46_001, -46_002);
+    let mut receiver = hub().subscribe(quiet);
+    for _ in 0..2 * BACKLOG {
+      publish(busy, Change::Favorites);
+    }
+    publish(quiet, Change::Ratings);
+
+    let event = receiver.try_recv().unwrap();
+    assert!(matches!(event.change, Change::Ratings));
+    assert!(receiver.try_recv().is_err());
+  }
+}
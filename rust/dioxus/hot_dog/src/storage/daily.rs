@@ -0,0 +1,54 @@
+use super::prelude::*;
+use crate::data::Dog;
+
+/// The dog already chosen for `date`, if any.
+pub fn get(conn: &Connection, date: &str) -> Result<Option<Dog>> {
+  let dog: Option<String> = conn
+    .query_row(
+      "SELECT dog FROM daily_dogs WHERE date = ?1",
+      [date],
+      |row| row.get(0)
+    )
+    .optional()?;
+  Ok(dog.map(|dog| serde_json::from_str(&dog)).transpose()?)
+}
+
+/// Stores `dog` for `date` unless another request got there first, returning
+/// whichever dog the day ends up with.
+pub fn insert(
+  conn: &Connection,
+  date: &str,
+  dog: &Dog,
+  created_at: i64
+) -> Result<Dog> {
+  conn.execute(
+    "INSERT OR IGNORE INTO daily_dogs (date, dog, created_at)
+     VALUES (?1, ?2, ?3)",
+    params![date, serde_json::to_string(dog)?, created_at]
+  )?;
+  get(conn, date)?.ok_or_else(|| Error::NotFound(format!("dog of {date}")))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::storage::{favorites::dog_from_url, testing};
+
+  #[test]
+  fn test_first_insert_wins() {
+    let conn = testing::connection();
+    let first = dog_from_url(
+      "https://images.dog.ceo/breeds/hound-afghan/n02088094_1003.jpg"
+    )
+    .unwrap();
+    let second = dog_from_url(
+      "https://images.dog.ceo/breeds/sheepdog-english/n02105641_1.jpg"
+    )
+    .unwrap();
+
+    assert_eq!(get(&conn, "2025-10-18").unwrap(), None);
+    assert_eq!(insert(&conn, "2025-10-18", &first, 1).unwrap(), first);
+    assert_eq!(insert(&conn, "2025-10-18", &second, 2).unwrap(), first);
+    assert_eq!(get(&conn, "2025-10-18").unwrap(), Some(first));
+  }
+}
@@ -0,0 +1,367 @@
+use super::prelude::*;
+use crate::{
+  data::{
+    Dog, Favorite, ImageMeta,
+    favorite::{MergeReport, duplicate_groups}
+  },
+  provider::{Breed, DogCeo},
+  utils::time
+};
+use rusqlite::Row;
+use std::{collections::HashMap, path::Path};
+
+const COLUMNS: &str = "id, photo_url, breed, sub_breed, display_name, \
+                       reference_url, tags, saved_at, meta";
+
+/// Owner of everything saved before there were accounts.
+pub const LEGACY_OWNER: i64 = 0;
+
+fn from_row(row: &Row) -> rusqlite::Result<Favorite> {
+  let tags: String = row.get(6)?;
+  Ok(Favorite {
+    id: row.get(0)?,
+    dog: Dog {
+      photo_url: row.get(1)?,
+      breed: row.get(2)?,
+      sub_breed: row.get(3)?,
+      display_name: row.get(4)?,
+      reference_url: row.get(5)?
+    },
+    tags: serde_json::from_str(&tags).unwrap_or_default(),
+    saved_at: row.get(7)?,
+    meta: row
+      .get::<_, Option<String>>(8)?
+      .and_then(|meta| serde_json::from_str(&meta).ok())
+  })
+}
+
+/// Saves `dog` for `owner`, returning the existing favorite if they already
+/// saved the photo.
+pub fn insert(
+  conn: &Connection,
+  owner: i64,
+  dog: &Dog,
+  saved_at: i64
+) -> Result<Favorite> {
+  conn.execute(
+    "INSERT OR IGNORE INTO favorites
+       (owner, photo_url, breed, sub_breed, display_name, reference_url,
+        saved_at)
+     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+    params![
+      owner,
+      dog.photo_url,
+      dog.breed,
+      dog.sub_breed,
+      dog.display_name,
+      dog.reference_url,
+      saved_at
+    ]
+  )?;
+  find_by_url(conn, owner, &dog.photo_url)?
+    .ok_or_else(|| Error::NotFound(dog.photo_url.clone()))
+}
+
+pub fn find_by_url(
+  conn: &Connection,
+  owner: i64,
+  photo_url: &str
+) -> Result<Option<Favorite>> {
+  Ok(
+    conn
+      .query_row(
+        &format!(
+          "SELECT {COLUMNS} FROM favorites WHERE owner = ?1 AND photo_url = ?2"
+        ),
+        params![owner, photo_url],
+        from_row
+      )
+      .optional()?
+  )
+}
+
+pub fn list(conn: &Connection, owner: i64) -> Result<Vec<Favorite>> {
+  let mut stmt = conn.prepare(&format!(
+    "SELECT {COLUMNS} FROM favorites WHERE owner = ?1
+     ORDER BY saved_at DESC, id DESC"
+  ))?;
+  let favorites = stmt
+    .query_map([owner], from_row)?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  Ok(favorites)
+}
+
+/// Image metadata of every analyzed favorite of `owner`.
+pub fn analyzed(conn: &Connection, owner: i64) -> Result<Vec<ImageMeta>> {
+  let mut stmt = conn.prepare(
+    "SELECT meta FROM favorites WHERE owner = ?1 AND meta IS NOT NULL"
+  )?;
+  let metas = stmt
+    .query_map([owner], |row| row.get::<_, String>(0))?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  Ok(
+    metas
+      .iter()
+      .filter_map(|meta| serde_json::from_str(meta).ok())
+      .collect()
+  )
+}
+
+/// Deletes the given favorites of `owner`, returning how many were removed.
+pub fn delete(conn: &Connection, owner: i64, ids: &[i64]) -> Result<usize> {
+  let mut stmt =
+    conn.prepare("DELETE FROM favorites WHERE owner = ?1 AND id = ?2")?;
+  let mut removed = 0;
+  for id in ids {
+    removed += stmt.execute(params![owner, id])?;
+  }
+  Ok(removed)
+}
+
+pub fn set_tags(
+  conn: &Connection,
+  owner: i64,
+  id: i64,
+  tags: &[String]
+) -> Result<()> {
+  let updated = conn.execute(
+    "UPDATE favorites SET tags = ?1 WHERE owner = ?2 AND id = ?3",
+    params![serde_json::to_string(tags)?, owner, id]
+  )?;
+  if updated == 0 {
+    return Err(Error::NotFound(format!("favorite {id}")));
+  }
+  Ok(())
+}
+
+pub fn set_meta(conn: &Connection, id: i64, meta: &ImageMeta) -> Result<()> {
+  conn.execute(
+    "UPDATE favorites SET meta = ?1 WHERE id = ?2",
+    params![serde_json::to_string(meta)?, id]
+  )?;
+  Ok(())
+}
+
+/// Favorites of `owner` whose photo hasn't been analyzed yet.
+pub fn missing_meta(conn: &Connection, owner: i64) -> Result<Vec<Favorite>> {
+  let mut stmt = conn.prepare(&format!(
+    "SELECT {COLUMNS} FROM favorites WHERE owner = ?1 AND meta IS NULL
+     ORDER BY id"
+  ))?;
+  let favorites = stmt
+    .query_map([owner], from_row)?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  Ok(favorites)
+}
+
+/// Favorites of `owner`, other than `id`, that look like the same picture as
+/// `meta`.
+pub fn find_duplicates(
+  conn: &Connection,
+  owner: i64,
+  id: i64,
+  meta: &ImageMeta
+) -> Result<Vec<Favorite>> {
+  Ok(
+    list(conn, owner)?
+      .into_iter()
+      .filter(|f| f.id != id)
+      .filter(|f| f.meta.as_ref().is_some_and(|m| m.is_duplicate(meta)))
+      .collect()
+  )
+}
+
+/// Collapses each group of near-duplicate favorites into its oldest entry,
+/// which inherits the tags of the others.
+pub fn merge_duplicates(conn: &Connection, owner: i64) -> Result<MergeReport> {
+  let all = list(conn, owner)?;
+  let by_id: HashMap<i64, &Favorite> = all.iter().map(|f| (f.id, f)).collect();
+  let groups = duplicate_groups(&all);
+
+  let tx = conn.unchecked_transaction()?;
+  let mut report = MergeReport::default();
+  for group in &groups {
+    let mut members: Vec<&Favorite> =
+      group.iter().map(|id| by_id[id]).collect();
+    members.sort_by_key(|f| (f.saved_at, f.id));
+    let (keeper, rest) = members.split_first().expect("groups are never empty");
+
+    let mut tags = keeper.tags.clone();
+    for tag in rest.iter().flat_map(|f| &f.tags) {
+      if !tags.contains(tag) {
+        tags.push(tag.clone());
+      }
+    }
+    set_tags(&tx, owner, keeper.id, &tags)?;
+
+    let ids: Vec<i64> = rest.iter().map(|f| f.id).collect();
+    report.removed += delete(&tx, owner, &ids)?;
+    report.groups += 1;
+  }
+  tx.commit()?;
+  Ok(report)
+}
+
+/// Resolves a dog.ceo photo URL into a [`Dog`] without any network access.
+pub fn dog_from_url(photo_url: &str) -> Result<Dog> {
+  let (main, sub) = DogCeo::extract_breed_from_url(photo_url)?;
+  let reference_url = DogCeo::build_reference_url(&main, &sub);
+  let display_name = Breed::format_name(&main, sub.as_deref());
+  Ok(Dog::new(
+    photo_url,
+    Breed::new(main, sub, reference_url, display_name)
+  ))
+}
+
+/// Imports the URL-per-line `dog.txt` that predates the database.
+///
+/// Only runs while the table is empty, so it happens once. The dogs belong to
+/// [`LEGACY_OWNER`] until the first account claims them.
+pub fn import_legacy(conn: &Connection, path: impl AsRef<Path>) -> Result<()> {
+  let path = path.as_ref();
+  let count: i64 =
+    conn.query_row("SELECT COUNT(*) FROM favorites", [], |row| row.get(0))?;
+  if count > 0 || !path.exists() {
+    return Ok(());
+  }
+
+  let saved_at = time::now();
+  for line in std::fs::read_to_string(path)?.lines().map(str::trim) {
+    if line.is_empty() {
+      continue;
+    }
+    match dog_from_url(line) {
+      Ok(dog) => {
+        insert(conn, LEGACY_OWNER, &dog, saved_at)?;
+      }
+      Err(e) => warn!("Skipping {line}: {e}")
+    }
+  }
+  let imported = list(conn, LEGACY_OWNER)?.len();
+  info!("Imported {imported} dogs from {}", path.display());
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::storage::testing;
+
+  const OWNER: i64 = 1;
+
+  const PHOTO: &str =
+    "https://images.dog.ceo/breeds/hound-afghan/n02088094_1003.jpg";
+
+  #[test]
+  fn test_insert_is_idempotent() {
+    let conn = testing::connection();
+    let dog = dog_from_url(PHOTO).unwrap();
+
+    let first = insert(&conn, OWNER, &dog, 1).unwrap();
+    let second = insert(&conn, OWNER, &dog, 2).unwrap();
+
+    assert_eq!(first, second);
+    assert_eq!(first.dog.display_name, "Afghan Hound");
+    assert_eq!(list(&conn, OWNER).unwrap().len(), 1);
+
+    //~@ Another owner gets their own copy
+    let other = insert(&conn, OWNER + 1, &dog, 3).unwrap();
+    assert_ne!(other.id, first.id);
+    assert_eq!(list(&conn, OWNER).unwrap().len(), 1);
+  }
+
+  #[test]
+  fn test_tags_meta_and_delete() {
+    let conn = testing::connection();
+    let favorite =
+      insert(&conn, OWNER, &dog_from_url(PHOTO).unwrap(), 1).unwrap();
+
+    set_tags(&conn, OWNER, favorite.id, &["fluffy".into()]).unwrap();
+    assert_eq!(list(&conn, OWNER).unwrap()[0].tags, vec!["fluffy"]);
+    assert!(set_tags(&conn, OWNER, favorite.id + 1, &[]).is_err());
+
+    let meta = ImageMeta {
+      width: 500,
+      height: 375,
+      format: "jpg".into(),
+      bytes: 1,
+      palette: vec!["#ffffff".into()],
+      ahash: 1,
+      dhash: 2
+    };
+    assert_eq!(missing_meta(&conn, OWNER).unwrap().len(), 1);
+    set_meta(&conn, favorite.id, &meta).unwrap();
+    assert_eq!(list(&conn, OWNER).unwrap()[0].meta, Some(meta.clone()));
+    assert_eq!(analyzed(&conn, OWNER).unwrap(), vec![meta]);
+    assert!(analyzed(&conn, OWNER + 1).unwrap().is_empty());
+    assert!(missing_meta(&conn, OWNER).unwrap().is_empty());
+
+    assert_eq!(
+      delete(&conn, OWNER, &[favorite.id, favorite.id + 1]).unwrap(),
+      1
+    );
+    assert!(list(&conn, OWNER).unwrap().is_empty());
+  }
+
+  #[test]
+  fn test_merge_duplicates() {
+    let conn = testing::connection();
+    let meta = |ahash: u64| ImageMeta {
+      width: 500,
+      height: 375,
+      format: "jpg".into(),
+      bytes: 1,
+      palette: Vec::new(),
+      ahash,
+      dhash: ahash
+    };
+    let save = |n: usize, saved_at: i64, tags: &[&str], hash: u64| {
+      let url = format!("https://images.dog.ceo/breeds/hound-afghan/{n}.jpg");
+      let favorite =
+        insert(&conn, OWNER, &dog_from_url(&url).unwrap(), saved_at);
+      let favorite = favorite.unwrap();
+      let tags: Vec<String> = tags.iter().map(|t| t.to_string()).collect();
+      set_tags(&conn, OWNER, favorite.id, &tags).unwrap();
+      set_meta(&conn, favorite.id, &meta(hash)).unwrap();
+      favorite.id
+    };
+    let oldest = save(1, 1, &["fluffy"], 0);
+    let newer = save(2, 2, &["sleepy", "fluffy"], 1);
+    let other = save(3, 3, &[], u64::MAX);
+    //~@ Looks like `newer` but not like the keeper, so it must survive
+    let chained = save(4, 4, &[], 0x7f);
+
+    let found = find_duplicates(&conn, OWNER, newer, &meta(1)).unwrap();
+    assert_eq!(
+      found.iter().map(|f| f.id).collect::<Vec<_>>(),
+      vec![chained, oldest]
+    );
+
+    let report = merge_duplicates(&conn, OWNER).unwrap();
+    assert_eq!(
+      report,
+      MergeReport {
+        groups: 1,
+        removed: 1
+      }
+    );
+
+    let left = list(&conn, OWNER).unwrap();
+    assert_eq!(
+      left.iter().map(|f| f.id).collect::<Vec<_>>(),
+      vec![chained, other, oldest]
+    );
+    assert_eq!(left[2].tags, vec!["fluffy", "sleepy"]);
+  }
+
+  #[test]
+  fn test_import_legacy_skips_duplicates() {
+    let conn = testing::connection();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("dog.txt");
+    std::fs::write(&path, format!("{PHOTO}\n{PHOTO}\nnot a dog\n")).unwrap();
+
+    import_legacy(&conn, &path).unwrap();
+    assert_eq!(list(&conn, LEGACY_OWNER).unwrap().len(), 1);
+  }
+}
@@ -0,0 +1,92 @@
+use super::prelude::*;
+use crate::data::history::{History, Scope};
+
+/// The most recent `capacity` photos served to `scope`.
+pub fn load(
+  conn: &Connection,
+  scope: &Scope,
+  capacity: usize
+) -> Result<History> {
+  let mut stmt = conn.prepare(
+    "SELECT url FROM seen_history WHERE scope = ?1
+     ORDER BY seen_at DESC, rowid DESC LIMIT ?2"
+  )?;
+  let mut urls = stmt
+    .query_map(params![scope.key(), capacity], |row| row.get(0))?
+    .collect::<rusqlite::Result<Vec<String>>>()?;
+  urls.reverse();
+  Ok(History::from_urls(capacity, urls))
+}
+
+/// Records `url` as served to `scope`, forgetting all but the newest
+/// `capacity` entries.
+pub fn record(
+  conn: &Connection,
+  scope: &Scope,
+  url: &str,
+  seen_at: i64,
+  capacity: usize
+) -> Result<()> {
+  let key = scope.key();
+  conn.execute(
+    "INSERT OR REPLACE INTO seen_history (scope, url, seen_at)
+     VALUES (?1, ?2, ?3)",
+    params![key, url, seen_at]
+  )?;
+  conn.execute(
+    "DELETE FROM seen_history WHERE scope = ?1 AND rowid NOT IN (
+       SELECT rowid FROM seen_history WHERE scope = ?1
+       ORDER BY seen_at DESC, rowid DESC LIMIT ?2
+     )",
+    params![key, capacity]
+  )?;
+  Ok(())
+}
+
+/// Forgets photos served to browser sessions before `seen_before`, since a
+/// session's id is never sent again once its tab is closed.
+pub fn prune_sessions(conn: &Connection, seen_before: i64) -> Result<usize> {
+  Ok(conn.execute(
+    "DELETE FROM seen_history WHERE scope LIKE 'session:%' AND seen_at < ?1",
+    [seen_before]
+  )?)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::storage::testing;
+
+  #[test]
+  fn test_record_and_load() {
+    let conn = testing::connection();
+    let user = Scope::User("me".into());
+    let session = Scope::Session("1".into());
+
+    record(&conn, &user, "a", 1, 2).unwrap();
+    record(&conn, &user, "b", 2, 2).unwrap();
+    record(&conn, &user, "c", 3, 2).unwrap();
+    record(&conn, &session, "a", 4, 2).unwrap();
+
+    let history = load(&conn, &user, 2).unwrap();
+    assert_eq!(history.len(), 2);
+    assert!(!history.contains("a"));
+    assert!(history.contains("b") && history.contains("c"));
+    assert!(load(&conn, &session, 2).unwrap().contains("a"));
+  }
+
+  #[test]
+  fn test_prune_sessions() {
+    let conn = testing::connection();
+    let user = Scope::User("me".into());
+    let session = Scope::Session("1".into());
+    record(&conn, &user, "a", 1, 10).unwrap();
+    record(&conn, &session, "a", 1, 10).unwrap();
+    record(&conn, &session, "b", 5, 10).unwrap();
+
+    assert_eq!(prune_sessions(&conn, 5).unwrap(), 1);
+    let left = load(&conn, &session, 10).unwrap();
+    assert!(!left.contains("a") && left.contains("b"));
+    assert!(load(&conn, &user, 10).unwrap().contains("a"));
+  }
+}
@@ -0,0 +1,103 @@
+//|-> Modules
+pub mod daily;
+pub mod favorites;
+pub mod history;
+pub mod quiz;
+pub mod ratings;
+mod schema;
+pub mod users;
+
+//|-> Internal Exports
+mod prelude {
+  pub use crate::prelude::*;
+  pub use rusqlite::{Connection, OptionalExtension, params};
+}
+
+//|-> External Exports
+use prelude::*;
+use std::{
+  sync::{Mutex, PoisonError},
+  time::Duration
+};
+
+pub const DATABASE: &str =
+  concat!("assets/data/", env!("CARGO_PKG_NAME"), ".db");
+
+//~@ How long a write waits for another connection's lock before failing
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+//~@ One connection for the whole process, opened and migrated once
+static DB: Mutex<Option<Connection>> = Mutex::new(None);
+
+/// Opens and migrates the database if that hasn't happened yet.
+///
+/// Called at startup so a broken database fails there; [`with_db`] opens it
+/// on first use otherwise.
+pub fn init() -> Result<()> {
+  with_db(|_| Ok(()))
+}
+
+/// Runs `f` against the shared database connection, opening it first if
+/// needed.
+///
+/// Calls are serialized, so `f` should be quick and must not call
+/// `with_db` itself.
+pub fn with_db<T>(f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+  let mut db = DB.lock().unwrap_or_else(PoisonError::into_inner);
+  let conn = match db.take() {
+    Some(conn) => conn,
+    None => open(DATABASE)?
+  };
+  f(db.insert(conn))
+}
+
+// -- Private Helper --
+
+fn open(path: &str) -> Result<Connection> {
+  //{ Open the database from the persisted file }
+  let conn = Connection::open(path)?;
+  conn.busy_timeout(BUSY_TIMEOUT)?;
+
+  //{ Bring the schema up to date }
+  schema::migrate(&conn)?;
+
+  //{ Pull in anything saved before the database existed }
+  if let Err(e) = favorites::import_legacy(&conn, DOG_TXT) {
+    warn!("Failed to import {DOG_TXT}: {e}");
+  }
+
+  Ok(conn)
+}
+
+#[cfg(test)]
+pub mod testing {
+  use super::prelude::*;
+
+  /// Opens a migrated, in-memory database for tests.
+  pub fn connection() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    super::schema::migrate(&conn).unwrap();
+    conn
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_reopening_keeps_the_schema() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("test.db");
+    let path = path.to_str().unwrap();
+    let first = open(path).unwrap();
+    let second = open(path).unwrap();
+    let version = |conn: &Connection| -> usize {
+      conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .unwrap()
+    };
+    assert_eq!(version(&first), version(&second));
+    assert!(version(&second) > 0);
+  }
+}
@@ -0,0 +1,82 @@
+use super::prelude::*;
+use crate::data::quiz::{Difficulty, HighScore};
+use rusqlite::Row;
+
+const COLUMNS: &str =
+  "name, difficulty, score, correct, best_streak, played_at";
+
+fn from_row(row: &Row) -> rusqlite::Result<HighScore> {
+  let difficulty: String = row.get(1)?;
+  Ok(HighScore {
+    name: row.get(0)?,
+    difficulty: Difficulty::parse(&difficulty).unwrap_or_default(),
+    score: row.get(2)?,
+    correct: row.get(3)?,
+    best_streak: row.get(4)?,
+    played_at: row.get(5)?
+  })
+}
+
+pub fn insert(conn: &Connection, score: &HighScore) -> Result<()> {
+  conn.execute(
+    &format!(
+      "INSERT INTO quiz_scores ({COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+    ),
+    params![
+      score.name,
+      score.difficulty.as_str(),
+      score.score,
+      score.correct,
+      score.best_streak,
+      score.played_at
+    ]
+  )?;
+  Ok(())
+}
+
+/// The best `limit` scores for one difficulty, earliest first on ties.
+pub fn top(
+  conn: &Connection,
+  difficulty: Difficulty,
+  limit: u32
+) -> Result<Vec<HighScore>> {
+  let mut stmt = conn.prepare(&format!(
+    "SELECT {COLUMNS} FROM quiz_scores WHERE difficulty = ?1
+     ORDER BY score DESC, played_at ASC LIMIT ?2"
+  ))?;
+  let scores = stmt
+    .query_map(params![difficulty.as_str(), limit], from_row)?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  Ok(scores)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::storage::testing;
+
+  fn high_score(name: &str, difficulty: Difficulty, score: u32) -> HighScore {
+    HighScore {
+      name: name.to_string(),
+      difficulty,
+      score,
+      correct: 0,
+      best_streak: 0,
+      played_at: 1
+    }
+  }
+
+  #[test]
+  fn test_top_scores_per_difficulty() {
+    let conn = testing::connection();
+    insert(&conn, &high_score("ada", Difficulty::Hard, 120)).unwrap();
+    insert(&conn, &high_score("bob", Difficulty::Hard, 300)).unwrap();
+    insert(&conn, &high_score("cy", Difficulty::Easy, 900)).unwrap();
+
+    let hard = top(&conn, Difficulty::Hard, 10).unwrap();
+    let names: Vec<_> = hard.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, ["bob", "ada"]);
+    assert_eq!(top(&conn, Difficulty::Easy, 10).unwrap().len(), 1);
+    assert_eq!(top(&conn, Difficulty::Hard, 1).unwrap().len(), 1);
+  }
+}
@@ -0,0 +1,182 @@
+use super::prelude::*;
+use crate::data::{
+  BreedScore, Dog, Vote,
+  rating::{self, INITIAL_ELO}
+};
+use rusqlite::Row;
+
+//~@ Sub-breeds are stored as '' so they can be part of the primary key
+const COLUMNS: &str =
+  "breed, NULLIF(sub_breed, ''), display_name, likes, skips, elo, matches";
+
+fn from_row(row: &Row) -> rusqlite::Result<BreedScore> {
+  Ok(BreedScore {
+    breed: row.get(0)?,
+    sub_breed: row.get(1)?,
+    display_name: row.get(2)?,
+    likes: row.get(3)?,
+    skips: row.get(4)?,
+    elo: row.get(5)?,
+    matches: row.get(6)?
+  })
+}
+
+fn sub_breed(dog: &Dog) -> &str {
+  dog.sub_breed.as_deref().unwrap_or_default()
+}
+
+/// Returns `owner`'s score for the breed of `dog`, creating it if needed.
+pub fn score(conn: &Connection, owner: i64, dog: &Dog) -> Result<BreedScore> {
+  conn.execute(
+    "INSERT OR IGNORE INTO ratings (owner, breed, sub_breed, display_name, elo)
+     VALUES (?1, ?2, ?3, ?4, ?5)",
+    params![
+      owner,
+      dog.breed,
+      sub_breed(dog),
+      dog.display_name,
+      INITIAL_ELO
+    ]
+  )?;
+  Ok(conn.query_row(
+    &format!(
+      "SELECT {COLUMNS} FROM ratings
+       WHERE owner = ?1 AND breed = ?2 AND sub_breed = ?3"
+    ),
+    params![owner, dog.breed, sub_breed(dog)],
+    from_row
+  )?)
+}
+
+/// Records `owner`'s like or skip on `dog` and returns its breed's updated
+/// score.
+///
+/// The vote and the count it bumps are written in one transaction.
+pub fn record_vote(
+  conn: &Connection,
+  owner: i64,
+  dog: &Dog,
+  vote: Vote,
+  voted_at: i64
+) -> Result<BreedScore> {
+  let tx = conn.unchecked_transaction()?;
+  score(&tx, owner, dog)?;
+  tx.execute(
+    "INSERT INTO votes (owner, photo_url, breed, sub_breed, vote, voted_at)
+     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    params![
+      owner,
+      dog.photo_url,
+      dog.breed,
+      sub_breed(dog),
+      vote.as_str(),
+      voted_at
+    ]
+  )?;
+  let column = match vote {
+    Vote::Like => "likes",
+    Vote::Skip => "skips"
+  };
+  tx.execute(
+    &format!(
+      "UPDATE ratings SET {column} = {column} + 1
+       WHERE owner = ?1 AND breed = ?2 AND sub_breed = ?3"
+    ),
+    params![owner, dog.breed, sub_breed(dog)]
+  )?;
+  let updated = score(&tx, owner, dog)?;
+  tx.commit()?;
+  Ok(updated)
+}
+
+/// Applies an Elo update for `winner` beating `loser`.
+///
+/// Returns the updated `(winner, loser)` scores; dogs of the same breed leave
+/// the scores untouched. Both ratings change in one transaction.
+pub fn record_matchup(
+  conn: &Connection,
+  owner: i64,
+  winner: &Dog,
+  loser: &Dog
+) -> Result<(BreedScore, BreedScore)> {
+  let tx = conn.unchecked_transaction()?;
+  let (before_winner, before_loser) =
+    (score(&tx, owner, winner)?, score(&tx, owner, loser)?);
+  if before_winner.breed == before_loser.breed
+    && before_winner.sub_breed == before_loser.sub_breed
+  {
+    tx.commit()?;
+    return Ok((before_winner, before_loser));
+  }
+
+  let (elo_winner, elo_loser) =
+    rating::elo_update(before_winner.elo, before_loser.elo);
+  {
+    let mut stmt = tx.prepare(
+      "UPDATE ratings SET elo = ?1, matches = matches + 1
+       WHERE owner = ?2 AND breed = ?3 AND sub_breed = ?4"
+    )?;
+    stmt.execute(params![
+      elo_winner,
+      owner,
+      winner.breed,
+      sub_breed(winner)
+    ])?;
+    stmt.execute(params![elo_loser, owner, loser.breed, sub_breed(loser)])?;
+  }
+
+  let updated = (score(&tx, owner, winner)?, score(&tx, owner, loser)?);
+  tx.commit()?;
+  Ok(updated)
+}
+
+/// Every breed `owner` has rated, best first.
+pub fn leaderboard(conn: &Connection, owner: i64) -> Result<Vec<BreedScore>> {
+  let mut stmt =
+    conn.prepare(&format!("SELECT {COLUMNS} FROM ratings WHERE owner = ?1"))?;
+  let mut scores = stmt
+    .query_map([owner], from_row)?
+    .collect::<rusqlite::Result<Vec<_>>>()?;
+  rating::rank(&mut scores);
+  Ok(scores)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::storage::{favorites::dog_from_url, testing};
+
+  fn dog(path: &str) -> Dog {
+    dog_from_url(&format!("https://images.dog.ceo/breeds/{path}.jpg")).unwrap()
+  }
+
+  #[test]
+  fn test_votes_and_matchups() {
+    let conn = testing::connection();
+    let (afghan, pug) = (dog("hound-afghan/1"), dog("pug/2"));
+
+    record_vote(&conn, 1, &afghan, Vote::Like, 1).unwrap();
+    record_vote(&conn, 1, &dog("hound-afghan/3"), Vote::Like, 2).unwrap();
+    let pug_score = record_vote(&conn, 1, &pug, Vote::Skip, 3).unwrap();
+    assert_eq!((pug_score.likes, pug_score.skips), (0, 1));
+
+    let (winner, loser) = record_matchup(&conn, 1, &afghan, &pug).unwrap();
+    assert!(winner.elo > INITIAL_ELO && loser.elo < INITIAL_ELO);
+    assert_eq!((winner.matches, loser.matches), (1, 1));
+
+    assert!(leaderboard(&conn, 2).unwrap().is_empty());
+    let board = leaderboard(&conn, 1).unwrap();
+    assert_eq!(board[0].display_name, "Afghan Hound");
+    assert_eq!(board[0].sub_breed.as_deref(), Some("afghan"));
+    assert_eq!(board[0].likes, 2);
+    assert_eq!(board[1].sub_breed, None);
+  }
+
+  #[test]
+  fn test_failed_votes_roll_back() {
+    let conn = testing::connection();
+    conn.execute("DROP TABLE votes", []).unwrap();
+    assert!(record_vote(&conn, 1, &dog("pug/1"), Vote::Like, 1).is_err());
+    assert!(leaderboard(&conn, 1).unwrap().is_empty());
+  }
+}
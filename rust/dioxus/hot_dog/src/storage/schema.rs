@@ -0,0 +1,142 @@
+use super::prelude::*;
+
+/// Schema changes, applied in order and tracked through `user_version`.
+///
+/// Never edit a released entry; append a new one instead.
+const MIGRATIONS: &[&str] = &[
+  "
+  CREATE TABLE favorites (
+    id INTEGER PRIMARY KEY,
+    photo_url TEXT NOT NULL UNIQUE,
+    breed TEXT NOT NULL,
+    sub_breed TEXT,
+    display_name TEXT NOT NULL,
+    reference_url TEXT NOT NULL,
+    tags TEXT NOT NULL DEFAULT '[]',
+    saved_at INTEGER NOT NULL
+  );
+  CREATE INDEX favorites_breed ON favorites (breed, sub_breed);
+",
+  "
+  CREATE TABLE votes (
+    id INTEGER PRIMARY KEY,
+    photo_url TEXT NOT NULL,
+    breed TEXT NOT NULL,
+    sub_breed TEXT NOT NULL DEFAULT '',
+    vote TEXT NOT NULL,
+    voted_at INTEGER NOT NULL
+  );
+  CREATE TABLE ratings (
+    breed TEXT NOT NULL,
+    sub_breed TEXT NOT NULL DEFAULT '',
+    display_name TEXT NOT NULL,
+    likes INTEGER NOT NULL DEFAULT 0,
+    skips INTEGER NOT NULL DEFAULT 0,
+    elo REAL NOT NULL,
+    matches INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (breed, sub_breed)
+  );
+",
+  "
+  CREATE TABLE quiz_scores (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    difficulty TEXT NOT NULL,
+    score INTEGER NOT NULL,
+    correct INTEGER NOT NULL,
+    best_streak INTEGER NOT NULL,
+    played_at INTEGER NOT NULL
+  );
+  CREATE INDEX quiz_scores_rank ON quiz_scores (difficulty, score DESC);
+",
+  "
+  ALTER TABLE favorites ADD COLUMN meta TEXT;
+",
+  "
+  CREATE TABLE seen_history (
+    scope TEXT NOT NULL,
+    url TEXT NOT NULL,
+    seen_at INTEGER NOT NULL,
+    PRIMARY KEY (scope, url)
+  );
+",
+  "
+  CREATE TABLE daily_dogs (
+    date TEXT PRIMARY KEY,
+    dog TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+  );
+",
+  "
+  CREATE TABLE users (
+    id INTEGER PRIMARY KEY,
+    username TEXT UNIQUE COLLATE NOCASE,
+    password_hash TEXT,
+    created_at INTEGER NOT NULL
+  );
+  CREATE TABLE sessions (
+    token TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL
+  );
+  CREATE INDEX sessions_user ON sessions (user_id);
+
+  CREATE TABLE owned_favorites (
+    id INTEGER PRIMARY KEY,
+    owner INTEGER NOT NULL DEFAULT 0,
+    photo_url TEXT NOT NULL,
+    breed TEXT NOT NULL,
+    sub_breed TEXT,
+    display_name TEXT NOT NULL,
+    reference_url TEXT NOT NULL,
+    tags TEXT NOT NULL DEFAULT '[]',
+    saved_at INTEGER NOT NULL,
+    meta TEXT,
+    UNIQUE (owner, photo_url)
+  );
+  INSERT INTO owned_favorites
+    (id, photo_url, breed, sub_breed, display_name, reference_url, tags,
+     saved_at, meta)
+  SELECT id, photo_url, breed, sub_breed, display_name, reference_url, tags,
+         saved_at, meta
+  FROM favorites;
+  DROP TABLE favorites;
+  ALTER TABLE owned_favorites RENAME TO favorites;
+  CREATE INDEX favorites_breed ON favorites (owner, breed, sub_breed);
+
+  CREATE TABLE owned_ratings (
+    owner INTEGER NOT NULL DEFAULT 0,
+    breed TEXT NOT NULL,
+    sub_breed TEXT NOT NULL DEFAULT '',
+    display_name TEXT NOT NULL,
+    likes INTEGER NOT NULL DEFAULT 0,
+    skips INTEGER NOT NULL DEFAULT 0,
+    elo REAL NOT NULL,
+    matches INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (owner, breed, sub_breed)
+  );
+  INSERT INTO owned_ratings
+    (breed, sub_breed, display_name, likes, skips, elo, matches)
+  SELECT breed, sub_breed, display_name, likes, skips, elo, matches
+  FROM ratings;
+  DROP TABLE ratings;
+  ALTER TABLE owned_ratings RENAME TO ratings;
+
+  ALTER TABLE votes ADD COLUMN owner INTEGER NOT NULL DEFAULT 0;
+"
+];
+
+/// Applies every migration newer than the database's `user_version`.
+pub fn migrate(conn: &Connection) -> Result<()> {
+  let version: usize =
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+  for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+    debug!("Applying database migration {}", index + 1);
+    conn.execute_batch(&format!(
+      "BEGIN; {migration} PRAGMA user_version = {}; COMMIT;",
+      index + 1
+    ))?;
+  }
+  Ok(())
+}
@@ -0,0 +1,310 @@
+use super::{favorites::LEGACY_OWNER, prelude::*};
+use crate::data::{history::Scope, user::User};
+use rusqlite::Row;
+
+fn from_row(row: &Row) -> rusqlite::Result<User> {
+  Ok(User {
+    id: row.get(0)?,
+    username: row.get(1)?
+  })
+}
+
+/// Creates a user without credentials for a new visitor.
+pub fn create_anonymous(conn: &Connection, created_at: i64) -> Result<User> {
+  conn.execute("INSERT INTO users (created_at) VALUES (?1)", [created_at])?;
+  Ok(User {
+    id: conn.last_insert_rowid(),
+    username: None
+  })
+}
+
+pub fn find(conn: &Connection, id: i64) -> Result<Option<User>> {
+  Ok(
+    conn
+      .query_row(
+        "SELECT id, username FROM users WHERE id = ?1",
+        [id],
+        from_row
+      )
+      .optional()?
+  )
+}
+
+/// The user registered as `username`.
+pub fn find_by_name(conn: &Connection, username: &str) -> Result<Option<User>> {
+  Ok(
+    conn
+      .query_row(
+        "SELECT id, username FROM users WHERE username = ?1",
+        [username],
+        from_row
+      )
+      .optional()?
+  )
+}
+
+/// The user registered as `username` along with their password hash.
+pub fn credentials(
+  conn: &Connection,
+  username: &str
+) -> Result<Option<(User, String)>> {
+  Ok(
+    conn
+      .query_row(
+        "SELECT id, username, password_hash FROM users
+         WHERE username = ?1 AND password_hash IS NOT NULL",
+        [username],
+        |row| Ok((from_row(row)?, row.get(2)?))
+      )
+      .optional()?
+  )
+}
+
+/// Gives the anonymous user `id` a username and password, keeping everything
+/// they saved so far.
+///
+/// The first account to register also takes over whatever was saved before
+/// accounts existed.
+pub fn register(
+  conn: &Connection,
+  id: i64,
+  username: &str,
+  password_hash: &str
+) -> Result<User> {
+  if credentials(conn, username)?.is_some() {
+    return Err(Error::Auth(format!("{username} is already taken")));
+  }
+  let first: bool = conn.query_row(
+    "SELECT NOT EXISTS (SELECT 1 FROM users WHERE username IS NOT NULL)",
+    [],
+    |row| row.get(0)
+  )?;
+
+  let tx = conn.unchecked_transaction()?;
+  let updated = tx.execute(
+    "UPDATE users SET username = ?1, password_hash = ?2
+     WHERE id = ?3 AND username IS NULL",
+    params![username, password_hash, id]
+  )?;
+  if updated == 0 {
+    return Err(Error::Auth("only guests can sign up".into()));
+  }
+  if first {
+    merge(&tx, LEGACY_OWNER, id)?;
+  }
+  tx.commit()?;
+
+  find(conn, id)?.ok_or_else(|| Error::NotFound(format!("user {id}")))
+}
+
+/// Moves everything owned by `from` over to `into`, e.g. when a guest signs
+/// in to an existing account.
+///
+/// Photos both of them saved keep `into`'s copy, and breed scores are added
+/// together. An anonymous `from` is deleted afterwards.
+pub fn merge(conn: &Connection, from: i64, into: i64) -> Result<()> {
+  if from == into {
+    return Ok(());
+  }
+  let (from_scope, into_scope) = (
+    Scope::User(from.to_string()).key(),
+    Scope::User(into.to_string()).key()
+  );
+  conn.execute_batch(&format!(
+    "UPDATE OR IGNORE favorites SET owner = {into} WHERE owner = {from};
+     DELETE FROM favorites WHERE owner = {from};
+
+     UPDATE votes SET owner = {into} WHERE owner = {from};
+
+     UPDATE ratings AS r
+     SET likes = r.likes + f.likes,
+         skips = r.skips + f.skips,
+         matches = r.matches + f.matches
+     FROM (SELECT * FROM ratings WHERE owner = {from}) AS f
+     WHERE r.owner = {into}
+       AND r.breed = f.breed AND r.sub_breed = f.sub_breed;
+     UPDATE OR IGNORE ratings SET owner = {into} WHERE owner = {from};
+     DELETE FROM ratings WHERE owner = {from};"
+  ))?;
+  conn.execute(
+    "UPDATE OR IGNORE seen_history SET scope = ?2 WHERE scope = ?1",
+    [&from_scope, &into_scope]
+  )?;
+  conn.execute("DELETE FROM seen_history WHERE scope = ?1", [&from_scope])?;
+
+  conn.execute("DELETE FROM sessions WHERE user_id = ?1", [from])?;
+  conn.execute(
+    "DELETE FROM users WHERE id = ?1 AND username IS NULL",
+    [from]
+  )?;
+  Ok(())
+}
+
+pub fn create_session(
+  conn: &Connection,
+  token: &str,
+  user_id: i64,
+  expires_at: i64
+) -> Result<()> {
+  conn.execute(
+    "INSERT INTO sessions (token, user_id, expires_at) VALUES (?1, ?2, ?3)",
+    params![token, user_id, expires_at]
+  )?;
+  Ok(())
+}
+
+/// The user behind an unexpired session `token`.
+pub fn session_user(
+  conn: &Connection,
+  token: &str,
+  now: i64
+) -> Result<Option<User>> {
+  Ok(
+    conn
+      .query_row(
+        "SELECT users.id, users.username FROM sessions
+         JOIN users ON users.id = sessions.user_id
+         WHERE sessions.token = ?1 AND sessions.expires_at > ?2",
+        params![token, now],
+        from_row
+      )
+      .optional()?
+  )
+}
+
+pub fn delete_session(conn: &Connection, token: &str) -> Result<()> {
+  conn.execute("DELETE FROM sessions WHERE token = ?1", [token])?;
+  Ok(())
+}
+
+/// Deletes sessions expired by `now`, then guests created before
+/// `created_before` that no session leads to any more, along with
+/// everything they owned. Returns how many guests went.
+///
+/// Nobody can sign back in as a guest, so their data is unreachable.
+pub fn prune(
+  conn: &Connection,
+  now: i64,
+  created_before: i64
+) -> Result<usize> {
+  let tx = conn.unchecked_transaction()?;
+  tx.execute("DELETE FROM sessions WHERE expires_at <= ?1", [now])?;
+
+  let abandoned = "SELECT id FROM users
+    WHERE username IS NULL AND created_at < ?1
+      AND id NOT IN (SELECT user_id FROM sessions)";
+  for owned in [
+    "DELETE FROM favorites WHERE owner IN",
+    "DELETE FROM votes WHERE owner IN",
+    "DELETE FROM ratings WHERE owner IN"
+  ] {
+    tx.execute(&format!("{owned} ({abandoned})"), [created_before])?;
+  }
+  tx.execute(
+    &format!(
+      "DELETE FROM seen_history WHERE scope IN (
+         SELECT 'user:' || id FROM ({abandoned})
+       )"
+    ),
+    [created_before]
+  )?;
+  let removed = tx.execute(
+    &format!("DELETE FROM users WHERE id IN ({abandoned})"),
+    [created_before]
+  )?;
+  tx.commit()?;
+  Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    data::Vote,
+    storage::{favorites, favorites::dog_from_url, ratings, testing}
+  };
+
+  fn dog(path: &str) -> crate::data::Dog {
+    dog_from_url(&format!("https://images.dog.ceo/breeds/{path}.jpg")).unwrap()
+  }
+
+  #[test]
+  fn test_register_and_sessions() {
+    let conn = testing::connection();
+    favorites::insert(&conn, LEGACY_OWNER, &dog("pug/1"), 1).unwrap();
+
+    let guest = create_anonymous(&conn, 1).unwrap();
+    assert!(guest.is_anonymous());
+    create_session(&conn, "token", guest.id, 100).unwrap();
+    assert_eq!(
+      session_user(&conn, "token", 50).unwrap(),
+      Some(guest.clone())
+    );
+    assert_eq!(session_user(&conn, "token", 100).unwrap(), None);
+
+    let user = register(&conn, guest.id, "rex", "hash").unwrap();
+    assert_eq!(user.username.as_deref(), Some("rex"));
+    assert_eq!(credentials(&conn, "REX").unwrap().unwrap().1, "hash");
+    assert_eq!(find_by_name(&conn, "rex").unwrap(), Some(user.clone()));
+    assert!(register(&conn, guest.id, "rex", "hash").is_err());
+
+    //~@ The first account claims what was saved before accounts
+    assert_eq!(favorites::list(&conn, user.id).unwrap().len(), 1);
+    assert!(favorites::list(&conn, LEGACY_OWNER).unwrap().is_empty());
+
+    delete_session(&conn, "token").unwrap();
+    assert_eq!(session_user(&conn, "token", 50).unwrap(), None);
+  }
+
+  #[test]
+  fn test_merge_guest_into_account() {
+    let conn = testing::connection();
+    let account = create_anonymous(&conn, 1).unwrap();
+    let account = register(&conn, account.id, "rex", "hash").unwrap();
+    let guest = create_anonymous(&conn, 2).unwrap();
+
+    favorites::insert(&conn, account.id, &dog("pug/1"), 1).unwrap();
+    favorites::insert(&conn, guest.id, &dog("pug/1"), 2).unwrap();
+    favorites::insert(&conn, guest.id, &dog("hound-afghan/2"), 3).unwrap();
+    ratings::record_vote(&conn, account.id, &dog("pug/1"), Vote::Like, 1)
+      .unwrap();
+    ratings::record_vote(&conn, guest.id, &dog("pug/3"), Vote::Like, 2)
+      .unwrap();
+    create_session(&conn, "guest", guest.id, 100).unwrap();
+
+    merge(&conn, guest.id, account.id).unwrap();
+
+    assert_eq!(favorites::list(&conn, account.id).unwrap().len(), 2);
+    assert!(favorites::list(&conn, guest.id).unwrap().is_empty());
+    let board = ratings::leaderboard(&conn, account.id).unwrap();
+    assert_eq!((board.len(), board[0].likes), (1, 2));
+    assert_eq!(find(&conn, guest.id).unwrap(), None);
+    assert_eq!(session_user(&conn, "guest", 50).unwrap(), None);
+  }
+
+  #[test]
+  fn test_prune_abandoned_guests() {
+    let conn = testing::connection();
+    let (kept, expired, fresh) = (
+      create_anonymous(&conn, 1).unwrap(),
+      create_anonymous(&conn, 1).unwrap(),
+      create_anonymous(&conn, 90).unwrap()
+    );
+    let account = create_anonymous(&conn, 1).unwrap();
+    let account = register(&conn, account.id, "rex", "hash").unwrap();
+    create_session(&conn, "kept", kept.id, 200).unwrap();
+    create_session(&conn, "expired", expired.id, 50).unwrap();
+    favorites::insert(&conn, expired.id, &dog("pug/1"), 1).unwrap();
+    ratings::record_vote(&conn, expired.id, &dog("pug/1"), Vote::Like, 1)
+      .unwrap();
+
+    assert_eq!(prune(&conn, 100, 10).unwrap(), 1);
+    assert_eq!(find(&conn, expired.id).unwrap(), None);
+    assert!(favorites::list(&conn, expired.id).unwrap().is_empty());
+    assert!(ratings::leaderboard(&conn, expired.id).unwrap().is_empty());
+    assert_eq!(session_user(&conn, "kept", 100).unwrap(), Some(kept));
+    //~@ Too new to have a session yet, or signed up
+    assert!(find(&conn, fresh.id).unwrap().is_some());
+    assert!(find(&conn, account.id).unwrap().is_some());
+  }
+}
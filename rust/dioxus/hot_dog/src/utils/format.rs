@@ -9,3 +9,117 @@ pub fn capitalize_first_letter(s: &str) -> String {
     Some(f) => f.to_uppercase().collect::<String>() + c.as_str()
   }
 }
+
+/// Percent-encodes everything but unreserved URL characters, e.g. for
+/// building `data:` URLs.
+pub fn percent_encode(s: &str) -> String {
+  let mut encoded = String::with_capacity(s.len());
+  for byte in s.bytes() {
+    match byte {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' =>
+        encoded.push(byte as char),
+      _ => encoded.push_str(&format!("%{byte:02X}"))
+    }
+  }
+  encoded
+}
+
+/// Quotes a CSV field when it contains a separator, quote or newline.
+pub fn csv_field(value: &str) -> String {
+  if value.contains([',', '"', '\n']) {
+    format!("\"{}\"", value.replace('"', "\"\""))
+  } else {
+    value.to_string()
+  }
+}
+
+/// Splits one CSV line into fields, undoing [`csv_field`] quoting.
+///
+/// Returns `None` when a quoted field is never closed.
+pub fn csv_record(line: &str) -> Option<Vec<String>> {
+  let mut fields = Vec::new();
+  let mut field = String::new();
+  let mut chars = line.chars().peekable();
+  let mut quoted = false;
+  while let Some(c) = chars.next() {
+    match (c, quoted) {
+      ('"', true) if chars.peek() == Some(&'"') => {
+        field.push('"');
+        chars.next();
+      }
+      ('"', true) => quoted = false,
+      ('"', false) if field.is_empty() => quoted = true,
+      (',', false) => fields.push(std::mem::take(&mut field)),
+      _ => field.push(c)
+    }
+  }
+  if quoted {
+    return None;
+  }
+  fields.push(field);
+  Some(fields)
+}
+
+/// Escapes text for use in HTML content and quoted attributes.
+pub fn html_escape(s: &str) -> String {
+  let mut escaped = String::with_capacity(s.len());
+  for c in s.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&#39;"),
+      _ => escaped.push(c)
+    }
+  }
+  escaped
+}
+
+/// Alt text for a photo of `name`, e.g. "Photo of an English Sheepdog".
+///
+/// Picks "a" or "an" from the first letter, which is right for breed names
+/// but not for every English word.
+pub fn photo_alt(name: &str) -> String {
+  let name = name.trim();
+  if name.is_empty() {
+    return "Photo of a dog".to_string();
+  }
+  let vowel = name
+    .chars()
+    .next()
+    .is_some_and(|c| "aeiouAEIOU".contains(c));
+  format!("Photo of {} {name}", if vowel { "an" } else { "a" })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_csv_round_trip() {
+    let fields = ["plain", "with, comma", "say \"woof\"", ""];
+    let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+    assert_eq!(csv_record(&line.join(",")).unwrap(), fields);
+    assert_eq!(csv_record("\"open"), None);
+  }
+
+  #[test]
+  fn test_html_escape() {
+    assert_eq!(
+      html_escape("<a href=\"x\">Tom & Jerry's</a>"),
+      "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+    );
+  }
+
+  #[test]
+  fn test_photo_alt() {
+    assert_eq!(
+      photo_alt("English Sheepdog"),
+      "Photo of an English Sheepdog"
+    );
+    assert_eq!(photo_alt("Afghan Hound"), "Photo of an Afghan Hound");
+    assert_eq!(photo_alt("Pug"), "Photo of a Pug");
+    assert_eq!(photo_alt(" "), "Photo of a dog");
+  }
+}
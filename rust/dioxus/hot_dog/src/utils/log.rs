@@ -1,7 +1,36 @@
-//TODO: Replace with logline
-use std::str::FromStr;
-use tracing::debug;
-use tracing_subscriber::{fmt, EnvFilter};
+use crate::error::{Error, Result};
+use std::{
+  path::PathBuf,
+  str::FromStr,
+  sync::{
+    OnceLock,
+    atomic::{AtomicU64, Ordering}
+  }
+};
+use tracing::{Span, debug, info_span};
+use tracing_appender::rolling::{
+  RollingFileAppender, Rotation as AppenderRotation
+};
+use tracing_subscriber::{
+  EnvFilter, Layer,
+  fmt::{self, MakeWriter},
+  layer::{Layered, SubscriberExt},
+  registry::Registry,
+  reload,
+  util::SubscriberInitExt
+};
+
+/// The subscriber stack that output layers are attached to.
+type Base = Layered<reload::Layer<EnvFilter, Registry>, Registry>;
+
+/// A boxed output layer, so stdout and file sinks can differ in format.
+type Output = Box<dyn Layer<Base> + Send + Sync + 'static>;
+
+//~@ The reload handle is only ever set once, by a successful `init`
+static HANDLE: OnceLock<Handle> = OnceLock::new();
+
+//~@ Monotonic counter used to tag provider requests
+static REQUEST_ID: AtomicU64 = AtomicU64::new(1);
 
 /// Represents a logging level.
 #[derive(Debug, Clone)]
@@ -10,6 +39,54 @@ pub enum Level {
   String(String)
 }
 
+/// How log lines are rendered.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+  /// Multi-line, human friendly output.
+  #[default]
+  Pretty,
+  /// Single-line, human friendly output.
+  Compact,
+  /// One JSON object per line, including the active span list.
+  Json
+}
+
+/// How often the log file rolls over.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+  Minutely,
+  Hourly,
+  #[default]
+  Daily,
+  Never
+}
+
+/// A rolling file sink, written alongside stdout.
+#[derive(Debug, Clone)]
+pub struct FileOutput {
+  pub directory: PathBuf,
+  pub prefix: String,
+  pub rotation: Rotation,
+  pub format: Format
+}
+
+/// Logger configuration, built with the `with_*` methods.
+#[derive(Debug, Clone)]
+pub struct Config {
+  pub level: Level,
+  pub directives: Option<String>,
+  pub format: Format,
+  pub timestamps: bool,
+  pub ansi: bool,
+  pub file: Option<FileOutput>
+}
+
+/// Changes the active filter of an initialized logger at runtime.
+#[derive(Clone)]
+pub struct Handle {
+  inner: reload::Handle<EnvFilter, Registry>
+}
+
 // -- Trait Implementations --
 impl From<tracing::Level> for Level {
   fn from(level: tracing::Level) -> Self {
@@ -32,6 +109,51 @@ impl Default for Level {
   }
 }
 
+impl FromStr for Format {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    match s.to_lowercase().as_str() {
+      "pretty" => Ok(Self::Pretty),
+      "compact" => Ok(Self::Compact),
+      "json" => Ok(Self::Json),
+      other => Err(Error::Logging(format!("Unknown log format: {other}")))
+    }
+  }
+}
+
+impl From<Rotation> for AppenderRotation {
+  fn from(rotation: Rotation) -> Self {
+    match rotation {
+      Rotation::Minutely => AppenderRotation::MINUTELY,
+      Rotation::Hourly => AppenderRotation::HOURLY,
+      Rotation::Daily => AppenderRotation::DAILY,
+      Rotation::Never => AppenderRotation::NEVER
+    }
+  }
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Self {
+      level: Level::default(),
+      directives: None,
+      format: Format::default(),
+      timestamps: true,
+      ansi: true,
+      file: None
+    }
+  }
+}
+
+impl std::fmt::Debug for Handle {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("Handle")
+      .field("filter", &self.current().ok())
+      .finish()
+  }
+}
+
 impl Level {
   fn to_tracing_level(&self) -> tracing::Level {
     match self {
@@ -42,41 +164,250 @@ impl Level {
   }
 }
 
+impl FileOutput {
+  /// A daily rolling JSON-lines file in `directory`, named after `prefix`.
+  pub fn new(directory: impl Into<PathBuf>, prefix: impl Into<String>) -> Self {
+    Self {
+      directory: directory.into(),
+      prefix: prefix.into(),
+      rotation: Rotation::default(),
+      format: Format::Json
+    }
+  }
+
+  pub fn with_rotation(mut self, rotation: Rotation) -> Self {
+    self.rotation = rotation;
+    self
+  }
+
+  pub fn with_format(mut self, format: Format) -> Self {
+    self.format = format;
+    self
+  }
+}
+
+impl Config {
+  /// Builds a configuration from the environment.
+  ///
+  /// - `RUST_LOG` sets the filter directives.
+  /// - `LOG_FORMAT` selects `pretty`, `compact` or `json`.
+  /// - `LOG_DIR` enables a daily rolling JSON file in that directory.
+  pub fn from_env() -> Self {
+    let mut config = Self::default();
+
+    if let Ok(directives) = std::env::var("RUST_LOG") {
+      if !directives.is_empty() {
+        config.directives = Some(directives);
+      }
+    }
+
+    if let Some(format) = std::env::var("LOG_FORMAT")
+      .ok()
+      .and_then(|s| Format::from_str(&s).ok())
+    {
+      config.format = format;
+    }
+
+    if let Ok(dir) = std::env::var("LOG_DIR") {
+      if !dir.is_empty() {
+        config.file = Some(FileOutput::new(dir, env!("CARGO_PKG_NAME")));
+      }
+    }
+
+    config
+  }
+
+  pub fn with_level<L: Into<Level>>(mut self, level: L) -> Self {
+    self.level = level.into();
+    self
+  }
+
+  pub fn with_directives(mut self, directives: impl Into<String>) -> Self {
+    self.directives = Some(directives.into());
+    self
+  }
+
+  pub fn with_format(mut self, format: Format) -> Self {
+    self.format = format;
+    self
+  }
+
+  pub fn with_timestamps(mut self, timestamps: bool) -> Self {
+    self.timestamps = timestamps;
+    self
+  }
+
+  pub fn with_ansi(mut self, ansi: bool) -> Self {
+    self.ansi = ansi;
+    self
+  }
+
+  pub fn with_file(mut self, file: FileOutput) -> Self {
+    self.file = Some(file);
+    self
+  }
+
+  /// Builds the filter, preferring explicit directives over the level.
+  fn filter(&self) -> Result<EnvFilter> {
+    match &self.directives {
+      Some(directives) => parse_filter(directives),
+      None => Ok(
+        EnvFilter::default()
+          .add_directive(self.level.to_tracing_level().into())
+      )
+    }
+  }
+}
+
+impl Handle {
+  /// Replaces the active filter with a single global level.
+  pub fn set_level<L: Into<Level>>(&self, level: L) -> Result<()> {
+    let level = level.into().to_tracing_level();
+    self.reload(EnvFilter::default().add_directive(level.into()))?;
+    debug!("Log level changed to {level}");
+    Ok(())
+  }
+
+  /// Replaces the active filter with `RUST_LOG`-style directives,
+  /// e.g. `info,hot_dog::provider=trace`.
+  pub fn set_directives(&self, directives: &str) -> Result<()> {
+    self.reload(parse_filter(directives)?)?;
+    debug!("Log filter changed to {directives}");
+    Ok(())
+  }
+
+  /// Returns the active filter rendered as directives.
+  pub fn current(&self) -> Result<String> {
+    self
+      .inner
+      .with_current(|filter| filter.to_string())
+      .map_err(|e| Error::Logging(e.to_string()))
+  }
+
+  fn reload(&self, filter: EnvFilter) -> Result<()> {
+    self
+      .inner
+      .reload(filter)
+      .map_err(|e| Error::Logging(e.to_string()))
+  }
+}
+
 // -- Private Helper --
 
-/// Builds and initializes the subscriber with a given filter.
-fn setup_subscriber(filter: EnvFilter) {
-  fmt().without_time().pretty().with_env_filter(filter).init();
+fn parse_filter(directives: &str) -> Result<EnvFilter> {
+  EnvFilter::try_new(directives).map_err(|e| {
+    Error::Logging(format!("Invalid filter directives '{directives}': {e}"))
+  })
+}
+
+/// Builds a boxed fmt layer for the given format and writer.
+fn output<W>(format: Format, timestamps: bool, ansi: bool, writer: W) -> Output
+where
+  W: for<'w> MakeWriter<'w> + Send + Sync + 'static
+{
+  let layer = fmt::layer().with_ansi(ansi).with_writer(writer);
+  match (format, timestamps) {
+    (Format::Pretty, true) => layer.pretty().boxed(),
+    (Format::Pretty, false) => layer.pretty().without_time().boxed(),
+    (Format::Compact, true) => layer.compact().boxed(),
+    (Format::Compact, false) => layer.compact().without_time().boxed(),
+    (Format::Json, true) => layer
+      .json()
+      .with_current_span(true)
+      .with_span_list(true)
+      .boxed(),
+    (Format::Json, false) => layer
+      .json()
+      .with_current_span(true)
+      .with_span_list(true)
+      .without_time()
+      .boxed()
+  }
+}
+
+/// Opens `file`'s rolling appender, surfacing an unwritable log file as an
+/// error instead of the panic `RollingFileAppender::new` gives.
+fn appender(file: &FileOutput) -> Result<RollingFileAppender> {
+  std::fs::create_dir_all(&file.directory)?;
+  RollingFileAppender::builder()
+    .rotation(file.rotation.into())
+    .filename_prefix(&file.prefix)
+    .build(&file.directory)
+    .map_err(|e| Error::Logging(format!("Cannot open log file: {e}")))
+}
+
+/// Builds and installs the subscriber described by `config`.
+fn setup_subscriber(config: Config) -> Result<&'static Handle> {
+  if HANDLE.get().is_some() {
+    return Err(Error::Logging("Logger is already initialized".into()));
+  }
+
+  let (filter, inner) = reload::Layer::new(config.filter()?);
+  let mut outputs: Vec<Output> = vec![output(
+    config.format,
+    config.timestamps,
+    config.ansi,
+    std::io::stdout
+  )];
+
+  if let Some(file) = &config.file {
+    outputs.push(output(file.format, true, false, appender(file)?));
+  }
+
+  tracing_subscriber::registry()
+    .with(filter)
+    .with(outputs)
+    .try_init()
+    .map_err(|e| Error::Logging(e.to_string()))?;
+
+  Ok(HANDLE.get_or_init(|| Handle { inner }))
 }
 
 // -- Public API --
 
-/// Initializes the logger, prioritizing the `RUST_LOG` environment variable.
+/// Initializes the logger from the environment (see [`Config::from_env`]).
 ///
-/// If `RUST_LOG` is not set, it falls back to the `INFO` level.
-pub fn init() {
-  let filter = match std::env::var("RUST_LOG") {
-    Ok(env_var) if !env_var.is_empty() => EnvFilter::new(env_var),
-    _ =>
-      EnvFilter::from_default_env().add_directive(tracing::Level::INFO.into()),
-  };
-  setup_subscriber(filter);
+/// If `RUST_LOG` is not set, it falls back to the `INFO` level. Returns an
+/// error if a logger is already installed.
+pub fn init() -> Result<()> {
+  setup_subscriber(Config::from_env())?;
   debug!("Logging initialized, respecting RUST_LOG.");
+  Ok(())
 }
 
 /// Initializes the logger with a specific level, **ignoring `RUST_LOG`**.
 ///
 /// This provides a hard override, which is useful for forcing a specific log
 /// level in tests or applications regardless of the environment.
-pub fn init_with_level<L>(level: L)
+pub fn init_with_level<L>(level: L) -> Result<()>
 where
   L: Into<Level>
 {
-  let tracing_level = level.into().to_tracing_level();
-  let filter =
-    EnvFilter::from_default_env().add_directive(tracing_level.into());
-  setup_subscriber(filter);
+  let level = level.into();
+  let tracing_level = level.to_tracing_level();
+  let mut config = Config::from_env().with_level(level);
+  config.directives = None;
+  setup_subscriber(config)?;
   debug!("Logging initialized with forced level: {:?}", tracing_level);
+  Ok(())
+}
+
+/// Initializes the logger with an explicit configuration.
+pub fn init_with(config: Config) -> Result<()> {
+  setup_subscriber(config)?;
+  debug!("Logging initialized with custom configuration.");
+  Ok(())
+}
+
+/// Returns the reload handle, if the logger has been initialized.
+pub fn handle() -> Option<&'static Handle> {
+  HANDLE.get()
+}
+
+/// Opens a span for one provider request, tagged with a unique request ID.
+pub fn request_span(provider: &str, operation: &str) -> Span {
+  let request_id = REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+  info_span!("request", request_id, provider, operation)
 }
 
 // -- Test Utilities --
@@ -93,7 +424,48 @@ pub mod testing {
   /// Call this function at the beginning of every test.
   pub fn init() {
     INIT.call_once(|| {
-      super::init();
+      let _ = super::init();
     });
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_init_twice_errors() {
+    testing::init();
+    assert!(init().is_err());
+    assert!(handle().is_some());
+  }
+
+  #[test]
+  fn test_reload_directives() {
+    //~@ A subscriber of its own, so the global filter other tests log
+    //~@ through is left alone
+    let (filter, inner) = reload::Layer::new(EnvFilter::new("info"));
+    let _subscriber = Registry::default().with(filter);
+    let handle = Handle { inner };
+
+    handle.set_directives("warn,hot_dog=trace").unwrap();
+    assert!(handle.current().unwrap().contains("hot_dog=trace"));
+    assert!(handle.set_directives("not a [valid filter").is_err());
+    assert!(handle.current().unwrap().contains("hot_dog=trace"));
+
+    handle.set_level(tracing::Level::DEBUG).unwrap();
+    assert_eq!(handle.current().unwrap(), "debug");
+  }
+
+  #[test]
+  fn test_unwritable_log_file_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(appender(&FileOutput::new(dir.path(), "app")).is_ok());
+
+    //~@ A directory where the log file should be can't be opened for writing
+    std::fs::create_dir(dir.path().join("taken")).unwrap();
+    let file =
+      FileOutput::new(dir.path(), "taken").with_rotation(Rotation::Never);
+    assert!(matches!(appender(&file), Err(Error::Logging(_))));
+  }
+}
@@ -0,0 +1,276 @@
+use serde::Serialize;
+use std::{
+  collections::BTreeMap,
+  fmt::Write,
+  sync::{Mutex, OnceLock}
+};
+use web_time::Duration;
+
+/// Upper bounds (in seconds) of the latency histogram buckets.
+pub const LATENCY_BUCKETS: [f64; 11] = [
+  0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0
+];
+
+//~@ Process-wide registry, created on first use
+static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+
+type Key = (&'static str, &'static str);
+
+#[derive(Debug, Default)]
+struct Registry {
+  requests: BTreeMap<Key, u64>,
+  failures: BTreeMap<(&'static str, &'static str, &'static str), u64>,
+  cache: BTreeMap<Key, u64>,
+  latency: BTreeMap<Key, Histogram>
+}
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+  buckets: [u64; LATENCY_BUCKETS.len()],
+  count: u64,
+  sum: f64
+}
+
+/// A request counter for one provider operation.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RequestSample {
+  pub provider: String,
+  pub operation: String,
+  pub count: u64
+}
+
+/// A failure counter, keyed by the [`crate::error::Error`] variant.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FailureSample {
+  pub provider: String,
+  pub operation: String,
+  pub error: String,
+  pub count: u64
+}
+
+/// A cache lookup counter, e.g. `breeds`/`memory`.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CacheSample {
+  pub cache: String,
+  pub outcome: String,
+  pub count: u64
+}
+
+/// A latency histogram with cumulative bucket counts.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct LatencySample {
+  pub provider: String,
+  pub operation: String,
+  pub buckets: Vec<(f64, u64)>,
+  pub count: u64,
+  pub sum: f64
+}
+
+/// A point-in-time copy of every metric.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct Snapshot {
+  pub requests: Vec<RequestSample>,
+  pub failures: Vec<FailureSample>,
+  pub cache: Vec<CacheSample>,
+  pub latency: Vec<LatencySample>
+}
+
+impl Histogram {
+  fn observe(&mut self, seconds: f64) {
+    for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+      if seconds <= bound {
+        *bucket += 1;
+      }
+    }
+    self.count += 1;
+    self.sum += seconds;
+  }
+}
+
+impl Snapshot {
+  /// Renders the snapshot in the Prometheus text exposition format.
+  pub fn to_prometheus(&self) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# TYPE hot_dog_requests_total counter");
+    for s in &self.requests {
+      let _ = writeln!(
+        out,
+        "hot_dog_requests_total{{provider=\"{}\",operation=\"{}\"}} {}",
+        s.provider, s.operation, s.count
+      );
+    }
+
+    let _ = writeln!(out, "# TYPE hot_dog_failures_total counter");
+    for s in &self.failures {
+      let _ = writeln!(
+        out,
+        "hot_dog_failures_total{{provider=\"{}\",operation=\"{}\",error=\"{}\"}} {}",
+        s.provider, s.operation, s.error, s.count
+      );
+    }
+
+    let _ = writeln!(out, "# TYPE hot_dog_cache_total counter");
+    for s in &self.cache {
+      let _ = writeln!(
+        out,
+        "hot_dog_cache_total{{cache=\"{}\",outcome=\"{}\"}} {}",
+        s.cache, s.outcome, s.count
+      );
+    }
+
+    let _ = writeln!(out, "# TYPE hot_dog_request_duration_seconds histogram");
+    for s in &self.latency {
+      let labels =
+        format!("provider=\"{}\",operation=\"{}\"", s.provider, s.operation);
+      for (bound, count) in &s.buckets {
+        let _ = writeln!(
+          out,
+          "hot_dog_request_duration_seconds_bucket{{{labels},le=\"{bound}\"}} {count}"
+        );
+      }
+      let _ = writeln!(
+        out,
+        "hot_dog_request_duration_seconds_bucket{{{labels},le=\"+Inf\"}} {}",
+        s.count
+      );
+      let _ = writeln!(
+        out,
+        "hot_dog_request_duration_seconds_sum{{{labels}}} {}",
+        s.sum
+      );
+      let _ = writeln!(
+        out,
+        "hot_dog_request_duration_seconds_count{{{labels}}} {}",
+        s.count
+      );
+    }
+
+    out
+  }
+}
+
+// -- Private Helper --
+
+fn with_registry<T>(f: impl FnOnce(&mut Registry) -> T) -> T {
+  let registry = REGISTRY.get_or_init(Default::default);
+  let mut guard = registry.lock().unwrap_or_else(|e| e.into_inner());
+  f(&mut guard)
+}
+
+// -- Public API --
+
+/// Counts one call to `operation` on `provider`.
+pub fn record_request(provider: &'static str, operation: &'static str) {
+  with_registry(|r| *r.requests.entry((provider, operation)).or_default() += 1);
+}
+
+/// Counts one failed call, labelled with the error variant.
+pub fn record_failure(
+  provider: &'static str,
+  operation: &'static str,
+  error: &'static str
+) {
+  with_registry(|r| {
+    *r.failures.entry((provider, operation, error)).or_default() += 1
+  });
+}
+
+/// Counts one cache lookup, e.g. `("breeds", "memory")` for a hit on
+/// `BREEDS_CACHE`, `("breeds", "disk")` for the JSON file or
+/// `("breeds", "miss")` when the API had to be called.
+pub fn record_cache(cache: &'static str, outcome: &'static str) {
+  with_registry(|r| *r.cache.entry((cache, outcome)).or_default() += 1);
+}
+
+/// Records how long one call to `operation` on `provider` took.
+pub fn record_latency(
+  provider: &'static str,
+  operation: &'static str,
+  elapsed: Duration
+) {
+  with_registry(|r| {
+    r.latency
+      .entry((provider, operation))
+      .or_default()
+      .observe(elapsed.as_secs_f64())
+  });
+}
+
+/// Copies the current value of every metric.
+pub fn snapshot() -> Snapshot {
+  with_registry(|r| Snapshot {
+    requests: r
+      .requests
+      .iter()
+      .map(|(&(provider, operation), &count)| RequestSample {
+        provider: provider.into(),
+        operation: operation.into(),
+        count
+      })
+      .collect(),
+    failures: r
+      .failures
+      .iter()
+      .map(|(&(provider, operation, error), &count)| FailureSample {
+        provider: provider.into(),
+        operation: operation.into(),
+        error: error.into(),
+        count
+      })
+      .collect(),
+    cache: r
+      .cache
+      .iter()
+      .map(|(&(cache, outcome), &count)| CacheSample {
+        cache: cache.into(),
+        outcome: outcome.into(),
+        count
+      })
+      .collect(),
+    latency: r
+      .latency
+      .iter()
+      .map(|(&(provider, operation), h)| LatencySample {
+        provider: provider.into(),
+        operation: operation.into(),
+        buckets: LATENCY_BUCKETS.iter().copied().zip(h.buckets).collect(),
+        count: h.count,
+        sum: h.sum
+      })
+      .collect()
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_histogram_buckets_are_cumulative() {
+    let mut histogram = Histogram::default();
+    histogram.observe(0.003);
+    histogram.observe(0.2);
+    histogram.observe(30.0);
+
+    assert_eq!(histogram.count, 3);
+    assert_eq!(histogram.buckets[0], 1);
+    assert_eq!(histogram.buckets[5], 2);
+    assert_eq!(histogram.buckets[LATENCY_BUCKETS.len() - 1], 2);
+  }
+
+  #[test]
+  fn test_prometheus_output() {
+    record_request("test", "photo");
+    record_failure("test", "photo", "network");
+    record_latency("test", "photo", Duration::from_millis(20));
+
+    let text = snapshot().to_prometheus();
+    assert!(text.contains(
+      r#"hot_dog_requests_total{provider="test",operation="photo"}"#
+    ));
+    assert!(text.contains(r#"error="network""#));
+    assert!(text.contains(r#"le="0.025"} 1"#));
+    assert!(text.contains("hot_dog_request_duration_seconds_count"));
+  }
+}
@@ -1,2 +1,7 @@
 pub mod format;
 pub mod log;
+pub mod metrics;
+pub mod prefetch;
+pub mod proxy;
+pub mod rng;
+pub mod time;
@@ -0,0 +1,126 @@
+use std::collections::VecDeque;
+
+/// Items fetched ahead of time, kept topped up to a fixed depth.
+///
+/// Every fill is tagged with the generation it was started in; a
+/// [`Queue::reset`] starts a new one, so fills still in flight from before
+/// are dropped when they land.
+#[derive(Debug, Clone)]
+pub struct Queue<T> {
+  depth: usize,
+  generation: u64,
+  ready: VecDeque<T>,
+  in_flight: usize
+}
+
+impl<T> Queue<T> {
+  pub fn new(depth: usize) -> Self {
+    Self {
+      depth,
+      generation: 0,
+      ready: VecDeque::with_capacity(depth),
+      in_flight: 0
+    }
+  }
+
+  pub fn generation(&self) -> u64 {
+    self.generation
+  }
+
+  /// How many fills to start to get back to full depth.
+  pub fn wanted(&self) -> usize {
+    self.depth.saturating_sub(self.ready.len() + self.in_flight)
+  }
+
+  /// Marks a fill as started, returning the generation to finish it with.
+  pub fn start(&mut self) -> u64 {
+    self.in_flight += 1;
+    self.generation
+  }
+
+  /// Lands a fill started in `generation`, keeping `item` unless the queue
+  /// was reset since. A failed fill passes `None`.
+  ///
+  /// Returns whether the fill was still current.
+  pub fn finish(&mut self, generation: u64, item: Option<T>) -> bool {
+    if generation != self.generation {
+      return false;
+    }
+    self.in_flight = self.in_flight.saturating_sub(1);
+    if let Some(item) = item {
+      self.ready.push_back(item);
+    }
+    true
+  }
+
+  /// Takes the oldest ready item.
+  pub fn pop(&mut self) -> Option<T> {
+    self.ready.pop_front()
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &T> {
+    self.ready.iter()
+  }
+
+  pub fn len(&self) -> usize {
+    self.ready.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.ready.is_empty()
+  }
+
+  /// Whether a fill is on its way.
+  pub fn is_filling(&self) -> bool {
+    self.in_flight > 0
+  }
+
+  /// Whether nothing is ready and nothing is on its way.
+  pub fn is_idle(&self) -> bool {
+    self.ready.is_empty() && self.in_flight == 0
+  }
+
+  /// Drops everything and orphans the fills in flight.
+  pub fn reset(&mut self) {
+    self.generation += 1;
+    self.ready.clear();
+    self.in_flight = 0;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_fills_up_to_depth() {
+    let mut queue = Queue::new(3);
+    assert_eq!(queue.wanted(), 3);
+    let tickets: Vec<u64> =
+      (0..queue.wanted()).map(|_| queue.start()).collect();
+    assert_eq!(queue.wanted(), 0);
+    assert!(queue.is_filling());
+
+    assert!(queue.finish(tickets[0], Some("a")));
+    assert!(queue.finish(tickets[1], None));
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue.wanted(), 1);
+
+    assert_eq!(queue.pop(), Some("a"));
+    assert_eq!(queue.wanted(), 2);
+  }
+
+  #[test]
+  fn test_resets_drop_stale_fills() {
+    let mut queue = Queue::new(2);
+    let done = queue.start();
+    let stale = queue.start();
+    queue.finish(done, Some(1));
+    queue.reset();
+    assert!(queue.is_idle());
+
+    assert!(!queue.finish(stale, Some(2)));
+    assert!(queue.is_empty());
+    assert_eq!(queue.wanted(), 2);
+  }
+}
@@ -0,0 +1,141 @@
+use crate::prelude::server_fn;
+
+/// Route the image proxy is mounted on, see `server::images`.
+pub const PROXY_PATH: &str = "/images";
+
+/// Widths the proxy will render, smallest first.
+pub const WIDTHS: [u32; 4] = [320, 640, 960, 1280];
+
+/// Hosts the proxy is allowed to fetch from.
+pub const ALLOWED_HOSTS: [&str; 1] = ["images.dog.ceo"];
+
+/// Output encodings the proxy can produce.
+///
+/// Photos offer AVIF to browsers that decode it, with JPEG as the fallback.
+/// WebP is only encoded losslessly, which makes a photo larger than its
+/// source, so it suits graphics alone.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+  Webp,
+  Avif,
+  #[default]
+  Jpeg
+}
+
+impl Format {
+  pub const ALL: [Format; 3] = [Format::Webp, Format::Avif, Format::Jpeg];
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Format::Webp => "webp",
+      Format::Avif => "avif",
+      Format::Jpeg => "jpeg"
+    }
+  }
+
+  pub fn parse(value: &str) -> Option<Self> {
+    let value = value.to_ascii_lowercase();
+    match value.as_str() {
+      "jpg" => Some(Format::Jpeg),
+      _ => Self::ALL.into_iter().find(|f| f.as_str() == value)
+    }
+  }
+
+  pub fn mime(&self) -> &'static str {
+    match self {
+      Format::Webp => "image/webp",
+      Format::Avif => "image/avif",
+      Format::Jpeg => "image/jpeg"
+    }
+  }
+}
+
+/// Returns the host of an `http(s)` URL.
+pub fn host(url: &str) -> Option<&str> {
+  let rest = url
+    .strip_prefix("https://")
+    .or_else(|| url.strip_prefix("http://"))?;
+  let authority = rest.split(['/', '?', '#']).next()?;
+  //~@ Drop credentials and port so neither can smuggle in another host
+  let host = authority.rsplit('@').next()?.split(':').next()?;
+  (!host.is_empty()).then_some(host)
+}
+
+/// Whether the proxy may fetch `url`.
+pub fn is_allowed(url: &str) -> bool {
+  host(url).is_some_and(|host| {
+    ALLOWED_HOSTS
+      .iter()
+      .any(|allowed| host.eq_ignore_ascii_case(allowed))
+  })
+}
+
+/// Rounds `width` up to the nearest rendered width, capped at the largest.
+pub fn clamp_width(width: u32) -> u32 {
+  WIDTHS
+    .into_iter()
+    .find(|&w| w >= width)
+    .unwrap_or(WIDTHS[WIDTHS.len() - 1])
+}
+
+/// The proxied URL of `url` at `width` pixels in `format`.
+///
+/// Prefixed with the server's origin where the app isn't served by it, as
+/// in the desktop and mobile webviews.
+pub fn proxy_url(url: &str, width: u32, format: Format) -> String {
+  format!(
+    "{}{PROXY_PATH}?url={}&w={}&format={}",
+    server_fn::client::get_server_url(),
+    super::format::percent_encode(url),
+    clamp_width(width),
+    format.as_str()
+  )
+}
+
+/// A `srcset` covering every rendered width, or an empty string when the
+/// proxy won't serve `url`.
+pub fn srcset(url: &str, format: Format) -> String {
+  if !is_allowed(url) {
+    return String::new();
+  }
+  WIDTHS
+    .iter()
+    .map(|&w| format!("{} {w}w", proxy_url(url, w, format)))
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PHOTO: &str =
+    "https://images.dog.ceo/breeds/hound-afghan/n02088094_1003.jpg";
+
+  #[test]
+  fn test_allowed_hosts() {
+    assert!(is_allowed(PHOTO));
+    assert!(is_allowed("http://IMAGES.dog.ceo:443/x.jpg"));
+    assert!(!is_allowed("https://images.dog.ceo.evil.com/x.jpg"));
+    assert!(!is_allowed("https://images.dog.ceo@evil.com/x.jpg"));
+    assert!(!is_allowed("file:///etc/passwd"));
+  }
+
+  #[test]
+  fn test_srcset() {
+    assert_eq!(clamp_width(1), 320);
+    assert_eq!(clamp_width(641), 960);
+    assert_eq!(clamp_width(5000), 1280);
+
+    let srcset = srcset(PHOTO, Format::Webp);
+    assert_eq!(srcset.matches("w, ").count(), WIDTHS.len() - 1);
+    assert!(srcset.starts_with(
+      "/images?url=https%3A%2F%2Fimages.dog.ceo%2Fbreeds%2Fhound-afghan%2F"
+    ));
+    assert!(srcset.ends_with("&w=1280&format=webp 1280w"));
+    assert!(
+      super::srcset("https://example.com/x.jpg", Format::Avif).is_empty()
+    );
+    assert_eq!(Format::parse("JPG"), Some(Format::Jpeg));
+  }
+}
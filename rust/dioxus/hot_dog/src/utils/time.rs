@@ -0,0 +1,94 @@
+use std::time::Duration;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Waits for `duration` on any platform.
+///
+/// Tokio timers are unavailable in the browser, so web builds fall back to
+/// `setTimeout` through `gloo-timers`.
+pub async fn sleep(duration: Duration) {
+  #[cfg(target_arch = "wasm32")]
+  gloo_timers::future::sleep(duration).await;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  tokio::time::sleep(duration).await;
+}
+
+/// Returns the current Unix time in seconds.
+pub fn now() -> i64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map_or(0, |d| d.as_secs() as i64)
+}
+
+/// Formats a Unix timestamp as a `YYYY-MM-DD` UTC date.
+pub fn format_date(timestamp: i64) -> String {
+  //~@ Civil-from-days, see http://howardhinnant.github.io/date_algorithms.html
+  let days = timestamp.div_euclid(86_400);
+  let z = days + 719_468;
+  let era = z.div_euclid(146_097);
+  let doe = z - era * 146_097;
+  let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = doy - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = yoe + era * 400 + i64::from(month <= 2);
+  format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Parses a `YYYY-MM-DD` date into the Unix timestamp of its UTC midnight.
+///
+/// Returns `None` for malformed input and for dates that don't exist, such
+/// as `2025-02-30`.
+pub fn parse_date(date: &str) -> Option<i64> {
+  let mut parts = date.splitn(3, '-');
+  let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+  if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+    return None;
+  }
+  let (year, month, day): (i64, i64, i64) =
+    (year.parse().ok()?, month.parse().ok()?, day.parse().ok()?);
+  if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+    return None;
+  }
+
+  //~@ Days-from-civil, the inverse of `format_date`
+  let year = if month <= 2 { year - 1 } else { year };
+  let era = year.div_euclid(400);
+  let yoe = year - era * 400;
+  let mp = (month + 9) % 12;
+  let doy = (153 * mp + 2) / 5 + day - 1;
+  let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+  let timestamp = (era * 146_097 + doe - 719_468) * 86_400;
+
+  //~@ Out-of-range days roll into the next month, so round-trip to catch them
+  (format_date(timestamp) == date).then_some(timestamp)
+}
+
+/// Today's `YYYY-MM-DD` date for a timezone `offset_minutes` east of UTC.
+pub fn today(offset_minutes: i64) -> String {
+  format_date(now() + offset_minutes * 60)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_format_date() {
+    assert_eq!(format_date(0), "1970-01-01");
+    assert_eq!(format_date(951_782_400), "2000-02-29");
+    assert_eq!(format_date(1_760_745_600), "2025-10-18");
+    assert_eq!(format_date(-86_400), "1969-12-31");
+  }
+
+  #[test]
+  fn test_parse_date() {
+    assert_eq!(parse_date("1970-01-01"), Some(0));
+    assert_eq!(parse_date("2000-02-29"), Some(951_782_400));
+    assert_eq!(parse_date("2025-10-18"), Some(1_760_745_600));
+    assert_eq!(parse_date("2025-02-29"), None);
+    assert_eq!(parse_date("2025-1-01"), None);
+    assert_eq!(parse_date("today"), None);
+  }
+}
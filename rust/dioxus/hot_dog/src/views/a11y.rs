@@ -0,0 +1,77 @@
+//~@ Accessibility checks for component tests, run on Dioxus SSR output
+use super::prelude::*;
+
+/// Renders `app` to HTML, as the server would on first load.
+pub fn render(app: fn() -> Element) -> String {
+  let mut dom = VirtualDom::new(app);
+  dom.rebuild_in_place();
+  dioxus_ssr::render(&dom)
+}
+
+/// Every `<name ...>` start tag in `html`, up to its closing `>`.
+pub fn tags<'a>(html: &'a str, name: &str) -> Vec<&'a str> {
+  let open = format!("<{name}");
+  html
+    .match_indices(&open)
+    .filter_map(|(start, _)| {
+      let rest = &html[start..];
+      //~@ Skip longer names sharing the prefix, e.g. `<imgs`
+      let after = rest[open.len()..].chars().next()?;
+      if !(after.is_whitespace() || after == '>' || after == '/') {
+        return None;
+      }
+      Some(&rest[..=rest.find('>')?])
+    })
+    .collect()
+}
+
+/// The value of attribute `name` in a start tag.
+pub fn attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+  let key = format!(" {name}=\"");
+  let start = tag.find(&key)? + key.len();
+  Some(&tag[start..start + tag[start..].find('"')?])
+}
+
+/// Text between a start tag and the next closing tag of `name`, without
+/// nested markup.
+fn text_after(html: &str, tag: &str, name: &str) -> String {
+  let start = html.find(tag).map_or(0, |i| i + tag.len());
+  let end = html[start..]
+    .find(&format!("</{name}>"))
+    .map_or(html.len(), |i| start + i);
+  let mut text = String::new();
+  let mut in_tag = false;
+  for c in html[start..end].chars() {
+    match c {
+      '<' => in_tag = true,
+      '>' => in_tag = false,
+      _ if !in_tag => text.push(c),
+      _ => {}
+    }
+  }
+  text.trim().to_string()
+}
+
+/// Panics unless every image has alt text, or is hidden and marked as
+/// decorative with an empty one.
+pub fn assert_images_described(html: &str) {
+  for img in tags(html, "img") {
+    match attr(img, "alt") {
+      Some(alt) if !alt.trim().is_empty() => {}
+      Some(_) if img.contains(" hidden") => {}
+      _ => panic!("image without alt text: {img}")
+    }
+  }
+}
+
+/// Panics unless every button has an accessible name, is a plain
+/// `type="button"` and can keep keyboard focus.
+pub fn assert_buttons_operable(html: &str) {
+  for button in tags(html, "button") {
+    let named = attr(button, "aria-label").is_some_and(|l| !l.is_empty())
+      || !text_after(html, button, "button").is_empty();
+    assert!(named, "button without a name: {button}");
+    assert_eq!(attr(button, "type"), Some("button"), "in {button}");
+    assert!(!button.contains(" disabled"), "focus is lost on {button}");
+  }
+}
@@ -0,0 +1,15 @@
+use super::prelude::*;
+
+#[component]
+pub fn About() -> Element {
+  rsx! {
+    section {
+      h2 { "About {TITLE}" }
+      p {
+        "A small Dioxus playground that serves random dogs from "
+        a { href: "https://dog.ceo/dog-api/", "the Dog CEO API" }
+        ". Skip the ones you don't fancy and save the ones you do."
+      }
+    }
+  }
+}
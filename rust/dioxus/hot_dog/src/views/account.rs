@@ -0,0 +1,89 @@
+use super::prelude::*;
+use crate::{
+  api::{login, logout, register, whoami},
+  data::User
+};
+
+#[component]
+pub fn Account() -> Element {
+  let mut user = use_resource(whoami);
+  let mut username = use_signal(String::new);
+  let mut password = use_signal(String::new);
+  let mut message = use_signal(|| None::<String>);
+
+  //{ Run one sign-in style call and report how it went }
+  let mut finish = move |result: std::result::Result<User, ServerFnError>| {
+    match result {
+      Ok(signed_in) => {
+        message.set(Some(format!("Signed in as {}", signed_in.display_name())));
+        password.set(String::new());
+      }
+      Err(e) => message.set(Some(e.to_string()))
+    }
+    user.restart();
+  };
+
+  let sign_up = move |_| async move {
+    finish(register(username(), password()).await);
+  };
+  let sign_in = move |_| async move {
+    finish(login(username(), password()).await);
+  };
+  let sign_out = move |_| async move {
+    match logout().await {
+      Ok(()) => message.set(Some("Signed out".into())),
+      Err(e) => message.set(Some(e.to_string()))
+    }
+    user.restart();
+  };
+
+  rsx! {
+    section { id: "account",
+      h2 { "Account" }
+      match &*user.read() {
+          Some(Err(e)) => rsx! {
+            p { class: "error", "Error: {e}" }
+          },
+          None => rsx! {
+            p { "Loading..." }
+          },
+          Some(Ok(current)) if !current.is_anonymous() => rsx! {
+            p { "Signed in as {current.display_name()}." }
+            button { onclick: sign_out, "Sign out" }
+          },
+          Some(Ok(_)) => rsx! {
+            p {
+              "You're browsing as a guest. Sign up to keep your favorites and ratings, "
+              "or sign in to bring them into an existing account."
+            }
+            form { onsubmit: move |evt| evt.prevent_default(),
+              label {
+                "Username "
+                input {
+                  value: "{username}",
+                  autocomplete: "username",
+                  oninput: move |evt| username.set(evt.value())
+                }
+              }
+              label {
+                "Password "
+                input {
+                  r#type: "password",
+                  value: "{password}",
+                  autocomplete: "current-password",
+                  oninput: move |evt| password.set(evt.value())
+                }
+              }
+              div {
+                button { r#type: "button", onclick: sign_in, "Sign in" }
+                button { r#type: "button", onclick: sign_up, "Sign up" }
+              }
+            }
+          },
+      }
+      if let Some(message) = message() {
+        p { class: "notice", "{message}" }
+      }
+    }
+  }
+}
@@ -0,0 +1,180 @@
+use super::{offline, prelude::*};
+use crate::provider::Breed;
+use std::collections::HashSet;
+
+//~@ Number of photos shown per gallery page
+const PAGE_SIZE: usize = 12;
+
+#[component]
+pub fn Breeds() -> Element {
+  let catalog = use_resource(|| async { offline::breeds().await });
+  let mut query = use_signal(String::new);
+  let mut expanded = use_signal(HashSet::<String>::new);
+  let mut selected = use_signal(|| None::<(String, Option<String>)>);
+
+  let results = use_memo(move || match &*catalog.read() {
+    Some(Ok(breeds)) => breeds.search(&query()),
+    _ => Vec::new()
+  });
+
+  let mut toggle = move |main: String| {
+    let mut expanded = expanded.write();
+    if !expanded.remove(&main) {
+      expanded.insert(main);
+    }
+  };
+
+  rsx! {
+    document::Stylesheet { href: BREEDS_CSS }
+    section { id: "breeds",
+      h2 { "Breeds" }
+      input {
+        r#type: "search",
+        placeholder: "Search breeds...",
+        value: "{query}",
+        oninput: move |evt| query.set(evt.value())
+      }
+
+      if let Some((main, sub)) = selected() {
+        BreedGallery {
+          key: "{main}-{sub:?}",
+          main,
+          sub,
+          onclose: move |_| selected.set(None)
+        }
+      }
+
+      match &*catalog.read() {
+          Some(Err(e)) => rsx! {
+            p { class: "error", "Error: {e}" }
+          },
+          None => rsx! {
+            p { "Loading..." }
+          },
+          Some(Ok(_)) => rsx! {
+            p { "{results.read().len()} breeds" }
+            ul { class: "breed-list",
+              for (main, subs) in results() {
+                li { key: "{main}",
+                  div { class: "breed-row",
+                    if !subs.is_empty() {
+                      button {
+                        onclick: {
+                            let main = main.clone();
+                            move |_| toggle(main.clone())
+                        },
+                        if expanded.read().contains(&main) || !query.read().is_empty() { "▾" } else { "▸" }
+                      }
+                    }
+                    button {
+                      onclick: {
+                          let main = main.clone();
+                          move |_| selected.set(Some((main.clone(), None)))
+                      },
+                      {Breed::format_name(&main, None)}
+                    }
+                    span { class: "count", {sub_breed_count(subs.len())} }
+                  }
+                  if !subs.is_empty() && (expanded.read().contains(&main) || !query.read().is_empty()) {
+                    ul { class: "sub-breeds",
+                      for sub in subs {
+                        li { key: "{main}-{sub}",
+                          div { class: "breed-row",
+                            button {
+                              onclick: {
+                                  let main = main.clone();
+                                  let sub = sub.clone();
+                                  move |_| selected.set(Some((main.clone(), Some(sub.clone()))))
+                              },
+                              {Breed::format_name(&main, Some(&sub))}
+                            }
+                          }
+                        }
+                      }
+                    }
+                  }
+                }
+              }
+            }
+          }
+      }
+    }
+  }
+}
+
+fn sub_breed_count(count: usize) -> String {
+  match count {
+    0 => "no sub-breeds".to_string(),
+    1 => "1 sub-breed".to_string(),
+    n => format!("{n} sub-breeds")
+  }
+}
+
+#[component]
+fn BreedGallery(
+  main: String,
+  sub: Option<String>,
+  onclose: EventHandler<MouseEvent>
+) -> Element {
+  let title = Breed::format_name(&main, sub.as_deref());
+  let mut page = use_signal(|| 0usize);
+  let photos = use_resource(move || {
+    let (main, sub) = (main.clone(), sub.clone());
+    async move { offline::breed_photos(&main, sub.as_deref()).await }
+  });
+
+  rsx! {
+    div { class: "breed-gallery",
+      div { class: "breed-row",
+        h3 { "{title}" }
+        button { class: "count", onclick: move |evt| onclose.call(evt), "close" }
+      }
+      match &*photos.read() {
+          Some(Err(e)) => rsx! {
+            p { class: "error", "Error: {e}" }
+          },
+          None => rsx! {
+            p { "Loading..." }
+          },
+          Some(Ok(photos)) => {
+              let pages = photos.len().div_ceil(PAGE_SIZE).max(1);
+              let current = page().min(pages - 1);
+              rsx! {
+                div { class: "breed-grid",
+                  for url in photos.iter().skip(current * PAGE_SIZE).take(PAGE_SIZE) {
+                    picture {
+                      key: "{url}",
+                      source {
+                        r#type: proxy::Format::Avif.mime(),
+                        "srcset": proxy::srcset(url, proxy::Format::Avif),
+                        "sizes": "160px"
+                      }
+                      img {
+                        src: photo_src(url),
+                        srcset: proxy::srcset(url, proxy::Format::Jpeg),
+                        "sizes": "160px",
+                        alt: photo_alt(&title),
+                        loading: "lazy"
+                      }
+                    }
+                  }
+                }
+                div { class: "pager",
+                  button {
+                    disabled: current == 0,
+                    onclick: move |_| page.set(current.saturating_sub(1)),
+                    "previous"
+                  }
+                  span { "Page {current + 1} of {pages}" }
+                  button {
+                    disabled: current + 1 >= pages,
+                    onclick: move |_| page.set(current + 1),
+                    "next"
+                  }
+                }
+              }
+          }
+      }
+    }
+  }
+}
@@ -1,234 +1,306 @@
-use super::prelude::*;
-
-#[derive(Clone, PartialEq)]
-pub struct CarouselItem {
-  pub id: usize,
-  pub title: String,
-  pub image_url: String,
-  pub description: String
-}
+use super::{offline, prelude::*};
+use crate::{api::save_dog, data::Dog, provider::Provider, utils::time::sleep};
+use std::time::Duration;
+
+//~@ Horizontal distance (px) a touch must travel to count as a swipe
+const SWIPE_THRESHOLD: f64 = 50.0;
 
 #[component]
-pub fn ImageCarousel() -> Element {
+pub fn ImageCarousel(
+  #[props(default = 5)] count: usize,
+  #[props(default = 5000)] interval_ms: u64
+) -> Element {
   let mut current_index = use_signal(|| 0usize);
+  let mut hovered = use_signal(|| false);
+  let mut focused = use_signal(|| false);
+  let mut stopped = use_signal(|| false);
+  let mut touch_start = use_signal(|| None::<f64>);
+  let mut saved = use_signal(Vec::<String>::new);
 
-  // Dummy data for the carousel
-  let carousel_items = use_memo(|| {
-    vec![
-      CarouselItem {
-        id: 1,
-        title: "Mountain Landscape".to_string(),
-        image_url: "https://picsum.photos/800/400?random=1".to_string(),
-        description: "Beautiful mountain scenery with snow-capped peaks"
-          .to_string()
-      },
-      CarouselItem {
-        id: 2,
-        title: "Ocean Sunset".to_string(),
-        image_url: "https://picsum.photos/800/400?random=2".to_string(),
-        description: "Stunning ocean sunset with vibrant colors".to_string()
-      },
-      CarouselItem {
-        id: 3,
-        title: "Forest Path".to_string(),
-        image_url: "https://picsum.photos/800/400?random=3".to_string(),
-        description: "Peaceful forest path surrounded by tall trees"
-          .to_string()
-      },
-      CarouselItem {
-        id: 4,
-        title: "City Skyline".to_string(),
-        image_url: "https://picsum.photos/800/400?random=4".to_string(),
-        description: "Modern city skyline during golden hour".to_string()
-      },
-      CarouselItem {
-        id: 5,
-        title: "Desert Dunes".to_string(),
-        image_url: "https://picsum.photos/800/400?random=5".to_string(),
-        description: "Rolling sand dunes in the desert".to_string()
-      },
-    ]
+  //{ Fetch a batch of dogs through the preferred provider }
+  let provider =
+    use_memo(move || Provider::from_name(&PREFERENCES.read().provider));
+  let dogs = use_resource(move || async move {
+    let provider = provider();
+    offline::dogs(&provider, count).await
   });
 
-  let items = carousel_items();
-  let total_items = items.len();
+  let total_items = move || {
+    dogs
+      .read()
+      .as_ref()
+      .and_then(|result| result.as_ref().ok())
+      .map_or(0, Vec::len)
+  };
 
-  let next_slide = move |_: Event<MouseData>| {
-    current_index.set((current_index() + 1) % total_items);
+  let mut next_slide = move || {
+    let total = total_items();
+    if total > 0 {
+      current_index.set((current_index() + 1) % total);
+    }
   };
 
-  let prev_slide = move |_: Event<MouseData>| {
-    current_index.set(if current_index() == 0 {
-      total_items - 1
-    } else {
-      current_index() - 1
-    });
+  let mut prev_slide = move || {
+    let total = total_items();
+    if total > 0 {
+      current_index.set(if current_index() == 0 {
+        total - 1
+      } else {
+        current_index() - 1
+      });
+    }
+  };
+
+  let mut go_to_slide = move |index: usize| {
+    current_index.set(index);
   };
 
-  let go_to_slide = move |index: usize| {
-    move |_: Event<MouseData>| {
-      current_index.set(index);
+  //{ Rotation the user hasn't turned off; hover and focus only hold it }
+  let rotating = move || PREFERENCES.read().autoplay && !stopped();
+
+  //{ Advance on a timer, if enabled, unless the pointer or focus is on it }
+  use_future(move || async move {
+    loop {
+      sleep(Duration::from_millis(interval_ms)).await;
+      if rotating() && !hovered() && !focused() {
+        next_slide();
+      }
     }
+  });
+
+  let items = match &*dogs.read() {
+    Some(Ok(dogs)) if !dogs.is_empty() => dogs.clone(),
+    Some(Ok(_)) =>
+      return rsx! {
+        p { class: "error", "No dogs to show." }
+      },
+    Some(Err(e)) =>
+      return rsx! {
+        p { class: "error", "Error: {e}" }
+      },
+    None =>
+      return rsx! {
+        p { "Loading..." }
+      },
   };
 
-  let current_item = &items[current_index()];
+  let index = current_index().min(items.len() - 1);
+  let current_item = items[index].clone();
+  let next_item = &items[(index + 1) % items.len()];
+  let is_saved = saved.read().contains(&current_item.photo_url);
+  let save_item = current_item.clone();
 
   rsx! {
-    div {
+    document::Stylesheet { href: CAROUSEL_CSS }
+    //{ Warm the cache for the upcoming slide }
+    document::Link { rel: "preload", r#as: "image", href: photo_src(&next_item.photo_url) }
+
+    section {
       class: "carousel-container",
-      style: "
-            position: relative;
-            max-width: 800px;
-            margin: 0 auto;
-            border-radius: 12px;
-            overflow: hidden;
-            box-shadow: 0 10px 30px rgba(0, 0, 0, 0.3);
-            background: white;
-        ",
-
-      // Main carousel display
+      aria_roledescription: "carousel",
+      aria_label: "Featured dogs",
+      tabindex: "0",
+      onmouseenter: move |_| hovered.set(true),
+      onmouseleave: move |_| hovered.set(false),
+      onfocusin: move |_| focused.set(true),
+      onfocusout: move |_| focused.set(false),
+      onkeydown: move |evt: KeyboardEvent| match evt.key() {
+          Key::ArrowLeft => prev_slide(),
+          Key::ArrowRight => next_slide(),
+          _ => {}
+      },
+      ontouchstart: move |evt: TouchEvent| {
+          let x = evt.touches().first().map(|t| t.client_coordinates().x);
+          touch_start.set(x);
+      },
+      ontouchend: move |evt: TouchEvent| {
+          let end = evt.touches_changed().first().map(|t| t.client_coordinates().x);
+          if let (Some(start), Some(end)) = (touch_start.take(), end) {
+              let delta = end - start;
+              if delta > SWIPE_THRESHOLD {
+                  prev_slide();
+              } else if delta < -SWIPE_THRESHOLD {
+                  next_slide();
+              }
+          }
+      },
+
+      // Controls come first so they are reached before the slides
+      div { class: "carousel-controls",
+        if PREFERENCES.read().autoplay {
+          button {
+            r#type: "button",
+            class: "carousel-rotation",
+            onclick: move |_| stopped.toggle(),
+            if stopped() { "Start slide rotation" } else { "Stop slide rotation" }
+          }
+        }
+        button {
+          r#type: "button",
+          class: "carousel-btn prev",
+          aria_controls: "carousel-slides",
+          aria_label: "Previous slide",
+          onclick: move |_| prev_slide(),
+          "‹"
+        }
+        button {
+          r#type: "button",
+          class: "carousel-btn next",
+          aria_controls: "carousel-slides",
+          aria_label: "Next slide",
+          onclick: move |_| next_slide(),
+          "›"
+        }
+      }
+
+      //{ Announce slide changes, except while rotating on its own }
       div {
-        class: "carousel-main",
-        style: "position: relative; height: 400px; overflow: hidden;",
-        img {
-          src: "{current_item.image_url}",
-          alt: "{current_item.title}",
-          style: "
-                width: 100%;
-                height: 100%;
-                object-fit: cover;
-                transition: transform 0.3s ease;
-            ",
+        id: "carousel-slides",
+        class: "carousel-slides",
+        aria_live: if rotating() { "off" } else { "polite" },
+        Slide {
+          dog: current_item.clone(),
+          position: index + 1,
+          total: items.len(),
+          button {
+            r#type: "button",
+            class: "carousel-save",
+            aria_disabled: is_saved,
+            onclick: move |_| {
+                let dog = save_item.clone();
+                async move {
+                    if is_saved {
+                        return;
+                    }
+                    match save_dog(dog).await {
+                        Ok(outcome) => saved.write().push(outcome.favorite.dog.photo_url),
+                        Err(e) => error!("Failed to save dog: {e}")
+                    }
+                }
+            },
+            if is_saved { "Saved" } else { "Save this dog" }
+          }
         }
       }
+
+      Dots {
+        count: items.len(),
+        index,
+        onselect: go_to_slide
+      }
+    }
+  }
+}
+
+/// One slide of the carousel: the photo, its breed and `children` as
+/// actions.
+#[component]
+fn Slide(
+  dog: Dog,
+  position: usize,
+  total: usize,
+  children: Element
+) -> Element {
+  rsx! {
+    div {
+      class: "carousel-slide",
+      role: "group",
+      aria_roledescription: "slide",
+      aria_label: "{position} of {total}",
+
+      div { class: "carousel-main",
+        picture {
+          source {
+            r#type: proxy::Format::Avif.mime(),
+            "srcset": proxy::srcset(&dog.photo_url, proxy::Format::Avif),
+            "sizes": "(max-width: 800px) 100vw, 800px"
+          }
+          img {
+            src: photo_src(&dog.photo_url),
+            srcset: proxy::srcset(&dog.photo_url, proxy::Format::Jpeg),
+            "sizes": "(max-width: 800px) 100vw, 800px",
+            alt: dog.alt_text(),
+          }
+        }
+      }
+
+      div { class: "carousel-info",
+        div {
+          h3 { "{dog.display_name}" }
+          a { href: "{dog.reference_url}", "{dog.reference_url}" }
+        }
+        {children}
+      }
+    }
+  }
+}
+
+/// Slide picker, one button per slide with the shown one marked current.
+#[component]
+fn Dots(count: usize, index: usize, onselect: EventHandler<usize>) -> Element {
+  rsx! {
+    div {
+      class: "carousel-dots",
+      role: "group",
+      aria_label: "Choose a slide",
+
+      for dot in 0..count {
+        button {
+          key: "dot-{dot}",
+          r#type: "button",
+          class: if dot == index { "dot active" } else { "dot" },
+          aria_controls: "carousel-slides",
+          aria_label: "Slide {dot + 1}",
+          aria_current: if dot == index { "true" } else { "false" },
+          onclick: move |_| onselect.call(dot)
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    provider::Breed,
+    views::a11y::{
+      assert_buttons_operable, assert_images_described, attr, render, tags
+    }
+  };
+
+  #[test]
+  fn test_slides_follow_the_carousel_pattern() {
+    fn app() -> Element {
+      let breed = Breed::new("hound", Some("afghan"), "", "Afghan Hound");
+      let dog = Dog::new("https://example.com/afghan.jpg", breed);
+      rsx! {
+        Slide { dog, position: 2, total: 5 }
+      }
+    }
+    let html = render(app);
+    let slide = tags(&html, "div")[0];
+    assert_eq!(attr(slide, "role"), Some("group"));
+    assert_eq!(attr(slide, "aria-roledescription"), Some("slide"));
+    assert_eq!(attr(slide, "aria-label"), Some("2 of 5"));
+    assert_images_described(&html);
+    assert!(html.contains(r#"alt="Photo of an Afghan Hound""#));
+  }
+
+  #[test]
+  fn test_dots_mark_the_current_slide() {
+    fn app() -> Element {
+      rsx! {
+        Dots { count: 3, index: 1, onselect: |_| {} }
+      }
     }
+    let html = render(app);
+    assert_buttons_operable(&html);
+    let current: Vec<_> = tags(&html, "button")
+      .into_iter()
+      .map(|dot| attr(dot, "aria-current"))
+      .collect();
+    assert_eq!(current, [Some("false"), Some("true"), Some("false")]);
+    assert!(html.contains(r#"aria-label="Slide 2""#));
   }
-  // rsx! {
-  //     div {
-  //         class: "carousel-container",
-  //         style: "
-  //               position: relative;
-  //               max-width: 800px;
-  //               margin: 0 auto;
-  //               border-radius: 12px;
-  //               overflow: hidden;
-  //               box-shadow: 0 10px 30px rgba(0, 0, 0, 0.3);
-  //               background: white;
-  //           ",
-
-  //         // Main carousel display
-  //         div {
-  //             class: "carousel-main",
-  //             style: "position: relative; height: 400px; overflow: hidden;",
-
-  //             img {
-  //                 src: "{current_item.image_url}",
-  //                 alt: "{current_item.title}",
-  //                 style: "
-  //                       width: 100%;
-  //                       height: 100%;
-  //                       object-fit: cover;
-  //                       transition: transform 0.3s ease;
-  //                   "
-  //             }
-
-  //             // Navigation buttons
-  //             button {
-  //                 class: "carousel-btn prev",
-  //                 style: "
-  //                       position: absolute;
-  //                       left: 10px;
-  //                       top: 50%;
-  //                       transform: translateY(-50%);
-  //                       background: rgba(0, 0, 0, 0.5);
-  //                       color: white;
-  //                       border: none;
-  //                       padding: 10px 15px;
-  //                       font-size: 18px;
-  //                       cursor: pointer;
-  //                       border-radius: 50%;
-  //                       transition: background-color 0.3s;
-  //                   ",
-  //                 onclick: prev_slide,
-  //                 "‹"
-  //             }
-
-  //             button {
-  //                 class: "carousel-btn next",
-  //                 style: "
-  //                       position: absolute;
-  //                       right: 10px;
-  //                       top: 50%;
-  //                       transform: translateY(-50%);
-  //                       background: rgba(0, 0, 0, 0.5);
-  //                       color: white;
-  //                       border: none;
-  //                       padding: 10px 15px;
-  //                       font-size: 18px;
-  //                       cursor: pointer;
-  //                       border-radius: 50%;
-  //                       transition: background-color 0.3s;
-  //                   ",
-  //                 onclick: next_slide,
-  //                 "›"
-  //             }
-  //         }
-
-  //         // Image info
-  //         div {
-  //             class: "carousel-info",
-  //             style: "
-  //                   padding: 20px;
-  //                   background: white;
-  //               ",
-
-  //             h3 {
-  //                 style: "
-  //                       margin: 0 0 10px 0;
-  //                       font-size: 1.3rem;
-  //                       color: #333;
-  //                   ",
-  //                 "{current_item.title}"
-  //             }
-
-  //             p {
-  //                 style: "
-  //                       margin: 0;
-  //                       color: #666;
-  //                       line-height: 1.5;
-  //                   ",
-  //                 "{current_item.description}"
-  //             }
-  //         }
-
-  //         // Dot indicators
-  //         div {
-  //             class: "carousel-dots",
-  //             style: "
-  //                   display: flex;
-  //                   justify-content: center;
-  //                   gap: 8px;
-  //                   padding: 15px 20px;
-  //                   background: white;
-  //               ",
-
-  //             for (index, _item) in items.iter().enumerate() {
-  //                 button {
-  //                     key: "dot-{index}",
-  //                     class: "dot",
-  //                     style: "
-  //                           width: 12px;
-  //                           height: 12px;
-  //                           border-radius: 50%;
-  //                           border: none;
-  //                           cursor: pointer;
-  //                           transition: background-color 0.3s;
-  //                           background-color: {if index == current_index() {
-  // \"#117eeb\" } else { \"#ddd\" }};                       ",
-  //                     onclick: go_to_slide(index)
-  //                 }
-  //             }
-  //         }
-  //     }
-  // }
 }
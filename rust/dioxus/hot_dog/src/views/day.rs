@@ -0,0 +1,88 @@
+use super::prelude::*;
+use crate::{api::dog_of_the_day, data::daily};
+
+/// Sends `/day` to the visitor's own calendar date.
+#[component]
+pub fn Today() -> Element {
+  let navigator = use_navigator();
+  use_effect(move || {
+    spawn(async move {
+      //~@ The browser reports the offset in minutes *behind* UTC
+      let behind = document::eval("return new Date().getTimezoneOffset();")
+        .join::<i64>()
+        .await
+        .unwrap_or(0);
+      navigator.replace(Route::Day {
+        date: time::today(-behind)
+      });
+    });
+  });
+
+  rsx! {
+    p { "Loading..." }
+  }
+}
+
+#[component]
+pub fn Day(date: String) -> Element {
+  let dog =
+    use_resource(use_reactive!(
+      |date| async move { dog_of_the_day(date).await }
+    ));
+
+  //{ Neighbouring days, hiding tomorrow until it has started somewhere }
+  let shift = |days: i64| {
+    time::parse_date(&date)
+      .map(|day| time::format_date(day + days * 86_400))
+      .filter(|day| daily::is_available(day, time::now()))
+  };
+  let previous = shift(-1);
+  let next = shift(1);
+
+  rsx! {
+    document::Stylesheet { href: DOG_CSS }
+    div { id: "dogview",
+      h2 { "Dog of the day" }
+      p { class: "day-date", "{date}" }
+      match &*dog.read() {
+          Some(Err(e)) => rsx! {
+            p { class: "error", "Error: {e}" }
+          },
+          None => rsx! {
+            p { "Loading..." }
+          },
+          Some(Ok(dog)) => rsx! {
+            picture {
+              source {
+                r#type: proxy::Format::Avif.mime(),
+                "srcset": proxy::srcset(&dog.photo_url, proxy::Format::Avif),
+                "sizes": "(max-width: 500px) 100vw, 500px"
+              }
+              img {
+                class: "dog-image-container",
+                src: photo_src(&dog.photo_url),
+                srcset: proxy::srcset(&dog.photo_url, proxy::Format::Jpeg),
+                "sizes": "(max-width: 500px) 100vw, 500px",
+                alt: dog.alt_text(),
+              }
+            }
+            div { class: "dog-info",
+              h2 { "{dog.display_name}" }
+              p {
+                "Source: "
+                a { href: "{dog.reference_url}", "{dog.photo_url}" }
+              }
+            }
+          },
+      }
+      nav { class: "day-nav",
+        if let Some(previous) = previous {
+          Link { to: Route::Day { date: previous }, "← Previous day" }
+        }
+        if let Some(next) = next {
+          Link { to: Route::Day { date: next }, "Next day →" }
+        }
+      }
+    }
+  }
+}
@@ -1,17 +1,22 @@
 use super::prelude::*;
 
 pub fn launch() {
+  #[cfg(feature = "server")]
+  crate::server::launch(view);
+
+  #[cfg(not(feature = "server"))]
   dioxus::launch(view);
 }
 
 #[component]
 fn view() -> Element {
+  use_preferences();
+
   rsx! {
     document::Title { {format!("{TITLE}")} }
-    // document::Stylesheet { href: CSS }
+    document::Stylesheet { href: CSS }
     document::Link { rel: "icon", href: ICON }
-    Header {}
-    Main {}
-    Footer {}
+    SavedTheme {}
+    Router::<Route> {}
   }
 }
@@ -1,138 +1,207 @@
-use crate::prelude::*;
-
-#[derive(serde::Deserialize, Clone, Default)]
-pub struct Config {
-  pub message: String
-}
+use super::prelude::*;
+use crate::{
+  api::{mark_served, next_dog, rate_dog, save_dog},
+  data::{self, Vote, filter::BreedFilter}
+};
 
 #[component]
-pub fn Dog() -> Element {
-  //{ Define the first dog }
-  let mut dog = use_resource(|| async move { get_from_dog_ceo().await });
-
-  #[derive(Clone)]
-  enum State {
-    Loading,
-    Error(String),
-    Loaded { image_url: String, breed: String }
-  }
-
-  let view_state = {
-    let state = dog.read();
-    match &*state {
-      Some(Ok(dog)) => {
-        let breed = extract_breed_from_url(&dog.message);
-        State::Loaded {
-          image_url: dog.message.clone(),
-          breed
+pub fn Dog(
+  /// Breeds the dogs are drawn from; changing it drops the queued ones.
+  filter: ReadOnlySignal<BreedFilter>,
+  /// Dogs kept loaded behind the one on screen.
+  #[props(default = 3)]
+  depth: usize
+) -> Element {
+  //{ Fetch dogs through the server so photos already seen are skipped }
+  let source =
+    use_memo(move || (PREFERENCES.read().provider.clone(), filter()));
+  let mut dogs =
+    use_prefetch(source.into(), depth, move |(provider, filter), taken| {
+      let queued = taken
+        .into_iter()
+        .map(|dog: data::Dog| dog.photo_url)
+        .collect();
+      next_dog(provider, filter, queued)
+    });
+  //{ Only a dog that made it on screen counts as served }
+  use_effect(move || {
+    if let Some(Ok(dog)) = dogs.current() {
+      spawn(async move {
+        if let Err(e) = mark_served(dog.photo_url).await {
+          warn!("Failed to record a served dog: {e}");
         }
-      }
-      Some(Err(e)) => State::Error(e.to_string()),
-      None => State::Loading
+      });
     }
-  };
+  });
+  let mut notice = use_signal(|| None::<String>);
+
+  //{ Keep a copy of the loaded dog for the save button }
+  let current = use_memo(move || dogs.current().and_then(|dog| dog.ok()));
 
   rsx! {
     document::Stylesheet { href: DOG_CSS }
     div { id: "dogview",
-      match &view_state {
-          State::Error(msg) => rsx! {
-            p { class: "error", "Error: {msg}" }
+      match dogs.current() {
+          Some(Err(e)) => rsx! {
+            p { class: "error", "Error: {e}" }
           },
-          State::Loading => rsx! {
+          None => rsx! {
             p { "Loading..." }
           },
-          State::Loaded { image_url, breed } => rsx! {
-            img {
-              class: "dog-image-container",
-              max_width: "500px",
-              max_height: "500px",
-              src: "{image_url}",
-            }
-            div { class: "dog-info",
-              h2 { "{breed}" }
-              p { "Source: {image_url}" }
+          Some(Ok(dog)) => rsx! {
+            DogCard { dog: dog.clone(),
+              p {
+                Link { to: permalink(&dog), "Permalink" }
+              }
             }
           },
       }
+      //{ Tell screen readers which dog the buttons now act on }
+      p { class: "visually-hidden", role: "status",
+        match dogs.current() {
+            Some(Ok(dog)) => rsx! { "Showing {dog.display_name}" },
+            Some(Err(_)) => rsx! { "Couldn't load a dog, skip to try another" },
+            None => rsx! { "Loading the next dog" },
+        }
+      }
       div { id: "buttons",
-        button { onclick: move |_| dog.restart(), id: "skip", "skip" }
+        button {
+          id: "skip",
+          r#type: "button",
+          onclick: move |_| async move {
+              let skipped = current();
+              notice.set(None);
+              dogs.advance();
+              if let Some(skipped) = skipped {
+                  if let Err(e) = rate_dog(skipped, Vote::Skip).await {
+                      error!("Failed to record skip: {e}");
+                  }
+              }
+          },
+          "skip"
+        }
         button {
           id: "save",
-          onclick: move |_| {
-              let value = view_state.clone();
-              async move {
-                  if let State::Loaded { image_url, .. } = value {
-                      let current = image_url.clone();
-                      _ = save_dog(current).await;
-                      dog.restart();
+          r#type: "button",
+          //~@ Not `disabled`, which would drop focus while the next dog loads
+          aria_disabled: if current().is_none() { "true" } else { "false" },
+          onclick: move |_| async move {
+              if let Some(current) = current() {
+                  dogs.advance();
+                  match save_dog(current.clone()).await {
+                      Ok(saved) if !saved.duplicates.is_empty() => {
+                          let count = saved.duplicates.len();
+                          notice.set(Some(format!("Saved, but it looks like {count} photo(s) already in your favorites")));
+                      }
+                      Ok(_) => notice.set(None),
+                      Err(e) => error!("Failed to save dog: {e}"),
+                  }
+                  if let Err(e) = rate_dog(current, Vote::Like).await {
+                      error!("Failed to record like: {e}");
                   }
               }
           },
-
           "save!"
         }
       }
+      if let Some(notice) = notice() {
+        p { class: "notice", role: "status", "{notice}" }
+      }
+      //{ Hidden copies of the queued photos so the browser loads them early }
+      for dog in dogs.queued() {
+        picture {
+          key: "{dog.photo_url}",
+          source {
+            r#type: proxy::Format::Avif.mime(),
+            "srcset": proxy::srcset(&dog.photo_url, proxy::Format::Avif),
+            "sizes": "(max-width: 500px) 100vw, 500px"
+          }
+          img {
+            hidden: true,
+            alt: "",
+            src: photo_src(&dog.photo_url),
+            srcset: proxy::srcset(&dog.photo_url, proxy::Format::Jpeg),
+            "sizes": "(max-width: 500px) 100vw, 500px",
+          }
+        }
+      }
     }
   }
 }
 
-fn ensure_not_empty(dog: Dog) -> Result<Dog, Error> {
-  if dog.message.is_empty() {
-    Err(Error::NoResponse)
-  } else {
-    Ok(dog)
+/// A dog's photo and breed, followed by `children`.
+#[component]
+fn DogCard(dog: data::Dog, children: Element) -> Element {
+  rsx! {
+    picture {
+      source {
+        r#type: proxy::Format::Avif.mime(),
+        "srcset": proxy::srcset(&dog.photo_url, proxy::Format::Avif),
+        "sizes": "(max-width: 500px) 100vw, 500px"
+      }
+      img {
+        class: "dog-image-container",
+        max_width: "500px",
+        max_height: "500px",
+        src: photo_src(&dog.photo_url),
+        srcset: proxy::srcset(&dog.photo_url, proxy::Format::Jpeg),
+        "sizes": "(max-width: 500px) 100vw, 500px",
+        alt: dog.alt_text(),
+      }
+    }
+    div { class: "dog-info",
+      h2 { "{dog.display_name}" }
+      p {
+        "Source: "
+        a { href: "{dog.reference_url}", "{dog.photo_url}" }
+      }
+      {children}
+    }
   }
 }
 
-async fn get_from_dog_ceo() -> Result<Dog, Error> {
-  let response = reqwest::get(DOG_CEO_RANDO).await?;
-  let dog = response.json::<Dog>().await?;
-  ensure_not_empty(dog)
-}
-
-pub fn extract_breed_from_url(dog_ceo_url: &str) -> String {
-  let raw = dog_ceo_url
-    .split("/breeds/")
-    .nth(1)
-    .and_then(|s| s.split('/').next())
-    .unwrap_or("");
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    provider::Breed,
+    views::a11y::{
+      assert_buttons_operable, assert_images_described, attr, render, tags
+    }
+  };
 
-  //{ Format: "sheepdog-english" -> "English Sheepdog" or "Sheepdog English" }
-  let mut parts: Vec<&str> = raw.split('-').collect();
-  if parts.len() == 2 {
-    //{ Try to present as "English Sheepdog" (sub-breed first) }
-    parts.reverse();
-  }
-  let formatted = parts
-    .into_iter()
-    .map(|s| {
-      let mut c = s.chars();
-      match c.next() {
-        Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
-        None => String::new()
+  #[test]
+  fn test_dog_card_describes_the_photo() {
+    fn app() -> Element {
+      let breed =
+        Breed::new("sheepdog", Some("english"), "", "English Sheepdog");
+      let dog = data::Dog::new("https://example.com/sheepdog.jpg", breed);
+      rsx! {
+        DogCard { dog }
       }
-    })
-    .collect::<Vec<_>>()
-    .join(" ");
-  formatted
-}
-
-#[server]
-async fn save_dog(image: String) -> Result<(), ServerFnError> {
-  use std::io::Write;
-
-  // Open the `dogs.txt` file in append-only mode, creating it if it doesn't
-  // exist;
-  let mut file = std::fs::OpenOptions::new()
-    .write(true)
-    .append(true)
-    .create(true)
-    .open(DOG_TXT)
-    .unwrap();
-
-  // And then write a newline to it with the image url
-  file.write_fmt(format_args!("{image}\n"));
+    }
+    let html = render(app);
+    assert_images_described(&html);
+    assert_eq!(
+      attr(tags(&html, "img")[0], "alt"),
+      Some("Photo of an English Sheepdog")
+    );
+  }
 
-  Ok(())
+  #[test]
+  fn test_buttons_stay_operable_while_loading() {
+    fn app() -> Element {
+      rsx! {
+        Dog { filter: BreedFilter::Any }
+      }
+    }
+    let html = render(app);
+    assert_buttons_operable(&html);
+    let save = tags(&html, "button")
+      .into_iter()
+      .find(|b| attr(b, "id") == Some("save"))
+      .unwrap();
+    assert_eq!(attr(save, "aria-disabled"), Some("true"));
+    assert!(html.contains(r#"role="status""#));
+  }
 }
@@ -0,0 +1,372 @@
+use super::prelude::*;
+use crate::{
+  api::{
+    analyze_favorites, delete_favorites, list_favorites, merge_duplicates,
+    set_favorite_tags
+  },
+  data::{
+    Favorite, ImageMeta, bundle,
+    favorite::{Filter, Sort, duplicate_groups, parse_tags},
+    transfer::{self, Format, Record}
+  },
+  utils::time::format_date
+};
+use std::collections::{BTreeSet, HashSet};
+
+#[component]
+pub fn Favorites() -> Element {
+  let mut favorites = use_resource(list_favorites);
+  let mut filter = use_signal(Filter::default);
+  let mut selected = use_signal(HashSet::<i64>::new);
+  let mut lightbox = use_signal(|| None::<Favorite>);
+  let mut notice = use_signal(|| None::<String>);
+  let mut export_format = use_signal(Format::default);
+
+  //{ Pick up what other tabs and devices save or change }
+  use_on_change(move |change| {
+    if change.touches_favorites() {
+      favorites.restart();
+    }
+  });
+
+  let all = move || {
+    favorites
+      .read()
+      .as_ref()
+      .and_then(|result| result.as_ref().ok())
+      .cloned()
+      .unwrap_or_default()
+  };
+
+  let visible = use_memo(move || filter.read().apply(&all()));
+
+  //{ Offer only the breeds and tags that actually appear }
+  let breeds = use_memo(move || {
+    all()
+      .into_iter()
+      .map(|f| f.dog.display_name)
+      .collect::<BTreeSet<_>>()
+  });
+  let tags = use_memo(move || {
+    all()
+      .into_iter()
+      .flat_map(|f| f.tags)
+      .collect::<BTreeSet<_>>()
+  });
+
+  //{ Favorites that look like another saved photo }
+  let duplicates = use_memo(move || {
+    duplicate_groups(&all())
+      .into_iter()
+      .flatten()
+      .collect::<HashSet<_>>()
+  });
+
+  let mut toggle = move |id: i64| {
+    let mut selected = selected.write();
+    if !selected.remove(&id) {
+      selected.insert(id);
+    }
+  };
+
+  let delete_selected = move |_| async move {
+    let ids: Vec<i64> = selected.read().iter().copied().collect();
+    match delete_favorites(ids).await {
+      Ok(removed) => debug!("Deleted {removed} favorites"),
+      Err(e) => error!("Failed to delete favorites: {e}")
+    }
+    selected.write().clear();
+    favorites.restart();
+  };
+
+  let analyze_missing = move |_| async move {
+    match analyze_favorites().await {
+      Ok(analyzed) => debug!("Analyzed {analyzed} favorites"),
+      Err(e) => error!("Failed to analyze favorites: {e}")
+    }
+    favorites.restart();
+  };
+
+  let merge = move |_| async move {
+    match merge_duplicates().await {
+      Ok(report) => notice.set(Some(format!(
+        "Merged {} group(s), removing {} duplicate(s)",
+        report.groups, report.removed
+      ))),
+      Err(e) => error!("Failed to merge duplicates: {e}")
+    }
+    selected.write().clear();
+    favorites.restart();
+  };
+
+  //{ The selection, or everything shown, as a downloadable document }
+  let export_href = use_memo(move || {
+    let chosen: Vec<Record> = visible()
+      .iter()
+      .filter(|f| selected.read().is_empty() || selected.read().contains(&f.id))
+      .map(Record::from)
+      .collect();
+    let format = export_format();
+    format!(
+      "data:{};charset=utf-8,{}",
+      format.mime(),
+      percent_encode(&transfer::export(&chosen, format))
+    )
+  });
+
+  //{ The selection, or every favorite, packed for offline use }
+  let bundle_href = use_memo(move || {
+    let mut ids: Vec<i64> = selected.read().iter().copied().collect();
+    ids.sort_unstable();
+    bundle::download_url(&ids)
+  });
+
+  rsx! {
+    document::Stylesheet { href: FAVORITES_CSS }
+    section { id: "favorites",
+      h2 { "Favorites" }
+
+      div { class: "favorites-toolbar",
+        select {
+          onchange: move |evt| {
+              let value = evt.value();
+              filter.write().breed = (!value.is_empty()).then_some(value);
+          },
+          option { value: "", "All breeds" }
+          for breed in breeds() {
+            option { key: "{breed}", value: "{breed}", "{breed}" }
+          }
+        }
+        select {
+          onchange: move |evt| {
+              let value = evt.value();
+              filter.write().tag = (!value.is_empty()).then_some(value);
+          },
+          option { value: "", "All tags" }
+          for tag in tags() {
+            option { key: "{tag}", value: "{tag}", "#{tag}" }
+          }
+        }
+        select {
+          onchange: move |evt| {
+              if let Some(sort) = evt.value().parse().ok().and_then(|i: usize| Sort::ALL.get(i)) {
+                  filter.write().sort = *sort;
+              }
+          },
+          for (i, sort) in Sort::ALL.iter().enumerate() {
+            option { key: "{i}", value: "{i}", "{sort.label()}" }
+          }
+        }
+      }
+
+      div { class: "favorites-toolbar",
+        span { class: "count", "{selected.read().len()} selected" }
+        button {
+          onclick: move |_| selected.set(visible().iter().map(|f| f.id).collect()),
+          "Select all"
+        }
+        button {
+          disabled: selected.read().is_empty(),
+          onclick: move |_| selected.write().clear(),
+          "Clear"
+        }
+        button {
+          disabled: selected.read().is_empty(),
+          onclick: delete_selected,
+          "Delete"
+        }
+        select {
+          onchange: move |evt| {
+              if let Some(format) = Format::parse(&evt.value()) {
+                  export_format.set(format);
+              }
+          },
+          for format in Format::ALL {
+            option { key: "{format.as_str()}", value: format.as_str(), "{format.label()}" }
+          }
+        }
+        a {
+          href: "{export_href}",
+          download: "favorites.{export_format().extension()}",
+          if selected.read().is_empty() { "Export all" } else { "Export selected" }
+        }
+        a {
+          href: "{bundle_href}",
+          download: "hot-dog-bundle.zip",
+          title: "Photos and breed data for running the app offline",
+          "Offline bundle"
+        }
+        if all().iter().any(|f| f.meta.is_none()) {
+          button { onclick: analyze_missing, "Analyze photos" }
+        }
+        if !duplicates.read().is_empty() {
+          button { onclick: merge, "Merge duplicates" }
+        }
+      }
+
+      ImportPanel { onimport: move |_| favorites.restart() }
+
+      if let Some(notice) = notice() {
+        p { class: "notice", "{notice}" }
+      }
+
+      match &*favorites.read() {
+          Some(Err(e)) => rsx! {
+            p { class: "error", "Error: {e}" }
+          },
+          None => rsx! {
+            p { "Loading..." }
+          },
+          Some(Ok(saved)) if saved.is_empty() => rsx! {
+            p { "Dogs you save from the viewer will show up here." }
+          },
+          Some(Ok(_)) => rsx! {
+            div { class: "masonry",
+              for favorite in visible() {
+                figure {
+                  key: "{favorite.id}",
+                  class: if selected.read().contains(&favorite.id) { "tile selected" } else { "tile" },
+                  input {
+                    r#type: "checkbox",
+                    checked: selected.read().contains(&favorite.id),
+                    onclick: move |evt| evt.stop_propagation(),
+                    onchange: move |_| toggle(favorite.id)
+                  }
+                  picture {
+                    source {
+                      r#type: proxy::Format::Avif.mime(),
+                      "srcset": proxy::srcset(&favorite.dog.photo_url, proxy::Format::Avif),
+                      "sizes": "(max-width: 600px) 50vw, 320px"
+                    }
+                    img {
+                      src: photo_src(&favorite.dog.photo_url),
+                      srcset: proxy::srcset(&favorite.dog.photo_url, proxy::Format::Jpeg),
+                      "sizes": "(max-width: 600px) 50vw, 320px",
+                      alt: favorite.dog.alt_text(),
+                      loading: "lazy",
+                      style: placeholder_style(favorite.meta.as_ref()),
+                      onclick: {
+                          let favorite = favorite.clone();
+                          move |_| lightbox.set(Some(favorite.clone()))
+                      }
+                    }
+                  }
+                  if duplicates.read().contains(&favorite.id) {
+                    span { class: "badge", title: "Looks like another saved photo", "duplicate" }
+                  }
+                  figcaption { "{favorite.dog.display_name}" }
+                }
+              }
+            }
+          },
+      }
+
+      if let Some(favorite) = lightbox() {
+        Lightbox {
+          key: "{favorite.id}",
+          favorite,
+          onclose: move |_| lightbox.set(None),
+          onchange: move |_| favorites.restart()
+        }
+      }
+    }
+  }
+}
+
+#[component]
+fn Lightbox(
+  favorite: Favorite,
+  onclose: EventHandler<()>,
+  onchange: EventHandler<()>
+) -> Element {
+  let mut tags = use_signal(|| favorite.tags.join(", "));
+  let id = favorite.id;
+
+  let save_tags = move |_| async move {
+    match set_favorite_tags(id, parse_tags(&tags())).await {
+      Ok(()) => onchange.call(()),
+      Err(e) => error!("Failed to update tags: {e}")
+    }
+  };
+
+  rsx! {
+    div {
+      class: "lightbox",
+      tabindex: "0",
+      onmounted: move |evt| async move {
+          let _ = evt.set_focus(true).await;
+      },
+      onclick: move |_| onclose.call(()),
+      onkeydown: move |evt: KeyboardEvent| {
+          if evt.key() == Key::Escape {
+              onclose.call(());
+          }
+      },
+      div {
+        class: "lightbox-content",
+        onclick: move |evt| evt.stop_propagation(),
+        picture {
+          source {
+            r#type: proxy::Format::Avif.mime(),
+            "srcset": proxy::srcset(&favorite.dog.photo_url, proxy::Format::Avif),
+            "sizes": "(max-width: 500px) 100vw, 500px"
+          }
+          img {
+            src: photo_src(&favorite.dog.photo_url),
+            srcset: proxy::srcset(&favorite.dog.photo_url, proxy::Format::Jpeg),
+            "sizes": "(max-width: 500px) 100vw, 500px",
+            alt: favorite.dog.alt_text()
+          }
+        }
+        div { class: "lightbox-details",
+          h3 { "{favorite.dog.display_name}" }
+          p { "Saved {format_date(favorite.saved_at)}" }
+          if let Some(meta) = &favorite.meta {
+            p {
+              "{meta.width} × {meta.height} · {meta.format.to_uppercase()} · {meta.size_label()}"
+            }
+            div { class: "palette",
+              for colour in meta.palette.iter() {
+                span {
+                  key: "{colour}",
+                  title: "{colour}",
+                  background_color: "{colour}"
+                }
+              }
+            }
+          }
+          p {
+            "Source: "
+            a { href: "{favorite.dog.reference_url}", "{favorite.dog.reference_url}" }
+          }
+          p {
+            Link { to: permalink(&favorite.dog), "Permalink" }
+          }
+          label {
+            "Tags "
+            input {
+              value: "{tags}",
+              placeholder: "fluffy, sleepy",
+              oninput: move |evt| tags.set(evt.value())
+            }
+          }
+          div { class: "favorites-toolbar",
+            button { onclick: save_tags, "Save tags" }
+            button { onclick: move |_| onclose.call(()), "Close" }
+          }
+        }
+      }
+    }
+  }
+}
+
+/// Reserves the photo's space and paints its dominant colour until it loads.
+fn placeholder_style(meta: Option<&ImageMeta>) -> String {
+  meta.map_or_else(String::new, |meta| {
+    format!(
+      "aspect-ratio: {}; background-color: {};",
+      meta.aspect_ratio(),
+      meta.placeholder()
+    )
+  })
+}
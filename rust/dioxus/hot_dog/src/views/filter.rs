@@ -0,0 +1,109 @@
+use super::prelude::*;
+use crate::{
+  data::filter::{BreedFilter, BreedKey},
+  provider::{Breed, DogCeo}
+};
+use std::path::PathBuf;
+
+/// Picks which breeds to show, handing every change to `onchange`.
+#[component]
+pub fn BreedPicker(
+  filter: BreedFilter,
+  onchange: EventHandler<BreedFilter>
+) -> Element {
+  let catalog =
+    use_resource(|| async { DogCeo::get_breeds(None::<PathBuf>, None).await });
+  let mut excluding = use_signal(|| matches!(filter, BreedFilter::Except(_)));
+
+  let chosen = filter.breeds().to_vec();
+  //~@ With no breeds chosen yet the mode only lives in this component
+  let except = match &filter {
+    BreedFilter::Any => excluding(),
+    BreedFilter::Only(_) => false,
+    BreedFilter::Except(_) => true
+  };
+  let apply = move |breeds: Vec<BreedKey>, excluding: bool| {
+    let filter = match (breeds.is_empty(), excluding) {
+      (true, _) => BreedFilter::Any,
+      (false, true) => BreedFilter::Except(breeds),
+      (false, false) => BreedFilter::Only(breeds)
+    };
+    onchange.call(filter);
+  };
+
+  rsx! {
+    div { class: "breed-filter",
+      select {
+        aria_label: "Filter mode",
+        value: if except { "except" } else { "only" },
+        onchange: {
+            let chosen = chosen.clone();
+            move |evt: FormEvent| {
+                let except = evt.value() == "except";
+                excluding.set(except);
+                apply(chosen.clone(), except);
+            }
+        },
+        option { value: "only", "Only show" }
+        option { value: "except", "Never show" }
+      }
+      ul { class: "breed-chips",
+        if chosen.is_empty() {
+          li { class: "breed-chip-empty", "any breed" }
+        }
+        for breed in chosen.clone() {
+          li { key: "{breed.label()}", class: "breed-chip",
+            {Breed::format_name(&breed.main, breed.sub.as_deref())}
+            button {
+              aria_label: "Remove {breed.label()}",
+              onclick: {
+                  let chosen = chosen.clone();
+                  let breed = breed.clone();
+                  move |_| {
+                      let rest = chosen.iter().filter(|b| **b != breed).cloned().collect();
+                      apply(rest, except);
+                  }
+              },
+              "×"
+            }
+          }
+        }
+      }
+      if let Some(Ok(breeds)) = &*catalog.read() {
+        select {
+          aria_label: "Add a breed",
+          value: "",
+          onchange: {
+              let chosen = chosen.clone();
+              move |evt: FormEvent| {
+                  let Some(breed) = BreedKey::parse(&evt.value()) else {
+                      return;
+                  };
+                  if !chosen.contains(&breed) {
+                      let mut breeds = chosen.clone();
+                      breeds.push(breed);
+                      apply(breeds, except);
+                  }
+              }
+          },
+          option { value: "", disabled: true, "Add a breed..." }
+          for (main, subs) in breeds.search("") {
+            if subs.is_empty() {
+              option { value: "{main}", {capitalize(&main)} }
+            } else {
+              option { value: "{main}", "Every {capitalize(&main)}" }
+            }
+            for sub in subs {
+              option { value: "{main}-{sub}",
+                {Breed::format_name(&main, Some(&sub))}
+              }
+            }
+          }
+        }
+      }
+      if !chosen.is_empty() {
+        button { onclick: move |_| apply(Vec::new(), except), "Clear" }
+      }
+    }
+  }
+}
@@ -4,31 +4,17 @@ use super::prelude::*;
 pub fn Footer() -> Element {
   rsx! {
     footer {
-      div {
-        class: "footer-content",
-        style: "display: flex; justify-content: space-between; align-items: center; flex-wrap: wrap; gap: 1rem;",
+      div { class: "footer-content",
 
-        div { style: "color: #666;", "© 2024 My Gallery Site. All rights reserved." }
+        div { "© 2024 My Gallery Site. All rights reserved." }
 
-        div { style: "display: flex; gap: 1rem;",
+        div { class: "footer-links",
 
-          a {
-            href: "#",
-            style: "color: #117eeb; text-decoration: none;",
-            "Privacy Policy"
-          }
+          a { href: "#", "Privacy Policy" }
 
-          a {
-            href: "#",
-            style: "color: #117eeb; text-decoration: none;",
-            "Terms of Service"
-          }
+          a { href: "#", "Terms of Service" }
 
-          a {
-            href: "#",
-            style: "color: #117eeb; text-decoration: none;",
-            "Contact Us"
-          }
+          a { href: "#", "Contact Us" }
         }
       }
     }
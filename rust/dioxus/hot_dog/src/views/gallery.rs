@@ -0,0 +1,17 @@
+use super::prelude::*;
+
+#[component]
+pub fn Gallery() -> Element {
+  rsx! {
+    section { class: "hero-section",
+
+      h2 { "Featured Gallery" }
+
+      p {
+        "Discover amazing dogs in our interactive carousel gallery. Swipe, use the arrow keys or just sit back and let them roll by."
+      }
+
+      ImageCarousel {}
+    }
+  }
+}
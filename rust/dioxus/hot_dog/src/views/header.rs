@@ -1,40 +1,49 @@
 use super::prelude::*;
+use crate::data::filter::BreedFilter;
 
 #[component]
 pub fn Header() -> Element {
   rsx! {
     header {
       img { src: LOGO }
-      h1 { style: "color: white; margin: 0; font-size: 1.5rem;", {format!("{TITLE}!")} }
+      h1 { {format!("{TITLE}!")} }
       nav {
         ul {
           li {
-            a {
-              href: "#",
-              style: "color: white; text-decoration: none; padding: 0.5rem 1rem; border-radius: 4px; transition: background-color 0.3s;",
-              "Home"
-            }
+            Link { to: Route::Home { filter: BreedFilter::Any }, "Home" }
           }
           li {
-            a {
-              href: "#",
-              style: "color: white; text-decoration: none; padding: 0.5rem 1rem; border-radius: 4px; transition: background-color 0.3s;",
-              "Gallery"
-            }
+            Link { to: Route::Gallery {}, "Gallery" }
           }
           li {
-            a {
-              href: "#",
-              style: "color: white; text-decoration: none; padding: 0.5rem 1rem; border-radius: 4px; transition: background-color 0.3s;",
-              "About"
-            }
+            Link { to: Route::Favorites {}, "Favorites" }
           }
           li {
-            a {
-              href: "#",
-              style: "color: white; text-decoration: none; padding: 0.5rem 1rem; border-radius: 4px; transition: background-color 0.3s;",
-              "Contact"
-            }
+            Link { to: Route::Breeds {}, "Breeds" }
+          }
+          li {
+            Link { to: Route::Rate {}, "Rate" }
+          }
+          li {
+            Link { to: Route::Matchup {}, "Matchup" }
+          }
+          li {
+            Link { to: Route::Quiz {}, "Quiz" }
+          }
+          li {
+            Link { to: Route::Today {}, "Daily" }
+          }
+          li {
+            Link { to: Route::Leaderboard {}, "Leaderboard" }
+          }
+          li {
+            Link { to: Route::About {}, "About" }
+          }
+          li {
+            Link { to: Route::Account {}, "Account" }
+          }
+          li {
+            Link { to: Route::Settings {}, "Settings" }
           }
         }
       }
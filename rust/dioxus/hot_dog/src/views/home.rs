@@ -0,0 +1,33 @@
+use super::{prefs, prelude::*};
+use crate::data::filter::BreedFilter;
+
+#[component]
+pub fn Home(filter: BreedFilter) -> Element {
+  let navigator = use_navigator();
+
+  //{ Fall back to the saved filter when the URL doesn't pick one }
+  let from_url = filter.clone();
+  use_effect(move || {
+    if !from_url.is_any() {
+      return;
+    }
+    spawn(async move {
+      let filter = prefs::load().await.filter;
+      if !filter.is_any() {
+        navigator.replace(Route::Home { filter });
+      }
+    });
+  });
+
+  rsx! {
+    BreedPicker {
+      filter: filter.clone(),
+      onchange: move |filter: BreedFilter| {
+          let saved = filter.clone();
+          prefs::update(move |prefs| prefs.filter = saved);
+          navigator.replace(Route::Home { filter });
+      }
+    }
+    Dog { filter }
+  }
+}
@@ -0,0 +1,29 @@
+use super::prelude::*;
+use crate::{api::whoami, data::filter::BreedFilter};
+
+#[component]
+pub fn Layout() -> Element {
+  //{ Start a guest session up front so saving works before signing up }
+  use_resource(whoami);
+  use_live_sync();
+  use_offline();
+
+  rsx! {
+    Header {}
+    main { Outlet::<Route> {} }
+    Footer {}
+  }
+}
+
+#[component]
+pub fn NotFound(segments: Vec<String>) -> Element {
+  let path = segments.join("/");
+
+  rsx! {
+    section {
+      h2 { "Page not found" }
+      p { "There is no page at /{path}." }
+      Link { to: Route::Home { filter: BreedFilter::Any }, "Back to the dogs" }
+    }
+  }
+}
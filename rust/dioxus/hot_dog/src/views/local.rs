@@ -0,0 +1,59 @@
+use super::prelude::*;
+
+/// Reads `key` from the browser's local storage, or from the config folder
+/// on desktop. `None` when unset or unavailable.
+pub async fn load(key: &str) -> Option<String> {
+  #[cfg(feature = "desktop")]
+  return std::fs::read_to_string(config_path(key)?).ok();
+
+  #[cfg(not(feature = "desktop"))]
+  {
+    let key = serde_json::to_string(key).ok()?;
+    document::eval(&format!("return localStorage.getItem({key});"))
+      .join::<Option<String>>()
+      .await
+      .ok()
+      .flatten()
+  }
+}
+
+/// Writes `value` under `key` in the browser's local storage, or to the
+/// config folder on desktop.
+pub fn store(key: &str, value: &str) {
+  #[cfg(feature = "desktop")]
+  {
+    let Some(path) = config_path(key) else {
+      warn!("No config folder to save {key} in");
+      return;
+    };
+    let written = path
+      .parent()
+      .map_or(Ok(()), std::fs::create_dir_all)
+      .and_then(|()| std::fs::write(&path, value));
+    if let Err(e) = written {
+      warn!("Failed to save {}: {e}", path.display());
+    }
+  }
+
+  #[cfg(not(feature = "desktop"))]
+  {
+    let (Ok(key), Ok(value)) =
+      (serde_json::to_string(key), serde_json::to_string(value))
+    else {
+      return;
+    };
+    document::eval(&format!("localStorage.setItem({key}, {value});"));
+  }
+}
+
+/// `<config dir>/hot_dog/<key>.json`, following `XDG_CONFIG_HOME` or
+/// `APPDATA` and falling back to `~/.config`.
+#[cfg(feature = "desktop")]
+fn config_path(key: &str) -> Option<std::path::PathBuf> {
+  use std::{env, path::PathBuf};
+  let dir = env::var_os("XDG_CONFIG_HOME")
+    .or_else(|| env::var_os("APPDATA"))
+    .map(PathBuf::from)
+    .or_else(|| Some(PathBuf::from(env::var_os("HOME")?).join(".config")))?;
+  Some(dir.join(env!("CARGO_PKG_NAME")).join(format!("{key}.json")))
+}
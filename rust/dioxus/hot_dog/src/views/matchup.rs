@@ -0,0 +1,176 @@
+use super::{offline, prelude::*};
+use crate::{
+  api::record_matchup,
+  data::{
+    Dog,
+    matchup::{Pair, Table, next_pair}
+  },
+  provider::Provider,
+  utils::rng::Rng
+};
+use std::collections::HashSet;
+
+#[component]
+pub fn Matchup() -> Element {
+  let provider =
+    use_memo(move || Provider::from_name(&PREFERENCES.read().provider));
+  let mut rng = use_signal(Rng::from_time);
+  let mut seen = use_signal(HashSet::<Pair>::new);
+  let mut table = use_signal(Table::default);
+
+  //{ Draw a fresh pair of breeds and fetch one dog of each }
+  let mut pair = use_resource(move || async move {
+    let breeds = offline::breeds().await?.keys();
+    let next = next_pair(&breeds, &seen.peek(), &mut rng.write());
+    let Some((first, second)) = next else {
+      return Ok(None);
+    };
+    seen
+      .write()
+      .insert(Pair::new(first.clone(), second.clone()));
+
+    offline::pair(
+      &provider(),
+      (&first.0, first.1.as_deref()),
+      (&second.0, second.1.as_deref())
+    )
+    .await
+    .map(Some)
+  });
+
+  let current = use_memo(move || match &*pair.read() {
+    Some(Ok(Some(dogs))) => Some(dogs.clone()),
+    _ => None
+  });
+
+  let mut pick = move |first_wins: bool| {
+    let Some((first, second)) = current() else {
+      return;
+    };
+    let (winner, loser) = if first_wins {
+      (first, second)
+    } else {
+      (second, first)
+    };
+    table
+      .write()
+      .record(&winner.display_name, &loser.display_name);
+    spawn(async move {
+      if let Err(e) = record_matchup(winner, loser).await {
+        error!("Failed to record matchup: {e}");
+      }
+      pair.restart();
+    });
+  };
+
+  let standings = use_memo(move || table.read().standings());
+  let json_href = use_memo(move || {
+    let json = table.read().to_json().unwrap_or_default();
+    format!(
+      "data:application/json;charset=utf-8,{}",
+      percent_encode(&json)
+    )
+  });
+  let csv_href = use_memo(move || {
+    format!(
+      "data:text/csv;charset=utf-8,{}",
+      percent_encode(&table.read().to_csv())
+    )
+  });
+
+  rsx! {
+    document::Stylesheet { href: DOG_CSS }
+    document::Stylesheet { href: MATCHUP_CSS }
+    section {
+      id: "matchup",
+      tabindex: "0",
+      onkeydown: move |evt: KeyboardEvent| match evt.key() {
+          Key::ArrowLeft => pick(true),
+          Key::ArrowRight => pick(false),
+          _ => {}
+      },
+      h2 { "Which dog wins?" }
+      match &*pair.read() {
+          Some(Err(e)) => rsx! {
+            p { class: "error", "Error: {e}" }
+          },
+          None => rsx! {
+            p { "Loading..." }
+          },
+          Some(Ok(None)) => rsx! {
+            p { "You've seen every pairing this session!" }
+          },
+          Some(Ok(Some((first, second)))) => rsx! {
+            div { class: "matchup-pair",
+              MatchupCard { dog: first.clone(), onpick: move |_| pick(true) }
+              span { class: "versus", "vs" }
+              MatchupCard { dog: second.clone(), onpick: move |_| pick(false) }
+            }
+          },
+      }
+      div { id: "buttons",
+        button { id: "skip", onclick: move |_| pair.restart(), "skip pair" }
+      }
+
+      if !standings.read().is_empty() {
+        h3 { "Round robin" }
+        table { class: "standings",
+          thead {
+            tr {
+              th { "Breed" }
+              th { "Played" }
+              th { "Won" }
+              th { "Lost" }
+            }
+          }
+          tbody {
+            for standing in standings() {
+              tr { key: "{standing.breed}",
+                td { "{standing.breed}" }
+                td { "{standing.played}" }
+                td { "{standing.wins}" }
+                td { "{standing.losses}" }
+              }
+            }
+          }
+        }
+        div { class: "exports",
+          a { href: "{json_href}", download: "matchups.json", "Export JSON" }
+          a { href: "{csv_href}", download: "matchups.csv", "Export CSV" }
+        }
+      }
+    }
+  }
+}
+
+#[component]
+fn MatchupCard(dog: Dog, onpick: EventHandler<MouseEvent>) -> Element {
+  rsx! {
+    div { class: "matchup-card",
+      picture {
+        source {
+          r#type: proxy::Format::Avif.mime(),
+          "srcset": proxy::srcset(&dog.photo_url, proxy::Format::Avif),
+          "sizes": "(max-width: 900px) 100vw, 420px"
+        }
+        img {
+          class: "dog-image-container",
+          src: photo_src(&dog.photo_url),
+          srcset: proxy::srcset(&dog.photo_url, proxy::Format::Jpeg),
+          "sizes": "(max-width: 900px) 100vw, 420px",
+          alt: dog.alt_text(),
+          onclick: move |evt| onpick.call(evt)
+        }
+      }
+      div { class: "dog-info",
+        h2 { "{dog.display_name}" }
+      }
+      button {
+        r#type: "button",
+        aria_label: "Pick the {dog.display_name}",
+        onclick: move |evt| onpick.call(evt),
+        "pick"
+      }
+    }
+  }
+}
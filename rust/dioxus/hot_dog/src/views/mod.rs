@@ -1,17 +1,64 @@
 //|-> Modules
+#[cfg(test)]
+mod a11y;
+mod about;
+mod account;
+mod breeds;
 mod carousel;
+mod day;
 mod default;
+mod dog;
+mod favorites;
+mod filter;
 mod footer;
+mod gallery;
 mod header;
-mod main;
+mod home;
+mod layout;
+mod local;
+mod matchup;
+mod offline;
+mod permalink;
+mod prefetch;
+mod prefs;
+mod quiz;
+mod rate;
+mod route;
+mod settings;
+mod sync;
+mod transfer;
 
 //|-> Internal Exports
 mod prelude {
   pub use super::{
-    carousel::ImageCarousel, footer::Footer, header::Header, main::Main
+    about::About,
+    account::Account,
+    breeds::Breeds,
+    carousel::ImageCarousel,
+    day::{Day, Today},
+    dog::Dog,
+    favorites::Favorites,
+    filter::BreedPicker,
+    footer::Footer,
+    gallery::Gallery,
+    header::Header,
+    home::Home,
+    layout::{Layout, NotFound},
+    matchup::Matchup,
+    offline::{photo_src, use_offline},
+    permalink::{DogPage, permalink},
+    prefetch::{Prefetch, use_prefetch},
+    prefs::{PREFERENCES, SavedTheme, use_preferences},
+    quiz::Quiz,
+    rate::{Leaderboard, Rate},
+    route::Route,
+    settings::Settings,
+    sync::{use_live_sync, use_on_change},
+    transfer::ImportPanel
   };
   pub use crate::prelude::*;
 }
 
 //|-> External Exports
 pub use default::launch;
+pub use route::Route;
@@ -0,0 +1,115 @@
+use super::prelude::*;
+use crate::{
+  api::{bundled_breed_photos, bundled_breeds, bundled_dogs, offline_mode},
+  data::{BreedScore, Dog, bundle},
+  provider::{BreedsApiResponse, DogCeo, Provider}
+};
+use std::{path::PathBuf, sync::OnceLock};
+
+/// Whether the server runs from an offline bundle, `false` until it has
+/// answered.
+pub static OFFLINE: GlobalSignal<bool> = Signal::global(|| false);
+
+/// Asks the server once whether it runs from an offline bundle.
+pub fn use_offline() {
+  use_future(|| async {
+    is_offline().await;
+  });
+}
+
+/// Whether the server runs from an offline bundle, asked once and then
+/// remembered. A failed answer counts as online and is asked again.
+pub async fn is_offline() -> bool {
+  static ANSWER: OnceLock<bool> = OnceLock::new();
+  if let Some(&offline) = ANSWER.get() {
+    return offline;
+  }
+  match offline_mode().await {
+    Ok(offline) => {
+      *OFFLINE.write() = offline;
+      *ANSWER.get_or_init(|| offline)
+    }
+    Err(e) => {
+      warn!("Failed to ask for offline mode: {e}");
+      false
+    }
+  }
+}
+
+/// Where an `img` loads `photo_url` from: the server's bundled copy while
+/// offline, the photo itself otherwise.
+pub fn photo_src(photo_url: &str) -> String {
+  if OFFLINE() {
+    bundle::photo_path(photo_url)
+  } else {
+    photo_url.to_string()
+  }
+}
+
+/// `count` dogs from `provider`, or from the bundle while offline.
+pub async fn dogs(provider: &Provider, count: usize) -> Result<Vec<Dog>> {
+  if is_offline().await {
+    return bundled_dogs(count).await.map_err(server_error);
+  }
+  provider.dogs(count).await
+}
+
+/// A dog from `provider` leaning toward `scores`, or a bundled one while
+/// offline.
+pub async fn preferred_dog(
+  provider: &Provider,
+  scores: &[BreedScore]
+) -> Result<Dog> {
+  if is_offline().await {
+    return one(bundled_dogs(1).await.map_err(server_error)?);
+  }
+  provider.preferred_dog(scores).await
+}
+
+/// One dog of each breed from `provider`, or two bundled dogs of whatever
+/// breed while offline.
+pub async fn pair(
+  provider: &Provider,
+  first: (&str, Option<&str>),
+  second: (&str, Option<&str>)
+) -> Result<(Dog, Dog)> {
+  if !is_offline().await {
+    return provider.pair(first, second).await;
+  }
+  let mut dogs = bundled_dogs(2).await.map_err(server_error)?.into_iter();
+  match (dogs.next(), dogs.next()) {
+    (Some(first), Some(second)) => Ok((first, second)),
+    _ => Err(Error::EmptyResponse)
+  }
+}
+
+/// The dog.ceo breed list, or the bundle's snapshot of it while offline.
+pub async fn breeds() -> Result<BreedsApiResponse> {
+  if is_offline().await {
+    return bundled_breeds().await.map_err(server_error);
+  }
+  DogCeo::get_breeds(None::<PathBuf>, None).await
+}
+
+/// Photos of one breed from dog.ceo, or the bundled ones while offline.
+pub async fn breed_photos(
+  main: &str,
+  sub: Option<&str>
+) -> Result<Vec<String>> {
+  if is_offline().await {
+    return bundled_breed_photos(main.to_string(), sub.map(str::to_string))
+      .await
+      .map_err(server_error);
+  }
+  DogCeo::breed_photos(main, sub, None).await
+}
+
+// -- Private Helper --
+
+fn one(dogs: Vec<Dog>) -> Result<Dog> {
+  dogs.into_iter().next().ok_or(Error::EmptyResponse)
+}
+
+fn server_error(e: ServerFnError) -> Error {
+  Error::Provider(e.to_string())
+}
@@ -0,0 +1,66 @@
+use super::prelude::*;
+use crate::{data::DogId, provider::Provider};
+
+/// One dog at a stable address, rendered on the server first so link
+/// previews pick up its OpenGraph tags.
+#[component]
+pub fn DogPage(provider: String, id: String) -> Element {
+  let dog = use_server_future(use_reactive!(|provider, id| async move {
+    Provider::find_dog(&DogId { provider, id })
+      .await
+      .map_err(|e| e.to_string())
+  }))?;
+
+  rsx! {
+    document::Stylesheet { href: DOG_CSS }
+    div { id: "dogview",
+      match &*dog.read() {
+          Some(Err(e)) => rsx! {
+            document::Title { "Dog not found | {TITLE}" }
+            p { class: "error", "Error: {e}" }
+          },
+          None => rsx! {
+            p { "Loading..." }
+          },
+          Some(Ok(dog)) => rsx! {
+            document::Title { "{dog.display_name} | {TITLE}" }
+            document::Meta { property: "og:type", content: "article" }
+            document::Meta { property: "og:title", content: "{dog.display_name}" }
+            document::Meta {
+              property: "og:description",
+              content: "A {dog.display_name} on {TITLE}"
+            }
+            document::Meta { property: "og:image", content: "{dog.photo_url}" }
+            document::Meta { name: "twitter:card", content: "summary_large_image" }
+            picture {
+              source {
+                r#type: proxy::Format::Avif.mime(),
+                "srcset": proxy::srcset(&dog.photo_url, proxy::Format::Avif),
+                "sizes": "(max-width: 500px) 100vw, 500px"
+              }
+              img {
+                class: "dog-image-container",
+                src: photo_src(&dog.photo_url),
+                srcset: proxy::srcset(&dog.photo_url, proxy::Format::Jpeg),
+                "sizes": "(max-width: 500px) 100vw, 500px",
+                alt: dog.alt_text(),
+              }
+            }
+            div { class: "dog-info",
+              h2 { "{dog.display_name}" }
+              p {
+                "Source: "
+                a { href: "{dog.reference_url}", "{dog.photo_url}" }
+              }
+            }
+          },
+      }
+    }
+  }
+}
+
+/// The permalink route for `dog`.
+pub fn permalink(dog: &crate::data::Dog) -> Route {
+  let DogId { provider, id } = dog.id();
+  Route::DogPage { provider, id }
+}
@@ -0,0 +1,159 @@
+use super::prelude::*;
+use crate::utils::prefetch::Queue;
+use futures::future::LocalBoxFuture;
+//~@ Fetch errors arrive as any `Display`, not the crate's `Error`
+use std::{fmt::Display, future::Future, rc::Rc, result::Result};
+
+type Fetch<K, T> =
+  Rc<dyn Fn(K, Vec<T>) -> LocalBoxFuture<'static, Result<T, String>>>;
+
+/// The item on screen plus a queue of the next ones, fetched in the
+/// background so moving on doesn't wait for the network.
+pub struct Prefetch<K: 'static, T: 'static> {
+  key: ReadOnlySignal<K>,
+  fetch: CopyValue<Fetch<K, T>>,
+  queue: Signal<Queue<T>>,
+  current: Signal<Option<Result<T, String>>>,
+  tasks: Signal<Vec<(u64, Task)>>,
+  spawned: CopyValue<u64>
+}
+
+impl<K, T> Clone for Prefetch<K, T> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<K, T> Copy for Prefetch<K, T> {}
+
+/// Keeps `depth` items from `fetch` ready behind the one on screen.
+///
+/// Items are fetched one after another, and `fetch` is handed the ones on
+/// screen or queued so it can avoid repeating them. Changing `key`, such as
+/// the provider or breed filter the items come from, cancels the fetch in
+/// flight and starts over.
+pub fn use_prefetch<K, T, E, F, Fut>(
+  key: ReadOnlySignal<K>,
+  depth: usize,
+  fetch: F
+) -> Prefetch<K, T>
+where
+  K: Clone + 'static,
+  T: Clone + 'static,
+  E: Display,
+  F: Fn(K, Vec<T>) -> Fut + 'static,
+  Fut: Future<Output = Result<T, E>> + 'static
+{
+  let fetch = use_hook(|| {
+    let fetch: Fetch<K, T> = Rc::new(move |key, taken| {
+      let pending = fetch(key, taken);
+      Box::pin(async move { pending.await.map_err(|e| e.to_string()) })
+    });
+    CopyValue::new(fetch)
+  });
+  let prefetch = Prefetch {
+    key,
+    fetch,
+    queue: use_signal(|| Queue::new(depth.max(1))),
+    current: use_signal(|| None),
+    tasks: use_signal(Vec::new),
+    spawned: use_hook(|| CopyValue::new(0))
+  };
+
+  use_effect(move || {
+    key.read();
+    let mut prefetch = prefetch;
+    prefetch.reset();
+  });
+  prefetch
+}
+
+impl<K: Clone, T: Clone> Prefetch<K, T> {
+  /// The item on screen, `None` while waiting for one to arrive.
+  pub fn current(&self) -> Option<Result<T, String>> {
+    self.current.read().clone()
+  }
+
+  /// The ready items after the current one, next first.
+  pub fn queued(&self) -> Vec<T> {
+    self.queue.read().iter().cloned().collect()
+  }
+
+  /// Shows the next ready item, or waits for one, and tops the queue up.
+  pub fn advance(&mut self) {
+    let next = self.queue.write().pop();
+    self.current.set(next.map(Ok));
+    self.refill();
+  }
+
+  fn reset(&mut self) {
+    for (_, task) in self.tasks.write().drain(..) {
+      task.cancel();
+    }
+    self.queue.write().reset();
+    self.current.set(None);
+    self.refill();
+  }
+
+  /// Starts the next fill unless one is on its way or the queue is full.
+  fn refill(&mut self) {
+    {
+      let queue = self.queue.peek();
+      if queue.is_filling() || queue.wanted() == 0 {
+        return;
+      }
+    }
+    let generation = self.queue.write().start();
+    let fetch = self.fetch.read().clone();
+    let pending = fetch(self.key.peek().clone(), self.taken());
+    let id = {
+      let mut spawned = self.spawned.write();
+      *spawned += 1;
+      *spawned
+    };
+    let mut this = *self;
+    let task = spawn(async move {
+      let result = pending.await;
+      this.tasks.write().retain(|(task, _)| *task != id);
+      this.land(generation, result);
+    });
+    self.tasks.write().push((id, task));
+  }
+
+  /// The item on screen and the queued ones.
+  fn taken(&self) -> Vec<T> {
+    let current = self.current.peek();
+    let current = current.as_ref().and_then(|item| item.as_ref().ok());
+    current
+      .into_iter()
+      .chain(self.queue.peek().iter())
+      .cloned()
+      .collect()
+  }
+
+  fn land(&mut self, generation: u64, result: Result<T, String>) {
+    let waiting = self.current.peek().is_none();
+    match result {
+      Ok(item) => {
+        if !self.queue.write().finish(generation, Some(item)) {
+          return;
+        }
+        if waiting {
+          self.advance();
+        } else {
+          self.refill();
+        }
+      }
+      Err(e) => {
+        if !self.queue.write().finish(generation, None) {
+          return;
+        }
+        warn!("Failed to prefetch: {e}");
+        //~@ Only surface the error once nothing else can fill the screen
+        if waiting && self.queue.peek().is_idle() {
+          self.current.set(Some(Err(e)));
+        }
+      }
+    }
+  }
+}
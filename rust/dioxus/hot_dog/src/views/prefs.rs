@@ -0,0 +1,94 @@
+use super::{local, prelude::*};
+use crate::data::prefs::Preferences;
+
+//~@ Storage key the preferences are saved under
+const SAVED: &str = "preferences";
+
+/// This device's preferences, defaults until the saved ones are loaded.
+pub static PREFERENCES: GlobalSignal<Preferences> =
+  Signal::global(Preferences::default);
+
+/// Whether [`PREFERENCES`] holds the saved preferences yet.
+static LOADED: GlobalSignal<bool> = Signal::global(|| false);
+
+/// Loads the saved preferences into [`PREFERENCES`] and keeps the page's
+/// theme in step with them.
+pub fn use_preferences() {
+  use_effect(|| {
+    spawn(load_once());
+  });
+  use_effect(|| {
+    //~@ The defaults would undo the theme [`SavedTheme`] already set
+    if !LOADED() {
+      return;
+    }
+    let theme = PREFERENCES.read().theme;
+    document::eval(&format!(
+      "document.documentElement.dataset.theme = '{}';",
+      theme.as_str()
+    ));
+  });
+}
+
+/// The saved preferences, read straight from storage.
+pub async fn load() -> Preferences {
+  local::load(SAVED)
+    .await
+    .map(|json| Preferences::from_json(&json))
+    .unwrap_or_default()
+}
+
+/// Changes [`PREFERENCES`] with `f` and saves the result.
+///
+/// Waits for the saved preferences first, so a change made while they load
+/// neither overwrites them nor gets overwritten by them.
+pub fn update(f: impl FnOnce(&mut Preferences) + 'static) {
+  if *LOADED.peek() {
+    save(f);
+  } else {
+    spawn(async move {
+      load_once().await;
+      save(f);
+    });
+  }
+}
+
+/// Sets the saved theme from the page head, before the app first paints.
+///
+/// Only the browser keeps preferences where a script can read them; the
+/// desktop app applies its theme once [`use_preferences`] has loaded it.
+#[component]
+pub fn SavedTheme() -> Element {
+  let script = format!(
+    "try {{
+      const saved = JSON.parse(localStorage.getItem('{SAVED}'));
+      if (saved && saved.theme) {{
+        document.documentElement.dataset.theme = saved.theme;
+      }}
+    }} catch (e) {{}}"
+  );
+  rsx! {
+    document::Script { "{script}" }
+  }
+}
+
+// -- Private Helper --
+
+/// Fills [`PREFERENCES`] from storage unless that already happened. The
+/// first load to finish wins.
+async fn load_once() {
+  if *LOADED.peek() {
+    return;
+  }
+  let saved = load().await;
+  if !*LOADED.peek() {
+    *PREFERENCES.write() = saved;
+    *LOADED.write() = true;
+  }
+}
+
+fn save(f: impl FnOnce(&mut Preferences)) {
+  let mut preferences = PREFERENCES.write();
+  f(&mut preferences);
+  local::store(SAVED, &preferences.to_json());
+}
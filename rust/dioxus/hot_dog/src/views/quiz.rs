@@ -0,0 +1,215 @@
+use super::{offline, prelude::*};
+use crate::{
+  api::{high_scores, submit_score},
+  data::{
+    matchup::BreedKey,
+    quiz::{Difficulty, HighScore, Question, ROUNDS, Session, display_name}
+  },
+  provider::{DogCeo, Provider},
+  utils::rng::Rng
+};
+
+#[component]
+pub fn Quiz() -> Element {
+  let mut difficulty = use_signal(Difficulty::default);
+  let mut session = use_signal(Session::default);
+  let mut rng = use_signal(Rng::from_time);
+  let mut chosen = use_signal(|| None::<BreedKey>);
+  //~@ Set from "See results", so the last answer's feedback stays up until then
+  let mut finished = use_signal(|| false);
+  let mut submitted = use_signal(|| false);
+  let mut name = use_signal(String::new);
+
+  //{ The photo's URL is the ground truth, the catalog supplies distractors }
+  let mut question = use_resource(move || async move {
+    let difficulty = difficulty();
+    let photo_url = offline::dogs(&Provider::dog_ceo(), 1)
+      .await?
+      .pop()
+      .ok_or(Error::EmptyResponse)?
+      .photo_url;
+    let answer = DogCeo::extract_breed_from_url(&photo_url)?;
+    let catalog = offline::breeds().await?.keys();
+    Ok::<_, Error>(Question::new(
+      photo_url,
+      answer,
+      &catalog,
+      difficulty,
+      &mut rng.write()
+    ))
+  });
+  let mut scores = use_resource(move || high_scores(difficulty()));
+
+  let mut restart = move |level: Difficulty| {
+    session.set(Session::new(level));
+    chosen.set(None);
+    finished.set(false);
+    submitted.set(false);
+    if difficulty() == level {
+      question.restart();
+    } else {
+      difficulty.set(level);
+    }
+  };
+
+  let submit = move |_| async move {
+    let current = session();
+    let score = HighScore {
+      name: name(),
+      difficulty: current.difficulty,
+      score: current.score,
+      correct: current.correct,
+      best_streak: current.best_streak,
+      played_at: 0
+    };
+    match submit_score(score).await {
+      Ok(_) => {
+        submitted.set(true);
+        scores.restart();
+      }
+      Err(e) => error!("Failed to submit score: {e}")
+    }
+  };
+
+  rsx! {
+    document::Stylesheet { href: DOG_CSS }
+    document::Stylesheet { href: QUIZ_CSS }
+    section { id: "quiz",
+      h2 { "Guess the breed" }
+      div { class: "quiz-levels",
+        for level in Difficulty::ALL {
+          button {
+            key: "{level.as_str()}",
+            class: if difficulty() == level { "active" } else { "" },
+            onclick: move |_| restart(level),
+            "{level.label()}"
+          }
+        }
+      }
+      p { class: "quiz-status",
+        "Question {session.read().answered.min(ROUNDS - 1) + 1} of {ROUNDS} · "
+        "Score {session.read().score} · Streak {session.read().streak}"
+      }
+
+      if finished() {
+        div { class: "quiz-summary",
+          h3 { "Final score: {session.read().score}" }
+          p {
+            "{session.read().correct} of {ROUNDS} right, best streak {session.read().best_streak}."
+          }
+          if !submitted() {
+            div { class: "quiz-submit",
+              input {
+                placeholder: "Your name",
+                maxlength: "32",
+                value: "{name}",
+                oninput: move |evt| name.set(evt.value())
+              }
+              button { onclick: submit, "Save score" }
+            }
+          }
+          button { onclick: move |_| restart(difficulty()), "Play again" }
+        }
+      } else {
+        match &*question.read() {
+            Some(Err(e)) => rsx! {
+              p { class: "error", "Error: {e}" }
+            },
+            None => rsx! {
+              p { "Loading..." }
+            },
+            Some(Ok(current)) => {
+                let answer = current.answer.clone();
+                rsx! {
+                  picture {
+                    source {
+                      r#type: proxy::Format::Avif.mime(),
+                      "srcset": proxy::srcset(&current.photo_url, proxy::Format::Avif),
+                      "sizes": "(max-width: 600px) 100vw, 600px"
+                    }
+                    img {
+                      class: "dog-image-container",
+                      src: photo_src(&current.photo_url),
+                      srcset: proxy::srcset(&current.photo_url, proxy::Format::Jpeg),
+                      "sizes": "(max-width: 600px) 100vw, 600px",
+                      alt: "Mystery dog",
+                    }
+                  }
+                  div { class: "quiz-choices",
+                    for choice in current.choices.clone() {
+                      button {
+                        key: "{choice.0}-{choice.1:?}",
+                        class: choice_class(&choice, &answer, chosen.read().as_ref()),
+                        disabled: chosen.read().is_some(),
+                        onclick: {
+                            let picked = choice.clone();
+                            let correct = picked == answer;
+                            move |_| {
+                                chosen.set(Some(picked.clone()));
+                                session.write().answer(correct);
+                            }
+                        },
+                        {display_name(&choice)}
+                      }
+                    }
+                  }
+                  if chosen.read().is_some() {
+                    p { class: "quiz-status",
+                      if chosen.read().as_ref() == Some(&answer) {
+                        "Correct!"
+                      } else {
+                        "It was a {display_name(&answer)}."
+                      }
+                    }
+                    button {
+                      onclick: move |_| {
+                          chosen.set(None);
+                          if session.read().is_over() {
+                              finished.set(true);
+                          } else {
+                              question.restart();
+                          }
+                      },
+                      if session.read().is_over() { "See results" } else { "Next" }
+                    }
+                  }
+                }
+            }
+        }
+      }
+
+      h3 { "High scores ({difficulty().label()})" }
+      match &*scores.read() {
+          Some(Ok(scores)) if !scores.is_empty() => rsx! {
+            ol { class: "quiz-scores",
+              for (rank, score) in scores.iter().enumerate() {
+                li { key: "{rank}",
+                  span { "{score.name}" }
+                  span { "{score.score}" }
+                }
+              }
+            }
+          },
+          Some(Err(e)) => rsx! {
+            p { class: "error", "Error: {e}" }
+          },
+          _ => rsx! {
+            p { "No scores yet." }
+          },
+      }
+    }
+  }
+}
+
+fn choice_class(
+  choice: &BreedKey,
+  answer: &BreedKey,
+  chosen: Option<&BreedKey>
+) -> &'static str {
+  match chosen {
+    None => "",
+    Some(_) if choice == answer => "correct",
+    Some(picked) if picked == choice => "wrong",
+    Some(_) => ""
+  }
+}
@@ -0,0 +1,204 @@
+use super::{offline, prelude::*};
+use crate::{
+  api::{leaderboard, rate_dog, record_matchup},
+  data::{BreedScore, Dog, Vote},
+  provider::Provider
+};
+
+//~@ Horizontal distance (px) a touch must travel to count as a swipe
+const SWIPE_THRESHOLD: f64 = 50.0;
+
+#[component]
+pub fn Rate() -> Element {
+  let provider =
+    use_memo(move || Provider::from_name(&PREFERENCES.read().provider));
+  let mut last_skipped = use_signal(|| None::<Dog>);
+  let mut last_score = use_signal(|| None::<BreedScore>);
+  let mut touch_start = use_signal(|| None::<f64>);
+
+  //{ Lean each pick toward the breeds liked so far }
+  let mut dog = use_resource(move || async move {
+    let scores = leaderboard().await.unwrap_or_default();
+    offline::preferred_dog(&provider(), &scores).await
+  });
+
+  let current = use_memo(move || {
+    dog
+      .read()
+      .as_ref()
+      .and_then(|result| result.as_ref().ok())
+      .cloned()
+  });
+
+  let vote = move |choice: Vote| {
+    let Some(current) = current() else {
+      return;
+    };
+    spawn(async move {
+      match cast_vote(current, choice, last_skipped).await {
+        Ok(score) => last_score.set(Some(score)),
+        Err(e) => error!("Failed to record vote: {e}")
+      }
+      dog.restart();
+    });
+  };
+
+  rsx! {
+    document::Stylesheet { href: DOG_CSS }
+    document::Stylesheet { href: RATE_CSS }
+    div {
+      id: "dogview",
+      class: "rate",
+      tabindex: "0",
+      onkeydown: move |evt: KeyboardEvent| match evt.key() {
+          Key::ArrowLeft => vote(Vote::Skip),
+          Key::ArrowRight => vote(Vote::Like),
+          _ => {}
+      },
+      ontouchstart: move |evt: TouchEvent| {
+          let x = evt.touches().first().map(|t| t.client_coordinates().x);
+          touch_start.set(x);
+      },
+      ontouchend: move |evt: TouchEvent| {
+          let end = evt.touches_changed().first().map(|t| t.client_coordinates().x);
+          if let (Some(start), Some(end)) = (touch_start.take(), end) {
+              let delta = end - start;
+              if delta > SWIPE_THRESHOLD {
+                  vote(Vote::Like);
+              } else if delta < -SWIPE_THRESHOLD {
+                  vote(Vote::Skip);
+              }
+          }
+      },
+      h2 { "Hot or Not" }
+      match &*dog.read() {
+          Some(Err(e)) => rsx! {
+            p { class: "error", "Error: {e}" }
+          },
+          None => rsx! {
+            p { "Loading..." }
+          },
+          Some(Ok(dog)) => rsx! {
+            picture {
+              source {
+                r#type: proxy::Format::Avif.mime(),
+                "srcset": proxy::srcset(&dog.photo_url, proxy::Format::Avif),
+                "sizes": "(max-width: 500px) 100vw, 500px"
+              }
+              img {
+                class: "dog-image-container",
+                src: photo_src(&dog.photo_url),
+                srcset: proxy::srcset(&dog.photo_url, proxy::Format::Jpeg),
+                "sizes": "(max-width: 500px) 100vw, 500px",
+                alt: dog.alt_text(),
+              }
+            }
+            div { class: "dog-info",
+              h2 { "{dog.display_name}" }
+            }
+          },
+      }
+      div { id: "buttons",
+        button {
+          id: "skip",
+          disabled: current().is_none(),
+          onclick: move |_| vote(Vote::Skip),
+          "not"
+        }
+        button {
+          id: "save",
+          disabled: current().is_none(),
+          onclick: move |_| vote(Vote::Like),
+          "hot!"
+        }
+      }
+      p { class: "rate-hint", "Swipe or use ← → to rate." }
+      if let Some(score) = last_score() {
+        p { class: "rate-hint",
+          "{score.display_name}: {like_percent(&score)} liked over {score.votes()} votes"
+        }
+      }
+    }
+  }
+}
+
+#[component]
+pub fn Leaderboard() -> Element {
+  let mut scores = use_resource(leaderboard);
+  use_on_change(move |change| {
+    if change.touches_ratings() {
+      scores.restart();
+    }
+  });
+
+  rsx! {
+    document::Stylesheet { href: RATE_CSS }
+    section { id: "leaderboard",
+      h2 { "Leaderboard" }
+      match &*scores.read() {
+          Some(Err(e)) => rsx! {
+            p { class: "error", "Error: {e}" }
+          },
+          None => rsx! {
+            p { "Loading..." }
+          },
+          Some(Ok(scores)) if scores.is_empty() => rsx! {
+            p {
+              "No ratings yet. "
+              Link { to: Route::Rate {}, "Start rating" }
+            }
+          },
+          Some(Ok(scores)) => rsx! {
+            table {
+              thead {
+                tr {
+                  th { "#" }
+                  th { "Breed" }
+                  th { "Score" }
+                  th { "Liked" }
+                  th { "Votes" }
+                  th { "Matchups" }
+                }
+              }
+              tbody {
+                for (rank, score) in scores.iter().enumerate() {
+                  tr { key: "{score.breed}-{score.sub_breed:?}",
+                    td { "{rank + 1}" }
+                    td { "{score.display_name}" }
+                    td { "{score.elo:.0}" }
+                    td { {like_percent(score)} }
+                    td { "{score.votes()}" }
+                    td { "{score.matches}" }
+                  }
+                }
+              }
+            }
+          },
+      }
+    }
+  }
+}
+
+/// Records `vote` on `dog`, turning a like that follows a skip of another
+/// breed into a head-to-head win for the liked breed.
+async fn cast_vote(
+  dog: Dog,
+  vote: Vote,
+  mut last_skipped: Signal<Option<Dog>>
+) -> std::result::Result<BreedScore, ServerFnError> {
+  let score = rate_dog(dog.clone(), vote).await?;
+  match vote {
+    Vote::Skip => last_skipped.set(Some(dog)),
+    Vote::Like =>
+      if let Some(loser) = last_skipped.take() {
+        record_matchup(dog, loser).await?;
+      },
+  }
+  Ok(score)
+}
+
+fn like_percent(score: &BreedScore) -> String {
+  score
+    .like_ratio()
+    .map_or_else(|| "–".to_string(), |ratio| format!("{:.0}%", ratio * 100.0))
+}
@@ -0,0 +1,38 @@
+use super::prelude::*;
+use crate::data::filter::BreedFilter;
+
+#[derive(Debug, Clone, PartialEq, Routable)]
+#[rustfmt::skip]
+pub enum Route {
+  #[layout(Layout)]
+    #[route("/?:..filter")]
+    Home { filter: BreedFilter },
+    #[route("/gallery")]
+    Gallery {},
+    #[route("/favorites")]
+    Favorites {},
+    #[route("/breeds")]
+    Breeds {},
+    #[route("/rate")]
+    Rate {},
+    #[route("/matchup")]
+    Matchup {},
+    #[route("/quiz")]
+    Quiz {},
+    #[route("/dog/:provider/:id")]
+    DogPage { provider: String, id: String },
+    #[route("/day")]
+    Today {},
+    #[route("/day/:date")]
+    Day { date: String },
+    #[route("/leaderboard")]
+    Leaderboard {},
+    #[route("/account")]
+    Account {},
+    #[route("/settings")]
+    Settings {},
+    #[route("/about")]
+    About {},
+    #[route("/:..segments")]
+    NotFound { segments: Vec<String> }
+}
@@ -0,0 +1,96 @@
+use super::{prefs, prelude::*};
+use crate::data::prefs::{Preferences, Theme};
+
+//~@ Providers offered by name; anything else is a custom base URL
+const PROVIDERS: [(&str, &str); 2] =
+  [("dog_ceo", "dog.ceo"), ("random", "Random")];
+
+#[component]
+pub fn Settings() -> Element {
+  let preferences = PREFERENCES.read().clone();
+  let named =
+    |provider: &str| PROVIDERS.iter().any(|(name, _)| *name == provider);
+  let mut picking_url = use_signal(|| false);
+  let custom = picking_url() || !named(&preferences.provider);
+
+  rsx! {
+    document::Stylesheet { href: DOG_CSS }
+    section { id: "settings",
+      h2 { "Settings" }
+      p { "Saved on this device only." }
+      form { onsubmit: move |evt| evt.prevent_default(),
+        label {
+          "Theme"
+          select {
+            value: preferences.theme.as_str(),
+            onchange: move |evt: FormEvent| {
+                if let Some(theme) = Theme::parse(&evt.value()) {
+                    prefs::update(move |prefs| prefs.theme = theme);
+                }
+            },
+            for theme in Theme::ALL {
+              option { value: theme.as_str(), {theme.label()} }
+            }
+          }
+        }
+        label {
+          "Default provider"
+          select {
+            value: if custom { "custom" } else { preferences.provider.as_str() },
+            onchange: move |evt: FormEvent| {
+                let value = evt.value();
+                picking_url.set(value == "custom");
+                if named(&value) {
+                    prefs::update(move |prefs| prefs.provider = value);
+                }
+            },
+            for (name, label) in PROVIDERS {
+              option { value: name, {label} }
+            }
+            option { value: "custom", "Custom URL" }
+          }
+        }
+        if custom {
+          label {
+            "Custom provider URL"
+            input {
+              r#type: "url",
+              placeholder: "https://example.com/api/random",
+              value: if named(&preferences.provider) { "" } else { preferences.provider.as_str() },
+              onchange: move |evt: FormEvent| {
+                  let url = evt.value();
+                  prefs::update(move |prefs| prefs.provider = url);
+              }
+            }
+          }
+        }
+        fieldset {
+          legend { "Breed filter" }
+          BreedPicker {
+            filter: preferences.filter.clone(),
+            onchange: move |filter| prefs::update(move |prefs| prefs.filter = filter)
+          }
+        }
+        label { class: "checkbox",
+          input {
+            r#type: "checkbox",
+            checked: preferences.autoplay,
+            onchange: move |evt: FormEvent| {
+                let autoplay = evt.checked();
+                prefs::update(move |prefs| prefs.autoplay = autoplay);
+            }
+          }
+          "Autoplay the gallery carousel"
+        }
+        button {
+          r#type: "button",
+          onclick: move |_| {
+              picking_url.set(false);
+              prefs::update(move |prefs| *prefs = Preferences::default());
+          },
+          "Reset to defaults"
+        }
+      }
+    }
+  }
+}
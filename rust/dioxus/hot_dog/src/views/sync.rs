@@ -0,0 +1,96 @@
+use super::prelude::*;
+use crate::data::sync::{Change, Event};
+use std::collections::VecDeque;
+
+/// Changes pushed by the server to this client.
+pub static LIVE: GlobalSignal<Received> = Signal::global(Received::default);
+
+//~@ Changes kept for components that haven't rendered since they arrived
+const KEPT: usize = 64;
+
+/// The latest changes pushed by the server, counted in arrival order so a
+/// component that misses a render still sees every one of them.
+#[derive(Debug, Default)]
+pub struct Received {
+  count: u64,
+  changes: VecDeque<Change>
+}
+
+impl Received {
+  fn push(&mut self, change: Change) {
+    self.count += 1;
+    if self.changes.len() == KEPT {
+      self.changes.pop_front();
+    }
+    self.changes.push_back(change);
+  }
+
+  /// The changes that arrived after the first `seen`, or a
+  /// [`Change::Resync`] when some of them are no longer kept.
+  fn since(&self, seen: u64) -> Vec<Change> {
+    let missed = (self.count - seen) as usize;
+    if missed > self.changes.len() {
+      return vec![Change::Resync];
+    }
+    self
+      .changes
+      .iter()
+      .skip(self.changes.len() - missed)
+      .cloned()
+      .collect()
+  }
+}
+
+//~@ The browser retries on its own with `Last-Event-ID`; once it gives up,
+//~@ reconnect with `?since=` so missed events are replayed
+const LISTEN_JS: &str = r#"
+let since = 0;
+function connect() {
+  const source = new EventSource(
+    "/sync/events" + (since ? "?since=" + since : "")
+  );
+  source.onmessage = (e) => {
+    since = Number(e.lastEventId) || since;
+    dioxus.send(e.data);
+  };
+  source.onerror = () => {
+    if (source.readyState === EventSource.CLOSED) {
+      setTimeout(connect, 5000);
+    }
+  };
+}
+connect();
+await new Promise(() => {});
+"#;
+
+/// Listens for changes made by the user's other tabs and devices, feeding
+/// them into [`LIVE`].
+pub fn use_live_sync() {
+  use_effect(|| {
+    spawn(async {
+      let mut listener = document::eval(LISTEN_JS);
+      while let Ok(data) = listener.recv::<String>().await {
+        match serde_json::from_str::<Event>(&data) {
+          Ok(event) => LIVE.write().push(event.change),
+          Err(e) => warn!("Ignoring a malformed sync event: {e}")
+        }
+      }
+    });
+  });
+}
+
+/// Runs `f` on every change pushed after the calling component mounted.
+pub fn use_on_change(mut f: impl FnMut(&Change) + 'static) {
+  let mut seen = None::<u64>;
+  use_effect(move || {
+    let (count, changes) = {
+      let live = LIVE.read();
+      //~@ The first run only subscribes; earlier changes are on screen
+      (live.count, live.since(seen.unwrap_or(live.count)))
+    };
+    seen = Some(count);
+    for change in &changes {
+      f(change);
+    }
+  });
+}
@@ -0,0 +1,145 @@
+use super::prelude::*;
+use crate::{
+  api::import_favorites,
+  data::transfer::{Format, ImportReport}
+};
+
+/// Imports favorites from an uploaded or pasted file, previewing the
+/// changes before anything is saved.
+#[component]
+pub fn ImportPanel(onimport: EventHandler<()>) -> Element {
+  let mut content = use_signal(String::new);
+  let mut format = use_signal(|| None::<Format>);
+  let mut report = use_signal(|| None::<ImportReport>);
+  let mut message = use_signal(|| None::<String>);
+
+  let read_file = move |evt: FormEvent| async move {
+    let Some(engine) = evt.files() else { return };
+    let Some(name) = engine.files().into_iter().next() else {
+      return;
+    };
+    match engine.read_file_to_string(&name).await {
+      Some(text) => {
+        content.set(text);
+        report.set(None);
+      }
+      None => message.set(Some(format!("Couldn't read {name}")))
+    }
+  };
+
+  //{ Dry runs fill the preview, real runs clear it }
+  let run = move |dry_run: bool| async move {
+    match import_favorites(content(), format(), dry_run).await {
+      Ok(done) if dry_run => {
+        message.set(None);
+        report.set(Some(done));
+      }
+      Ok(done) => {
+        message.set(Some(format!(
+          "Imported {} new and retagged {} saved photos",
+          done.added.len(),
+          done.retagged.len()
+        )));
+        report.set(None);
+        content.set(String::new());
+        onimport.call(());
+      }
+      Err(e) => message.set(Some(e.to_string()))
+    }
+  };
+
+  rsx! {
+    details { class: "import-panel",
+      summary { "Import favorites" }
+      div { class: "favorites-toolbar",
+        input {
+          r#type: "file",
+          accept: ".json,.csv,.txt,.html",
+          onchange: read_file
+        }
+        select {
+          onchange: move |evt| format.set(Format::parse(&evt.value())),
+          option { value: "", "Detect format" }
+          for f in Format::ALL {
+            option { key: "{f.as_str()}", value: f.as_str(), "{f.label()}" }
+          }
+        }
+      }
+      textarea {
+        rows: "6",
+        placeholder: "…or paste an export here",
+        value: "{content}",
+        oninput: move |evt| {
+            content.set(evt.value());
+            report.set(None);
+        }
+      }
+      div { class: "favorites-toolbar",
+        button {
+          disabled: content.read().trim().is_empty(),
+          onclick: move |_| run(true),
+          "Preview"
+        }
+        button {
+          disabled: report.read().as_ref().is_none_or(|r| r.added.is_empty() && r.retagged.is_empty()),
+          onclick: move |_| run(false),
+          "Import"
+        }
+      }
+      if let Some(message) = message() {
+        p { class: "notice", "{message}" }
+      }
+      if let Some(preview) = report() {
+        ImportPreview { report: preview }
+      }
+    }
+  }
+}
+
+#[component]
+fn ImportPreview(report: ImportReport) -> Element {
+  rsx! {
+    div { class: "import-preview",
+      p {
+        "{report.added.len()} to add, {report.retagged.len()} to retag, "
+        "{report.unchanged} already saved, {report.rejected.len()} rejected."
+      }
+      if !report.added.is_empty() {
+        h4 { "New" }
+        ul {
+          for record in report.added.iter() {
+            li { key: "{record.photo_url}",
+              "{record.display_name} "
+              span { class: "muted", "{record.photo_url}" }
+            }
+          }
+        }
+      }
+      if !report.retagged.is_empty() {
+        h4 { "Retagged" }
+        ul {
+          for record in report.retagged.iter() {
+            li { key: "{record.photo_url}",
+              "{record.display_name}:"
+              for tag in record.tags.iter() {
+                " #{tag}"
+              }
+            }
+          }
+        }
+      }
+      if !report.rejected.is_empty() {
+        h4 { "Rejected" }
+        ul {
+          for rejected in report.rejected.iter() {
+            li { key: "{rejected.position}",
+              "#{rejected.position} "
+              code { "{rejected.input}" }
+              " — {rejected.reason}"
+            }
+          }
+        }
+      }
+    }
+  }
+}